@@ -1,6 +1,17 @@
-use std::error::Error;
-use crate::errors::InvalidTextError;
+use std::collections::HashSet;
+use crate::errors::CipherError;
 
+const RUSSIAN: &str = "абвгдежзийклмнопрстуфхцчшщъыьэюя";
+
+/// Number of letters of `Alphabet::new()`
+pub const RUSSIAN_LEN: usize = 32;
+
+/// Ordered set of letters the classical ciphers work with.
+///
+/// `Alphabet::new()` is the 32-letter Russian alphabet without ё,
+/// other presets and custom alphabets are passed to the `*_with_alphabet`
+/// functions of the ciphers.
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Alphabet {
     alphabet: String,
     len: usize,
@@ -32,11 +43,49 @@ impl Alphabet {
     }
 
     pub fn new() -> Alphabet {
-        let alphabet = "абвгдежзийклмнопрстуфхцчшщъыьэюя".to_string();
-        Alphabet {
-            len: alphabet.chars().count(),
-            alphabet,
+        Alphabet::from(RUSSIAN.to_string())
+    }
+
+    pub fn russian() -> Alphabet {
+        Alphabet::new()
+    }
+
+    pub fn russian_with_yo() -> Alphabet {
+        Alphabet::from("абвгдеёжзийклмнопрстуфхцчшщъыьэюя".to_string())
+    }
+
+    pub fn latin() -> Alphabet {
+        Alphabet::from("abcdefghijklmnopqrstuvwxyz".to_string())
+    }
+
+    /// Latin alphabet without `j` used by the 5x5 Polybius square
+    pub fn latin_25() -> Alphabet {
+        Alphabet::from("abcdefghiklmnopqrstuvwxyz".to_string())
+    }
+
+    pub fn digits() -> Alphabet {
+        Alphabet::from("0123456789".to_string())
+    }
+
+    /// Printable ASCII characters from space to `~`
+    pub fn printable_ascii() -> Alphabet {
+        Alphabet::from((' '..='~').collect::<String>())
+    }
+
+    pub fn custom(letters: &str) -> Result<Alphabet, CipherError> {
+        if letters.is_empty() { Err(CipherError::EmptyValue { param: "Алфавит" })?; }
+        let mut seen = HashSet::new();
+        if !letters.chars().all(|letter| seen.insert(letter)) {
+            Err(CipherError::invalid_key("Алфавит", "Алфавит должен состоять из уникальных символов"))?;
         }
+        Ok(Alphabet::from(letters.to_string()))
+    }
+
+    /// Letter used to pad the text up to a full block
+    pub fn filler(&self) -> char {
+        ['ф', 'x'].into_iter()
+            .find(|letter| self.contains(*letter))
+            .unwrap_or_else(|| self.get(self.len - 1))
     }
 
     pub fn alphabet(&self) -> &String {
@@ -47,9 +96,19 @@ impl Alphabet {
         self.len
     }
 
-    pub fn validate(&self, text: &str) -> Result<(), Box<dyn Error>> {
-        for letter in text.chars() {
-            if !self.contains(letter) { Err(InvalidTextError)?; }
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn validate(&self, text: &str) -> Result<(), CipherError> {
+        self.validate_param(text, "Фраза")
+    }
+
+    pub fn validate_param(&self, text: &str, param: &'static str) -> Result<(), CipherError> {
+        for (position, letter) in text.chars().enumerate() {
+            if !self.contains(letter) {
+                Err(CipherError::InvalidCharacter { param, letter, position })?;
+            }
         }
         Ok(())
     }
@@ -72,12 +131,14 @@ impl From<String> for Alphabet {
 
 #[cfg(test)]
 mod alphabet_tests {
-    use crate::alphabet::Alphabet;
+    use crate::alphabet::{Alphabet, RUSSIAN_LEN};
+    use crate::errors::CipherError;
 
     #[test]
     fn test_new() {
         let alphabet = Alphabet::new();
         assert_eq!(alphabet.len, 32);
+        assert_eq!(alphabet.len, RUSSIAN_LEN);
         assert_eq!(alphabet.alphabet, "абвгдежзийклмнопрстуфхцчшщъыьэюя".to_string());
     }
 
@@ -115,7 +176,6 @@ mod alphabet_tests {
     fn test_validate() {
         let alphabet = Alphabet::new();
         alphabet.validate("отодногопорченогояблокавесьвоззагниваеттчк").unwrap();
-        assert!(true);
     }
 
     #[test]
@@ -152,4 +212,44 @@ mod alphabet_tests {
         let alphabet = Alphabet::new();
         alphabet.validate("o").unwrap();
     }
+
+    #[test]
+    fn test_presets() {
+        assert_eq!(Alphabet::russian(), Alphabet::new());
+        assert_eq!(Alphabet::russian_with_yo().len(), 33);
+        assert_eq!(Alphabet::russian_with_yo().index_of('ё'), 6);
+        assert_eq!(Alphabet::latin().len(), 26);
+        assert_eq!(Alphabet::latin_25().len(), 25);
+        assert!(!Alphabet::latin_25().contains('j'));
+        assert_eq!(Alphabet::digits().alphabet(), "0123456789");
+        assert_eq!(Alphabet::printable_ascii().len(), 95);
+        assert_eq!(Alphabet::printable_ascii().get(0), ' ');
+    }
+
+    #[test]
+    fn test_custom() {
+        let alphabet = Alphabet::custom("abc").unwrap();
+        assert_eq!(alphabet.len(), 3);
+        assert_eq!(Alphabet::custom(""), Err(CipherError::EmptyValue { param: "Алфавит" }));
+        assert!(matches!(
+            Alphabet::custom("aba"),
+            Err(CipherError::InvalidKey { param: "Алфавит", .. })
+        ));
+    }
+
+    #[test]
+    fn test_filler() {
+        assert_eq!(Alphabet::new().filler(), 'ф');
+        assert_eq!(Alphabet::latin().filler(), 'x');
+        assert_eq!(Alphabet::digits().filler(), '9');
+    }
+
+    #[test]
+    fn test_validate_error() {
+        let alphabet = Alphabet::new();
+        assert_eq!(
+            alphabet.validate_param("арбуz", "Ключ"),
+            Err(CipherError::InvalidCharacter { param: "Ключ", letter: 'z', position: 4 })
+        );
+    }
 }
@@ -1,5 +1,5 @@
 use std::collections::HashMap;
-use std::error::Error;
+use crate::errors::CipherError;
 use std::fmt::{Display, Formatter};
 use std::ops::{Add, AddAssign};
 use num::Integer;
@@ -7,11 +7,12 @@ use primes::is_prime;
 use rand::Rng;
 use regex::Regex;
 use crate::alphabet::Alphabet;
-use crate::errors::InvalidKeyError;
-use crate::methods::{modd, validate_single};
+use crate::encoding::Encoding;
+use crate::methods::{modd, parse_number, validate_single};
 use super::{phi, pow_mod};
 
-#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+#[derive(Copy, Clone, Debug, Default, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Point {
     pub a: isize,
     pub b: isize,
@@ -19,26 +20,41 @@ pub struct Point {
     pub point: Option<(usize, usize)>
 }
 
-#[derive(Copy, Clone)]
-pub struct CipherValue(Point, usize);
+/// Encrypted letter: the point `kG` and the masked letter number
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CipherValue {
+    pub point: Point,
+    pub value: usize,
+}
+
+/// Generator `g` of order `q`, private key `secret` and public key `open = secret * g`
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EccKeyPair {
+    pub g: Point,
+    pub q: usize,
+    pub secret: usize,
+    pub open: Point,
+}
 
 impl CipherValue {
-    pub fn new(s: &str, a: isize, b: isize, modula: usize) -> Self {
-        let buff: Vec<usize> =  s.to_string()
-            .replace(['(', ')'], "")
+    /// Parses `((x,y),value)`
+    pub fn new(s: &str, a: isize, b: isize, modula: usize) -> Result<Self, CipherError> {
+        let buff = s.replace(['(', ')'], "")
             .split(',')
-            .map(|x| x.parse::<usize>().unwrap())
-            .collect();
+            .map(|x| parse_number::<usize>(x.trim(), "Фраза"))
+            .collect::<Result<Vec<usize>, CipherError>>()?;
         match buff[..] {
-            [x, y, e, ..] => Self(Point::new(a, b, x, y, modula), e),
-            [] | [_] | [_, _] => panic!("Непредвиденное поведение")
+            [x, y, value] => Ok(Self { point: Point::new(a, b, x, y, modula), value }),
+            _ => Err(CipherError::invalid_size("Фраза", "Значение шифртекста должно состоять из трех чисел"))
         }
     }
 }
 
 impl Display for CipherValue {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "({},{})", self.0, self.1)
+        write!(f, "({},{})", self.point, self.value)
     }
 }
 
@@ -95,8 +111,8 @@ impl Point {
     }
 
     pub fn mul(&self, n: usize) -> Self {
-        let point = self.clone();
-        let mut temp = point.clone();
+        let point = *self;
+        let mut temp = point;
         for _ in 1..n {
             temp += point;
         }
@@ -146,14 +162,6 @@ impl AddAssign for Point {
     }
 }
 
-impl Default for Point {
-    fn default() -> Self {
-        Point {
-            a: 0, b: 0, modula: 0, point: None
-        }
-    }
-}
-
 fn get_points(a: isize, b: isize, modula: usize) -> Vec<Point> {
     let ys: Vec<usize> = (0..modula).collect();
     let y2s: HashMap<usize, usize> = ys.iter().map(|y| (((*y)*(*y)) % modula, *y)).collect();
@@ -163,7 +171,7 @@ fn get_points(a: isize, b: isize, modula: usize) -> Vec<Point> {
     let y4x: Vec<Option<usize>> = y4x.iter()
         .map(|y| if y2s.contains_key(y) { Some(*y) } else { None }).collect();
     let mut xys = Vec::new();
-    for (y4xi, xsi) in y4x.into_iter().zip(xs.into_iter())
+    for (y4xi, xsi) in y4x.into_iter().zip(xs)
         .filter(|a| a.0.is_some())
         .map(|a| (a.0.unwrap(), a.1)) {
         xys.push(Point::new(a, b, xsi, y2s[&y4xi], modula));
@@ -182,7 +190,7 @@ fn get_q(n: usize) -> usize{
     if q == 1 { n } else { q }
 }
 
-pub fn get_keys() -> (Point, usize, usize, Point) {
+pub fn get_keys() -> EccKeyPair {
     let mut rng = rand::thread_rng();
     let mut modula: usize = rng.gen_range(34..60);
     while !is_prime(modula as u64) {
@@ -205,7 +213,7 @@ pub fn get_keys() -> (Point, usize, usize, Point) {
     let g = points_group[index].mul(h);
     let secret = rng.gen_range(1..q);
     let open = g.mul(secret);
-    (g, q, secret, open)
+    EccKeyPair { g, q, secret, open }
 }
 
 fn validate_ell(a: isize, b: isize, modula: usize) -> bool {
@@ -223,52 +231,85 @@ pub fn enc(mi: isize, db: &Point, g: &Point, mut k: usize, q: usize) -> CipherVa
         p = db.mul(k);
         (x, _) = p.get_x_y_isize();
     }
-    CipherValue(r, modd(mi * x, p.modula))
+    CipherValue { point: r, value: modd(mi * x, p.modula) }
 }
 
-pub fn encrypt(phrase: &str, db: Point, g: Point, q: usize) -> Result<String, Box<dyn Error>> {
-    let alphabet = Alphabet::new();
-    validate_single(&alphabet, phrase)?;
-    if !validate_ell(g.a, g.b, g.modula) {
-        Err(InvalidKeyError::new("Кривая не соответстует условию"))?;
+fn validate_curve(a: isize, b: isize, modula: usize) -> Result<(), CipherError> {
+    if !validate_ell(a, b, modula) {
+        Err(CipherError::invalid_key("Кривая", "Кривая не соответстует условию"))?;
     }
-    if !is_prime(g.modula as u64) {
-        Err(InvalidKeyError::new("Модуль кривой не является простым числом"))?;
+    if !is_prime(modula as u64) {
+        Err(CipherError::invalid_key("Модуль", "Модуль кривой не является простым числом"))?;
     }
+    Ok(())
+}
+
+fn encrypt_values(phrase: &str, db: Point, g: Point, q: usize) -> Result<Vec<CipherValue>, CipherError> {
+    let alphabet = Alphabet::new();
+    validate_single(&alphabet, phrase)?;
+    validate_curve(g.a, g.b, g.modula)?;
     let mut rng = rand::thread_rng();
-    let result = phrase.chars().map(|x| {
+    Ok(phrase.chars().map(|x| {
         let mi = alphabet.index_of(x) + 1;
         let k = rng.gen_range(1..q);
-        enc(mi as isize, &db, &g, k, q).to_string()
-    }).collect::<Vec<String>>().join("");
-    Ok(result)
+        enc(mi as isize, &db, &g, k, q)
+    }).collect())
+}
+
+pub fn encrypt(phrase: &str, db: Point, g: Point, q: usize) -> Result<String, CipherError> {
+    let result = encrypt_values(phrase, db, g, q)?;
+    Ok(result.iter().map(CipherValue::to_string).collect())
+}
+
+/// Writes every letter as the numbers `x`, `y` and value below the curve modulus
+pub fn encrypt_with_encoding(phrase: &str, db: Point, g: Point, q: usize, encoding: Encoding)
+    -> Result<String, CipherError>
+{
+    let numbers: Vec<usize> = encrypt_values(phrase, db, g, q)?.iter()
+        .flat_map(|value| {
+            let (x, y) = value.point.get_x_y();
+            [x, y, value.value]
+        })
+        .collect();
+    Ok(encoding.encode_numbers(&numbers, g.modula))
 }
 
 pub fn dec(cb: usize, value: CipherValue, modula: usize) -> usize {
-    let q = value.0.mul(cb);
+    let q = value.point.mul(cb);
     let (x, _) = q.get_x_y();
-    modd(value.1 as isize * pow_mod(x, modula-2, modula) as isize, modula)
+    modd(value.value as isize * pow_mod(x, modula-2, modula) as isize, modula)
+}
+
+fn decrypt_values(values: &[CipherValue], cb: usize, modula: usize) -> String {
+    let alphabet = Alphabet::new();
+    values.iter().map(|value| alphabet.get(dec(cb, *value, modula) - 1)).collect()
 }
 
 pub fn decrypt(phrase: &str, cb: usize, a: isize, b: isize, modula: usize)
-    -> Result<String, Box<dyn Error>>
+    -> Result<String, CipherError>
 {
     let alphabet = Alphabet::from("0123456789(),".to_string());
     alphabet.validate(phrase)?;
-    let alphabet = Alphabet::new();
-    if !validate_ell(a, b, modula) {
-        Err(InvalidKeyError::new("Кривая не соответстует условию"))?;
-    }
-    if !is_prime(modula as u64) {
-        Err(InvalidKeyError::new("Модуль кривой не является простым числом"))?;
-    }
+    validate_curve(a, b, modula)?;
     let re = Regex::new(r"(\(\(\d+,\d+\),\d+\))").unwrap();
-    let result: String = re.find_iter(phrase).map(|x| {
-        let val = CipherValue::new(x.as_str(), a, b, modula);
-        let m = dec(cb, val, modula);
-        alphabet.get(m-1)
-    }).collect();
-    Ok(result)
+    let values = re.find_iter(phrase)
+        .map(|x| CipherValue::new(x.as_str(), a, b, modula))
+        .collect::<Result<Vec<CipherValue>, CipherError>>()?;
+    Ok(decrypt_values(&values, cb, modula))
+}
+
+pub fn decrypt_with_encoding(phrase: &str, cb: usize, a: isize, b: isize, modula: usize, encoding: Encoding)
+    -> Result<String, CipherError>
+{
+    validate_curve(a, b, modula)?;
+    let numbers = encoding.decode_numbers(phrase, modula)?;
+    if !numbers.len().is_multiple_of(3) {
+        Err(CipherError::invalid_size("Фраза", "Число чисел шифртекста должно быть кратно трём"))?;
+    }
+    let values: Vec<CipherValue> = numbers.chunks(3)
+        .map(|chunk| CipherValue { point: Point::new(a, b, chunk[0], chunk[1], modula), value: chunk[2] })
+        .collect();
+    Ok(decrypt_values(&values, cb, modula))
 }
 
 #[cfg(test)]
@@ -304,4 +345,34 @@ mod ecc_tests {
         let valid = "отодно";
         assert_eq!(valid, decrypt(phrase, cb, a, b, p).unwrap());
     }
+
+    #[test]
+    fn test_with_encoding() {
+        let db = Point::new(2, 7, 8, 21, 47);
+        let g = Point::new(2, 7, 8, 26, 47);
+        let phrase = "отодно";
+        for encoding in Encoding::ALL {
+            let encrypted = encrypt_with_encoding(phrase, db, g, 3, encoding).unwrap();
+            assert_eq!(decrypt_with_encoding(&encrypted, 2, 2, 7, 47, encoding).unwrap(), phrase);
+        }
+        assert_eq!(decrypt_with_encoding("08151a0815", 2, 2, 7, 47, Encoding::HexLower).unwrap_err(),
+            CipherError::invalid_size("Фраза", "Число чисел шифртекста должно быть кратно трём"));
+    }
+
+    #[test]
+    fn test_cipher_value() {
+        let value = CipherValue::new("((8,21),26)", 2, 7, 47).unwrap();
+        assert_eq!(value, CipherValue { point: Point::new(2, 7, 8, 21, 47), value: 26 });
+        assert_eq!(value.to_string(), "((8,21),26)");
+        assert!(matches!(CipherValue::new("((8,21))", 2, 7, 47), Err(CipherError::InvalidSize { .. })));
+        assert!(matches!(CipherValue::new("((8,x),26)", 2, 7, 47), Err(CipherError::InvalidNumber { .. })));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        let value = CipherValue { point: Point::new(2, 7, 8, 21, 47), value: 26 };
+        let json = serde_json::to_string(&value).unwrap();
+        assert_eq!(serde_json::from_str::<CipherValue>(&json).unwrap(), value);
+    }
 }
@@ -1,11 +1,11 @@
-use std::error::Error;
+use crate::errors::CipherError;
 use itertools::Itertools;
 use num::Integer;
 use rand::Rng;
 use primes::is_prime;
 use crate::alphabet::Alphabet;
-use crate::asymmetric::{get_numbers, pow_mod, to_string};
-use crate::errors::{InvalidIndex, InvalidKeyError, InvalidTextError};
+use crate::asymmetric::pow_mod;
+use crate::encoding::Encoding;
 use crate::methods::validate_single;
 
 struct Generator {
@@ -48,7 +48,16 @@ impl Iterator for Generator {
     }
 }
 
-pub fn gen_keys() -> (usize, usize, usize, usize) {
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ElgamalKeyPair {
+    pub p: usize,
+    pub x: usize,
+    pub g: usize,
+    pub y: usize,
+}
+
+pub fn gen_keys() -> ElgamalKeyPair {
     let alphabet = Alphabet::new();
     let mut rng = rand::thread_rng();
     let mut p = rng.gen_range(alphabet.len()+1..60);
@@ -58,62 +67,69 @@ pub fn gen_keys() -> (usize, usize, usize, usize) {
     let x = rng.gen_range(2..p);
     let g = rng.gen_range(2..p);
     let y = pow_mod(g, x, p);
-    (p, x, g, y)
+    ElgamalKeyPair { p, x, g, y }
 }
 
-fn validate(p: usize, g: usize) -> Result<(), Box<dyn Error>> {
+fn validate(p: usize, g: usize) -> Result<(), CipherError> {
     let alphabet = Alphabet::new();
     if p <= alphabet.len() {
-        Err(InvalidIndex)?;
+        Err(CipherError::invalid_key("p", "p должно быть больше длины алфавита"))?;
     }
     if g >= p || g == 1 {
-        Err(InvalidIndex)?;
+        Err(CipherError::invalid_key("g", "g должно быть больше 1 и меньше p"))?;
     }
     Ok(())
 }
 
-fn validate_dec(phrase: &str, x: usize, p: usize) -> Result<Vec<(usize, usize)>, Box<dyn Error>> {
-    let alphabet = Alphabet::from("0123456789".to_string());
-    alphabet.validate(phrase)?;
-    let len = p.to_string().len();
-    if x >= p || x == 1 { Err(InvalidKeyError::new("x должно быть меньше p"))?; }
-    if phrase.chars().count() % (len * 2) != 0 {
-        Err(InvalidTextError)?;
+fn validate_dec(phrase: &str, x: usize, p: usize, encoding: Encoding) -> Result<Vec<(usize, usize)>, CipherError> {
+    if x >= p || x == 1 { Err(CipherError::invalid_key("x", "x должно быть меньше p"))?; }
+    let numbers = encoding.decode_numbers(phrase, p)?;
+    if !numbers.len().is_multiple_of(2) {
+        Err(CipherError::invalid_size("Фраза", "Количество блоков шифртекста должно быть четным"))?;
     }
-    let result: Vec<_> = get_numbers(phrase, len)
+    let result: Vec<_> = numbers
         .into_iter()
         .tuple_windows()
         .step_by(2)
         .collect();
     for (ai, bi) in result.iter() {
         if *ai >= p || *bi >= p {
-            Err(InvalidTextError)?;
+            Err(CipherError::invalid_size("Фраза", "Блок шифртекста должен быть меньше p"))?;
         }
     }
     Ok(result)
 }
 
 pub fn encrypt(phrase: &str, p: usize, g: usize, y: usize, r: Option<Vec<usize>>)
-    -> Result<String, Box<dyn Error>>
+    -> Result<String, CipherError>
 {
+    encrypt_with_encoding(phrase, p, g, y, r, Encoding::Decimal)
+}
+
+pub fn encrypt_with_encoding(
+    phrase: &str, p: usize, g: usize, y: usize, r: Option<Vec<usize>>, encoding: Encoding
+) -> Result<String, CipherError> {
     let alphabet = Alphabet::new();
     validate_single(&alphabet, phrase)?;
     validate(p, g)?;
     let phi = p-1;
-    let len = p.to_string().len();
     let gen = Generator::new(phi, phrase.chars().count(), r);
-    let result: String = phrase.chars().zip(gen).map(|(mi, ki)| {
+    let result: Vec<usize> = phrase.chars().zip(gen).flat_map(|(mi, ki)| {
         let mi = alphabet.index_of(mi)+1;
         let ai = pow_mod(g, ki, p);
         let bi = (pow_mod(y, ki, p) * mi) % p;
-        to_string(ai, len) + &to_string(bi, len)
-    }).collect::<Vec<String>>().join("");
-    Ok(result)
+        [ai, bi]
+    }).collect();
+    Ok(encoding.encode_numbers(&result, p))
 }
 
-pub fn decrypt(phrase: &str, p: usize, x: usize) -> Result<String, Box<dyn Error>> {
+pub fn decrypt(phrase: &str, p: usize, x: usize) -> Result<String, CipherError> {
+    decrypt_with_encoding(phrase, p, x, Encoding::Decimal)
+}
+
+pub fn decrypt_with_encoding(phrase: &str, p: usize, x: usize, encoding: Encoding) -> Result<String, CipherError> {
     let alphabet = Alphabet::new();
-    let phrase = validate_dec(phrase, x, p)?;
+    let phrase = validate_dec(phrase, x, p, encoding)?;
     let buffer: Vec<usize> = phrase.into_iter().map(|(ai, bi)| {
         (bi * pow_mod(pow_mod(ai, x, p), p-2, p)) % p - 1
     }).collect();
@@ -122,7 +138,7 @@ pub fn decrypt(phrase: &str, p: usize, x: usize) -> Result<String, Box<dyn Error
         if alphabet.has(num) {
             result.push(alphabet.get(num));
         } else {
-            Err(InvalidIndex)?;
+            Err(CipherError::InvalidIndex)?;
         }
     }
     Ok(result)
@@ -150,4 +166,11 @@ mod elgamal_tests {
         let valid = "отодно";
         assert_eq!(decrypt(phrase, p, x).unwrap(), valid);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_with_encoding() {
+        let encrypted = encrypt_with_encoding("отодно", 41, 3, 14, Some(vec![3, 11, 7]), Encoding::HexUpper);
+        assert_eq!(encrypted.unwrap(), "1B251B190E041B1A1B280E04");
+        assert_eq!(decrypt_with_encoding("1b251b190e041b1a1b280e04", 41, 15, Encoding::HexUpper).unwrap(), "отодно");
+    }
+}
@@ -1,16 +1,24 @@
 use rand::Rng;
-use std::error::Error;
+use crate::errors::CipherError;
 use num::Integer;
-use super::{get_numbers, pow_mod, to_string};
+use super::pow_mod;
 use crate::alphabet::Alphabet;
-use crate::errors::{InvalidIndex, InvalidKeyError, InvalidTextError};
+use crate::encoding::Encoding;
 use crate::methods::validate_single;
 
 fn proto(letters: &[usize], power: usize, modula: usize) -> Vec<usize> {
     letters.iter().map(|x| pow_mod(*x, power, modula)).collect()
 }
 
-pub fn gen_keys(p: usize, q: usize) -> (usize, usize, usize) {
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RsaKeyPair {
+    pub e: usize,
+    pub d: usize,
+    pub n: usize,
+}
+
+pub fn gen_keys(p: usize, q: usize) -> RsaKeyPair {
     let phi = (p-1) * (q-1);
     let n = p*q;
     let mut rng = rand::thread_rng();
@@ -19,50 +27,50 @@ pub fn gen_keys(p: usize, q: usize) -> (usize, usize, usize) {
         e = rng.gen_range(2..phi);
     }
     let d = pow_mod(e, phi-1, phi);
-    (e, d, n)
+    RsaKeyPair { e, d, n }
 }
 
-pub fn encrypt(phrase: &str, n: usize, e: usize) -> Result<String, Box<dyn Error>> {
+pub fn encrypt(phrase: &str, n: usize, e: usize) -> Result<String, CipherError> {
+    encrypt_with_encoding(phrase, n, e, Encoding::Decimal)
+}
+
+pub fn encrypt_with_encoding(phrase: &str, n: usize, e: usize, encoding: Encoding) -> Result<String, CipherError> {
     let alphabet = Alphabet::new();
     validate_single(&alphabet, phrase)?;
-    if e >= n { Err(InvalidKeyError::new("E должно быть меньше либо равно n"))?; }
-    let mut result = String::new();
-    let len = n.to_string().len();
-    let _: () = proto(
+    if e >= n { Err(CipherError::invalid_key("E", "E должно быть меньше либо равно n"))?; }
+    let result = proto(
         &phrase.chars().map(|letter| alphabet.index_of(letter)+1).collect::<Vec<usize>>(),
         e,
         n
-    ).into_iter().map(|res| result.push_str(&to_string(res, len))).collect();
-    Ok(result)
+    );
+    Ok(encoding.encode_numbers(&result, n))
 }
 
-fn validate(phrase: &str, n: usize, d: usize) -> Result<Vec<usize>, Box<dyn Error>> {
-    let alphabet = Alphabet::from("0123456789".to_string());
-    alphabet.validate(phrase)?;
-    let len = n.to_string().len();
-    if d >= n { Err(InvalidKeyError::new("D должно быть меньше либо равно n"))?; }
-    if phrase.chars().count() % len != 0 {
-        Err(InvalidTextError)?;
-    }
-    let result = get_numbers(phrase, len);
+fn validate(phrase: &str, n: usize, d: usize, encoding: Encoding) -> Result<Vec<usize>, CipherError> {
+    if d >= n { Err(CipherError::invalid_key("D", "D должно быть меньше либо равно n"))?; }
+    let result = encoding.decode_numbers(phrase, n)?;
     for letter in result.iter() {
         if *letter >= n {
-            Err(InvalidTextError)?;
+            Err(CipherError::invalid_size("Фраза", "Блок шифртекста должен быть меньше n"))?;
         }
     }
     Ok(result)
 }
 
-pub fn decrypt(phrase: &str, n: usize, d: usize) -> Result<String, Box<dyn Error>> {
+pub fn decrypt(phrase: &str, n: usize, d: usize) -> Result<String, CipherError> {
+    decrypt_with_encoding(phrase, n, d, Encoding::Decimal)
+}
+
+pub fn decrypt_with_encoding(phrase: &str, n: usize, d: usize, encoding: Encoding) -> Result<String, CipherError> {
     let alphabet = Alphabet::new();
     let mut result = String::new();
-    let phrase = validate(phrase, n, d)?;
+    let phrase = validate(phrase, n, d, encoding)?;
     let buffer = proto(&phrase, d, n);
     for num in buffer {
         if alphabet.has(num - 1) {
             result.push(alphabet.get(num-1));
         } else {
-            Err(InvalidIndex)?;
+            Err(CipherError::InvalidIndex)?;
         }
     }
     Ok(result)
@@ -89,4 +97,30 @@ mod rsa_tests {
         let valid = "отодногопорченогояблокавесьвоззагниваеттчк";
         assert_eq!(decrypt(phrase, n, d).unwrap(), valid);
     }
+
+    #[test]
+    fn test_with_encoding() {
+        let phrase = "отодно";
+        let encrypted = encrypt_with_encoding(phrase, 77, 23, Encoding::HexLower).unwrap();
+        assert_eq!(encrypted, "4011403b3140");
+        assert_eq!(decrypt_with_encoding(&encrypted, 77, 47, Encoding::HexLower).unwrap(), phrase);
+        let encrypted = encrypt_with_encoding(phrase, 77, 23, Encoding::Base64).unwrap();
+        assert_eq!(decrypt_with_encoding(&encrypted, 77, 47, Encoding::Base64).unwrap(), phrase);
+    }
+
+    #[test]
+    fn test_gen_keys() {
+        let RsaKeyPair { e, d, n } = gen_keys(7, 11);
+        assert_eq!(n, 77);
+        assert_eq!((e * d) % 60, 1);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        let keys = RsaKeyPair { e: 23, d: 47, n: 77 };
+        let json = serde_json::to_string(&keys).unwrap();
+        assert_eq!(json, r#"{"e":23,"d":47,"n":77}"#);
+        assert_eq!(serde_json::from_str::<RsaKeyPair>(&json).unwrap(), keys);
+    }
 }
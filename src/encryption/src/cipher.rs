@@ -0,0 +1,131 @@
+use crate::errors::CipherError;
+
+/// Common interface of the symmetric ciphers.
+///
+/// Every algorithm is a type implementing this trait, the key of the
+/// algorithm is described by the associated `Key` type, so callers can
+/// work with the ciphers generically instead of calling the module functions.
+pub trait Cipher {
+    type Key;
+
+    fn encrypt(&self, phrase: &str, key: &Self::Key) -> Result<String, CipherError>;
+
+    fn decrypt(&self, phrase: &str, key: &Self::Key) -> Result<String, CipherError>;
+}
+
+#[cfg(test)]
+mod cipher_tests {
+    use super::*;
+    use crate::a5_1::A51;
+    use crate::a5_2::A52;
+    use crate::aes::{Aes, AesKey};
+    use crate::atbash::Atbash;
+    use crate::belazo::Belazo;
+    use crate::caesar::Caesar;
+    use crate::cardano::{Cardano, CardanoKey};
+    use crate::kuznechik::{Kuznechik, KuznechikKey};
+    use crate::magma::{CtrMagma, CtrMagmaKey, Magma, MagmaKey};
+    use crate::matrix::MatrixCipher;
+    use crate::playfair::Playfair;
+    use crate::polybius::{Polybius, PolybiusKey};
+    use crate::shenon::{Shenon, ShenonKey};
+    use crate::trithemium::Trithemium;
+    use crate::vertical::Vertical;
+    use crate::vigenere::Vigenere;
+
+    const PHRASE: &str = "отодногопорченогояблокавесьвоззагниваеттчк";
+
+    fn round_trip<C: Cipher>(cipher: &C, phrase: &str, key: &C::Key) -> String {
+        let encrypted = cipher.encrypt(phrase, key).unwrap();
+        cipher.decrypt(&encrypted, key).unwrap()
+    }
+
+    #[test]
+    fn test_mono_alphabetic() {
+        assert_eq!(round_trip(&Atbash, PHRASE, &()), PHRASE);
+        assert_eq!(round_trip(&Caesar, PHRASE, &3), PHRASE);
+        let key = PolybiusKey { rows: 6, columns: 6 };
+        assert_eq!(round_trip(&Polybius, PHRASE, &key), PHRASE);
+    }
+
+    #[test]
+    fn test_multi_alphabetic() {
+        assert_eq!(round_trip(&Trithemium, PHRASE, &()), PHRASE);
+        assert_eq!(round_trip(&Belazo, PHRASE, &"арбуз".to_string()), PHRASE);
+        assert_eq!(round_trip(&Vigenere, PHRASE, &'ю'), PHRASE);
+    }
+
+    #[test]
+    fn test_block() {
+        let matrix = vec![vec![2, 5, 6], vec![4, 3, 2], vec![7, 1, 5]];
+        assert_eq!(MatrixCipher.encrypt("окно", &matrix).unwrap().len(), 6 * 3);
+        assert_eq!(round_trip(&MatrixCipher, "отодно", &matrix), "отодно");
+        assert_eq!(round_trip(&Playfair, "отодно", &"респавн".to_string()), "отодно");
+    }
+
+    #[test]
+    fn test_transposition() {
+        assert_eq!(round_trip(&Vertical, PHRASE, &"супчик".to_string()), PHRASE);
+        let key = CardanoKey {
+            grid: vec![false, true, false, true,
+                       true, false, true, false,
+                       false, false, false, false,
+                       false, false, false, false],
+            rows: 4,
+            cols: 4,
+            dirs: vec![true, false, true]
+        };
+        assert_eq!(round_trip(&Cardano, "окноокноокноокно", &key), "окноокноокноокно");
+    }
+
+    #[test]
+    fn test_gamma() {
+        let key = ShenonKey { t0: 3, a: 9, c: 5 };
+        assert_eq!(
+            Shenon.encrypt(PHRASE, &key).unwrap(),
+            "очалсчщщчыкжсюмцюфгввгжээожбкихггтъйдоиэяч"
+        );
+        assert_eq!(round_trip(&Shenon, PHRASE, &key), PHRASE);
+    }
+
+    #[test]
+    fn test_inline() {
+        let key = 0xffeeddcc77665544;
+        assert_eq!(A51.encrypt("ото", &key).unwrap(), "f48466990c59");
+        assert_eq!(round_trip(&A51, "ото", &key), "ото");
+        assert_eq!(A52.encrypt("ото", &key).unwrap(), "9b84cdab0f44");
+        assert_eq!(round_trip(&A52, "ото", &key), "ото");
+    }
+
+    #[test]
+    fn test_combinational() {
+        let key = MagmaKey::from_hex("ffeeddccbbaa99887766554433221100f0f1f2f3f4f5f6f7f8f9fafbfcfdfeff").unwrap();
+        assert_eq!(Magma.encrypt("fedcba9876543210", &key).unwrap(), "4ee901e5c2d8ca3d");
+        assert_eq!(round_trip(&Magma, "fedcba9876543210", &key), "fedcba9876543210");
+        let ctr_key = CtrMagmaKey { key, init_v: [0x12, 0x34, 0x56, 0x78] };
+        assert_eq!(round_trip(&CtrMagma, "92def06b3c130a59", &ctr_key), "92def06b3c130a59");
+        let key = AesKey::from_hex("2b7e151628aed2a6abf7158809cf4f3c").unwrap();
+        let phrase = "3243f6a8885a308d313198a2e0370734";
+        assert_eq!(Aes.encrypt(phrase, &key).unwrap(), "3925841d02dc09fbdc118597196a0b32");
+        assert_eq!(round_trip(&Aes, phrase, &key), phrase);
+        let key = KuznechikKey::from_hex("8899aabbccddeeff0011223344556677fedcba98765432100123456789abcdef").unwrap();
+        let phrase = "1122334455667700ffeeddccbbaa9988";
+        assert_eq!(Kuznechik.encrypt(phrase, &key).unwrap(), "7f679d90bebc24305a468d42b9d4edcd");
+        assert_eq!(round_trip(&Kuznechik, phrase, &key), phrase);
+    }
+
+    #[test]
+    fn test_combinational_unaligned() {
+        let phrase = "d0bed182d0be";
+        let key = "ffeeddccbbaa99887766554433221100f0f1f2f3f4f5f6f7f8f9fafbfcfdfeff";
+        let result = Magma.encrypt(phrase, &MagmaKey::from_hex(key).unwrap()).unwrap();
+        assert_eq!(result, crate::magma::encrypt(phrase, key).unwrap());
+        assert_eq!(round_trip(&Magma, phrase, &MagmaKey::from_hex(key).unwrap()), format!("{phrase}0000"));
+        let result = Kuznechik.encrypt(phrase, &KuznechikKey::from_hex(key).unwrap()).unwrap();
+        assert_eq!(result, crate::kuznechik::encrypt(phrase, key).unwrap());
+        let key = "2b7e151628aed2a6abf7158809cf4f3c";
+        let result = Aes.encrypt(phrase, &AesKey::from_hex(key).unwrap()).unwrap();
+        assert_eq!(result, crate::aes::encrypt(phrase, key).unwrap());
+        assert_eq!(round_trip(&Aes, phrase, &AesKey::from_hex(key).unwrap()), format!("{phrase}{}", "0".repeat(20)));
+    }
+}
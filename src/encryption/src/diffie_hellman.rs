@@ -1,15 +1,23 @@
-use std::error::Error;
+use crate::errors::CipherError;
 use rand::Rng;
 use crate::asymmetric::pow_mod;
 
-pub fn gen_keys(a: usize, n: usize) -> Result<(usize, usize), Box<dyn Error>> {
+/// Private key `k` and public key `y = a^k mod n` of one side of the exchange
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DiffieHellmanKeys {
+    pub k: usize,
+    pub y: usize,
+}
+
+pub fn gen_keys(a: usize, n: usize) -> Result<DiffieHellmanKeys, CipherError> {
     if a >= n || a <= 1{
-        Err("введены некорректные начальные значения")?;
+        Err(CipherError::invalid_key("a", "Введены некорректные начальные значения"))?;
     }
     let mut rnd = rand::thread_rng();
     let k = rnd.gen_range(2..n-1);
     let y = get_y(a, n, k);
-    Ok((k, y))
+    Ok(DiffieHellmanKeys { k, y })
 }
 
 pub fn get_y(a: usize, n: usize, k: usize) -> usize {
@@ -4,7 +4,8 @@ use rand::Rng;
 use crate::alphabet::Alphabet;
 use crate::asymmetric::{phi, pow_mod};
 use crate::methods::{modd, validate_single};
-use std::error::Error;
+use crate::errors::CipherError;
+use crate::digital_signature::Signature;
 
 use super::square_hash;
 pub use crate::asymmetric::elgamal::gen_keys;
@@ -23,23 +24,23 @@ pub fn sign(
     x: usize,
     modula: usize,
     k: Option<usize>,
-) -> Result<(usize, usize), Box<dyn Error>> {
+) -> Result<Signature, CipherError> {
     let alphabet = Alphabet::new();
     validate_single(&alphabet, phrase)?;
     let m = square_hash(phrase, modula);
-    let k = if k.is_none() {
+    let k = if let Some(k) = k {
+        k
+    } else {
         let mut rng = rand::thread_rng();
         let mut k = rng.gen_range(2..p - 1);
         while (p - 1).gcd(&k) == 1 {
             k = rng.gen_range(2..p - 1);
         }
         k
-    } else {
-        k.unwrap()
     };
     let a = pow_mod(g, k, p);
     let b = get_b(m, x, a, k, p - 1);
-    Ok((a, b))
+    Ok(Signature { r: a, s: b })
 }
 
 pub fn check_sign(
@@ -48,8 +49,9 @@ pub fn check_sign(
     g: usize,
     y: usize,
     modula: usize,
-    (a, b): (usize, usize),
-) -> Result<bool, Box<dyn Error>> {
+    sign: &Signature,
+) -> Result<bool, CipherError> {
+    let Signature { r: a, s: b } = *sign;
     let alphabet = Alphabet::new();
     validate_single(&alphabet, phrase)?;
     let m = square_hash(phrase, modula);
@@ -80,7 +82,7 @@ mod egsa_tests {
         let g = 31;
         let x = 3;
         let modula = 11;
-        let valid = (31, 11);
+        let valid = Signature { r: 31, s: 11 };
         let result = sign(phrase, p, g, x, modula, Some(5)).unwrap();
         assert_eq!(result, valid);
     }
@@ -92,8 +94,8 @@ mod egsa_tests {
         let g = 31;
         let y = 6;
         let modula = 11;
-        let s = (31, 11);
-        let result = check_sign(phrase, p, g, y, modula, s).unwrap();
+        let s = Signature { r: 31, s: 11 };
+        let result = check_sign(phrase, p, g, y, modula, &s).unwrap();
         assert!(result);
     }
 }
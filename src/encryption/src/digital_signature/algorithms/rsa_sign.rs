@@ -1,17 +1,16 @@
 use crate::alphabet::Alphabet;
 use crate::asymmetric::pow_mod;
-use crate::errors::InvalidKeyError;
 use crate::methods::validate_single;
-use std::error::Error;
+use crate::errors::CipherError;
 
 use super::square_hash;
 pub use crate::asymmetric::rsa::gen_keys;
 
-pub fn sign(phrase: &str, n: usize, d: usize, modula: usize) -> Result<usize, Box<dyn Error>> {
+pub fn sign(phrase: &str, n: usize, d: usize, modula: usize) -> Result<usize, CipherError> {
     let alphabet = Alphabet::new();
     validate_single(&alphabet, phrase)?;
     if d >= n {
-        Err(InvalidKeyError::new("D должно быть меньше либо равно n"))?;
+        Err(CipherError::invalid_key("D", "D должно быть меньше либо равно n"))?;
     }
     let m = square_hash(phrase, modula);
     let result = pow_mod(m, d, n);
@@ -24,11 +23,11 @@ pub fn check_sign(
     e: usize,
     s: usize,
     modula: usize,
-) -> Result<bool, Box<dyn Error>> {
+) -> Result<bool, CipherError> {
     let alphabet = Alphabet::new();
     validate_single(&alphabet, phrase)?;
     if e >= n {
-        Err(InvalidKeyError::new("E должно быть меньше либо равно n"))?;
+        Err(CipherError::invalid_key("E", "E должно быть меньше либо равно n"))?;
     }
     let m = square_hash(phrase, modula);
     let ms = pow_mod(s, e, n);
@@ -1,2 +1,79 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use crate::encoding::Encoding;
+use crate::errors::CipherError;
+
 pub mod algorithms;
 pub mod standarts;
+
+/// Signature pair written as `r,s`
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Signature {
+    pub r: usize,
+    pub s: usize,
+}
+
+impl Signature {
+    /// Writes both components as numbers below `modulus` in the given encoding
+    pub fn encode(&self, encoding: Encoding, modulus: usize) -> String {
+        encoding.encode_numbers(&[self.r, self.s], modulus)
+    }
+
+    pub fn decode(text: &str, encoding: Encoding, modulus: usize) -> Result<Self, CipherError> {
+        match encoding.decode_numbers(text, modulus)?[..] {
+            [r, s] => Ok(Signature { r, s }),
+            _ => Err(CipherError::InvalidSignature),
+        }
+    }
+}
+
+impl Display for Signature {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{},{}", self.r, self.s)
+    }
+}
+
+impl FromStr for Signature {
+    type Err = CipherError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (r, s) = s.split_once(',').ok_or(CipherError::InvalidSignature)?;
+        let r = r.trim().parse().map_err(|_| CipherError::InvalidSignature)?;
+        let s = s.trim().parse().map_err(|_| CipherError::InvalidSignature)?;
+        Ok(Signature { r, s })
+    }
+}
+
+#[cfg(test)]
+mod signature_tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!("31, 11".parse::<Signature>().unwrap(), Signature { r: 31, s: 11 });
+        assert_eq!(Signature { r: 31, s: 11 }.to_string(), "31,11");
+        assert_eq!("31".parse::<Signature>(), Err(CipherError::InvalidSignature));
+        assert_eq!("31,x".parse::<Signature>(), Err(CipherError::InvalidSignature));
+    }
+
+    #[test]
+    fn test_encoding() {
+        let signature = Signature { r: 31, s: 11 };
+        assert_eq!(signature.encode(Encoding::Decimal, 37), "3111");
+        assert_eq!(signature.encode(Encoding::HexLower, 37), "1f0b");
+        for encoding in Encoding::ALL {
+            let text = signature.encode(encoding, 37);
+            assert_eq!(Signature::decode(&text, encoding, 37).unwrap(), signature);
+        }
+        assert_eq!(Signature::decode("1f0b0c", Encoding::HexLower, 37), Err(CipherError::InvalidSignature));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        let json = serde_json::to_string(&Signature { r: 31, s: 11 }).unwrap();
+        assert_eq!(json, r#"{"r":31,"s":11}"#);
+        assert_eq!(serde_json::from_str::<Signature>(&json).unwrap(), Signature { r: 31, s: 11 });
+    }
+}
@@ -1,12 +1,13 @@
-use std::error::Error;
+use crate::errors::CipherError;
 use rand::Rng;
 use crate::asymmetric::pow_mod;
 use crate::digital_signature::algorithms::square_hash;
 use crate::methods::modd;
+use crate::digital_signature::Signature;
 pub use crate::asymmetric::ecc::Point;
 pub use crate::asymmetric::ecc::get_keys;
 
-pub fn sign(message: &str, x: usize, g: Point, q: usize, m: usize) -> Result<String, Box<dyn Error>> {
+pub fn sign(message: &str, x: usize, g: Point, q: usize, m: usize) -> Result<Signature, CipherError> {
     let mut rang = rand::thread_rng();
     let mut h = square_hash(message, m);
     if h == 0 {
@@ -20,20 +21,17 @@ pub fn sign(message: &str, x: usize, g: Point, q: usize, m: usize) -> Result<Str
     }
     let r = modd(p.get_x_y_isize().0, q);
     let s = modd((k * h + r * x) as isize, q);
-    Ok(format!("{},{}", r, s))
+    Ok(Signature { r, s })
 }
 
-pub fn check_sign(message: &str, y: Point, g: Point, q: usize, sign: &str, m: usize)
-    -> Result<bool, Box<dyn Error>>
+pub fn check_sign(message: &str, y: Point, g: Point, q: usize, sign: &Signature, m: usize)
+    -> Result<bool, CipherError>
 {
     let mut h = square_hash(message, m);
     if h == 0 {
         h = 1;
     }
-    let parsed: Vec<usize> = sign.split(',')
-        .map(|x| x.to_string().parse::<usize>().expect("Not a number"))
-        .collect();
-    let [r, s] = parsed[..] else { Err("1")? };
+    let Signature { r, s } = *sign;
     if r == 0 || s >= q {
         return Ok(false)
     }
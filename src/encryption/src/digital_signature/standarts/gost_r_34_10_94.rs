@@ -1,8 +1,9 @@
-use std::error::Error;
+use crate::errors::CipherError;
 use primes::is_prime;
 use rand::Rng;
 use crate::alphabet::Alphabet;
 use crate::methods::modd;
+use crate::digital_signature::Signature;
 
 fn square_hash(phrase: &str, modula: u128) -> u128 {
     let alphabet = Alphabet::new();
@@ -25,12 +26,17 @@ fn pow_mod(left: u128, right: u128, modula: u128) -> u128 {
     result
 }
 
-pub fn sign(message: &str, a: u128, p: u128, x: u128, q: u128, m: u128) -> Result<(u128, u128), Box<dyn Error>> {
+pub fn sign(message: &str, a: u128, p: u128, x: u128, q: u128, m: u128) -> Result<Signature, CipherError> {
     let mut rang = rand::thread_rng();
-    if p < 32 || !is_prime(p as u64) { Err("Ошибка")?; }
-    if a <=1 || a >= p-1 { Err("Ошибка")?; }
-    if pow_mod(a, q, p) != 1 { Err("Ошибка")?; }
-    if q <= 1 || x <= 1 { Err("Ошибка")?; }
+    if p < 32 || !is_prime(p as u64) {
+        Err(CipherError::invalid_key("p", "p должно быть простым числом не меньше 32"))?;
+    }
+    if a <=1 || a >= p-1 { Err(CipherError::invalid_key("a", "a должно быть в пределах от 2 до p-2"))?; }
+    let too_large = || CipherError::invalid_size("q", "q не должно превышать наибольшее значение usize");
+    if usize::try_from(q).is_err() { Err(too_large())?; }
+    if pow_mod(a, q, p) != 1 { Err(CipherError::invalid_key("a", "a^q mod p должно быть равно 1"))?; }
+    if q <= 1 { Err(CipherError::invalid_key("q", "q должно быть больше 1"))?; }
+    if x <= 1 { Err(CipherError::invalid_key("x", "x должно быть больше 1"))?; }
     let mut rs = 0;
     let mut h = square_hash(message, m);
     if h == 0 {
@@ -47,7 +53,10 @@ pub fn sign(message: &str, a: u128, p: u128, x: u128, q: u128, m: u128) -> Resul
         }
         s = (x * rs + k * h) % q;
     }
-    Ok((rs, s))
+    Ok(Signature {
+        r: usize::try_from(rs).map_err(|_| too_large())?,
+        s: usize::try_from(s).map_err(|_| too_large())?
+    })
 }
 
 pub fn check_sign(
@@ -57,8 +66,9 @@ pub fn check_sign(
     a: u128,
     y: u128,
     m: u128,
-    (rs, s): (u128, u128),
+    sign: &Signature,
 ) -> bool {
+    let (rs, s) = (sign.r as u128, sign.s as u128);
     let mut h = square_hash(message, m);
     if h == 0 {
         h = 1;
@@ -69,3 +79,14 @@ pub fn check_sign(
     let u = ((pow_mod(a, z1, p) * pow_mod(y, z2, p)) % p) % q;
     u == rs
 }
+
+#[cfg(test)]
+mod gost_r_34_10_94_tests {
+    use super::*;
+
+    #[test]
+    fn test_sign_large_q() {
+        let q = usize::MAX as u128 + 1;
+        assert!(matches!(sign("отодно", 2, 47, 5, q, 11), Err(CipherError::InvalidSize { param: "q", .. })));
+    }
+}
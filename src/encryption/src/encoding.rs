@@ -0,0 +1,195 @@
+use data_encoding::{BASE32, BASE64, BASE64URL};
+use crate::alphabet::Alphabet;
+use crate::errors::CipherError;
+use crate::methods::parse_number;
+
+/// Text representation of the binary output of the algorithms.
+///
+/// Byte-oriented ciphers go through `encode`/`decode`, the asymmetric ones
+/// emit numbers below a modulus and go through `encode_numbers`/`decode_numbers`.
+/// Whitespace is ignored while decoding.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Encoding {
+    #[default]
+    HexLower,
+    HexUpper,
+    Base64,
+    Base64Url,
+    Base32,
+    /// Eight bits per byte
+    Binary,
+    /// Zero-padded decimal groups: three digits per byte
+    /// or the number of digits of the modulus per number
+    Decimal,
+}
+
+impl Encoding {
+    pub const ALL: [Encoding; 7] = [
+        Encoding::HexLower,
+        Encoding::HexUpper,
+        Encoding::Base64,
+        Encoding::Base64Url,
+        Encoding::Base32,
+        Encoding::Binary,
+        Encoding::Decimal,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Encoding::HexLower => "hex",
+            Encoding::HexUpper => "HEX",
+            Encoding::Base64 => "Base64",
+            Encoding::Base64Url => "Base64 URL",
+            Encoding::Base32 => "Base32",
+            Encoding::Binary => "Двоичная",
+            Encoding::Decimal => "Десятичная",
+        }
+    }
+
+    pub fn encode(&self, bytes: &[u8]) -> String {
+        match self {
+            Encoding::HexLower => hex::encode(bytes),
+            Encoding::HexUpper => hex::encode_upper(bytes),
+            Encoding::Base64 => BASE64.encode(bytes),
+            Encoding::Base64Url => BASE64URL.encode(bytes),
+            Encoding::Base32 => BASE32.encode(bytes),
+            Encoding::Binary => bytes.iter().map(|byte| format!("{byte:08b}")).collect(),
+            Encoding::Decimal => bytes.iter().map(|byte| format!("{byte:03}")).collect(),
+        }
+    }
+
+    pub fn decode(&self, text: &str) -> Result<Vec<u8>, CipherError> {
+        let text = strip(text);
+        match self {
+            Encoding::HexLower | Encoding::HexUpper => Ok(hex::decode(text)?),
+            Encoding::Base64 => self.decode_with(&BASE64, &text),
+            Encoding::Base64Url => self.decode_with(&BASE64URL, &text),
+            Encoding::Base32 => self.decode_with(&BASE32, &text),
+            Encoding::Binary => self.decode_groups(&text, 8, 2),
+            Encoding::Decimal => self.decode_groups(&text, 3, 10),
+        }
+    }
+
+    /// Encodes numbers less than `modulus` as fixed-width groups
+    pub fn encode_numbers(&self, numbers: &[usize], modulus: usize) -> String {
+        match self {
+            Encoding::Decimal => {
+                let width = modulus.to_string().len();
+                numbers.iter().map(|number| format!("{number:0width$}")).collect()
+            },
+            _ => {
+                let width = byte_width(modulus);
+                let bytes: Vec<u8> = numbers.iter()
+                    .flat_map(|number| number.to_be_bytes()[size_of::<usize>() - width..].to_vec())
+                    .collect();
+                self.encode(&bytes)
+            }
+        }
+    }
+
+    pub fn decode_numbers(&self, text: &str, modulus: usize) -> Result<Vec<usize>, CipherError> {
+        match self {
+            Encoding::Decimal => {
+                let text = strip(text);
+                Alphabet::digits().validate(&text)?;
+                let width = modulus.to_string().len();
+                check_length(text.len(), width)?;
+                text.as_bytes()
+                    .chunks(width)
+                    .map(|chunk| parse_number(std::str::from_utf8(chunk)?, "Фраза"))
+                    .collect()
+            },
+            _ => {
+                let bytes = self.decode(text)?;
+                let width = byte_width(modulus);
+                check_length(bytes.len(), width)?;
+                Ok(bytes.chunks(width)
+                    .map(|chunk| chunk.iter().fold(0, |number, byte| number << 8 | *byte as usize))
+                    .collect())
+            }
+        }
+    }
+
+    fn decode_with(&self, encoding: &data_encoding::Encoding, text: &str) -> Result<Vec<u8>, CipherError> {
+        encoding.decode(text.as_bytes()).map_err(|_| CipherError::InvalidEncoding(*self))
+    }
+
+    fn decode_groups(&self, text: &str, width: usize, radix: u32) -> Result<Vec<u8>, CipherError> {
+        if !text.len().is_multiple_of(width) || !text.chars().all(|letter| letter.is_digit(radix)) {
+            Err(CipherError::InvalidEncoding(*self))?;
+        }
+        text.as_bytes()
+            .chunks(width)
+            .map(|chunk| {
+                let chunk = std::str::from_utf8(chunk)?;
+                u8::from_str_radix(chunk, radix).map_err(|_| CipherError::InvalidEncoding(*self))
+            })
+            .collect()
+    }
+}
+
+fn strip(text: &str) -> String {
+    text.chars().filter(|letter| !letter.is_whitespace()).collect()
+}
+
+fn byte_width(modulus: usize) -> usize {
+    let bits = usize::BITS - modulus.saturating_sub(1).leading_zeros();
+    (bits as usize).div_ceil(8).max(1)
+}
+
+fn check_length(len: usize, width: usize) -> Result<(), CipherError> {
+    if !len.is_multiple_of(width) {
+        Err(CipherError::invalid_size("Фраза", "Длина шифртекста должна быть кратна длине блока"))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod encoding_tests {
+    use super::*;
+
+    #[test]
+    fn test_encode() {
+        let bytes = b"Hi?";
+        assert_eq!(Encoding::HexLower.encode(&[0xab, 0x01]), "ab01");
+        assert_eq!(Encoding::HexUpper.encode(&[0xab, 0x01]), "AB01");
+        assert_eq!(Encoding::Base64.encode(&[0xfb, 0xff]), "+/8=");
+        assert_eq!(Encoding::Base64Url.encode(&[0xfb, 0xff]), "-_8=");
+        assert_eq!(Encoding::Base32.encode(bytes), "JBUT6===");
+        assert_eq!(Encoding::Binary.encode(bytes), "010010000110100100111111");
+        assert_eq!(Encoding::Decimal.encode(bytes), "072105063");
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let bytes: Vec<u8> = (0..=255).collect();
+        for encoding in Encoding::ALL {
+            assert_eq!(encoding.decode(&encoding.encode(&bytes)).unwrap(), bytes, "{}", encoding.name());
+        }
+    }
+
+    #[test]
+    fn test_decode_errors() {
+        assert!(matches!(Encoding::HexLower.decode("zz"), Err(CipherError::InvalidHex(_))));
+        assert_eq!(Encoding::Base64.decode("-_8="), Err(CipherError::InvalidEncoding(Encoding::Base64)));
+        assert_eq!(Encoding::Binary.decode("0102"), Err(CipherError::InvalidEncoding(Encoding::Binary)));
+        assert_eq!(Encoding::Decimal.decode("256"), Err(CipherError::InvalidEncoding(Encoding::Decimal)));
+        assert_eq!(Encoding::Decimal.decode("07 2 105").unwrap(), b"Hi");
+    }
+
+    #[test]
+    fn test_numbers() {
+        let numbers = [64, 17, 5];
+        assert_eq!(Encoding::Decimal.encode_numbers(&numbers, 77), "641705");
+        assert_eq!(Encoding::HexLower.encode_numbers(&numbers, 77), "401105");
+        assert_eq!(Encoding::HexLower.encode_numbers(&[300], 1000), "012c");
+        for encoding in Encoding::ALL {
+            let text = encoding.encode_numbers(&numbers, 77);
+            assert_eq!(encoding.decode_numbers(&text, 77).unwrap(), numbers);
+        }
+        assert!(matches!(
+            Encoding::Decimal.decode_numbers("64170", 77),
+            Err(CipherError::InvalidSize { param: "Фраза", .. })
+        ));
+    }
+}
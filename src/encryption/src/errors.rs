@@ -1,95 +1,132 @@
 use std::error::Error;
 use std::fmt::Display;
-
-#[derive(Debug, Clone)]
-pub struct InvalidTextError;
-
-#[derive(Debug, Clone)]
-pub struct InvalidKeyError {
-    pub message: String
-}
-
-#[derive(Debug, Clone)]
-pub struct NullSizedValue {
-    pub message: String
-}
-
-#[derive(Debug, Clone)]
-pub struct InvalidSize {
-    pub message: String
+use std::num::ParseIntError;
+use std::str::Utf8Error;
+use hex::FromHexError;
+use crate::encoding::Encoding;
+
+/// Error returned by every fallible function of the crate.
+///
+/// `param` fields hold the name of the offending parameter the way it is
+/// shown to the user, e.g. `"Фраза"` or `"Ключ"`.
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub enum CipherError {
+    /// Required value is empty
+    EmptyValue { param: &'static str },
+    /// Value contains a letter missing from the alphabet
+    InvalidCharacter { param: &'static str, letter: char, position: usize },
+    /// Key does not satisfy the requirements of the algorithm
+    InvalidKey { param: &'static str, reason: String },
+    /// Value has an unsupported length, dimensions or magnitude
+    InvalidSize { param: &'static str, reason: String },
+    /// Coordinate points outside of the table
+    OutOfBounds { param: &'static str, value: usize },
+    /// Element is missing at the computed index
+    InvalidIndex,
+    /// Value is not a valid number
+    InvalidNumber { param: &'static str, source: ParseIntError },
+    /// Value is not a valid hex string
+    InvalidHex(FromHexError),
+    /// Value can not be decoded with the given encoding
+    InvalidEncoding(Encoding),
+    /// Decrypted bytes are not a valid UTF-8 string
+    InvalidUtf8(Utf8Error),
+    /// Signature can not be parsed
+    InvalidSignature,
+    /// Algorithm does not support the requested operation
+    UnsupportedOperation,
+    /// Counter of the CTR mode would wrap around and repeat the gamma
+    CounterOverflow,
+    /// Padding of the decrypted data is malformed
+    InvalidPadding,
+    /// Authentication tag does not match the data
+    InvalidTag,
 }
 
-#[derive(Debug, Clone)]
-pub struct OutOfBounds {
-    pub message: String
-}
-
-#[derive(Debug, Clone)]
-pub struct InvalidIndex;
-
-impl InvalidKeyError {
-    pub fn new(message: &str) -> Self { InvalidKeyError {
-        message: message.to_owned()
-    } }
-}
-
-impl OutOfBounds {
-    pub fn new(message: &str) -> Self { OutOfBounds {
-        message: message.to_owned()
-    } }
-}
-
-impl InvalidSize {
-    pub fn new(message: &str) -> Self { InvalidSize {
-        message: message.to_owned()
-    } }
-}
+impl CipherError {
+    pub fn invalid_key(param: &'static str, reason: &str) -> Self {
+        CipherError::InvalidKey { param, reason: reason.to_owned() }
+    }
 
-impl NullSizedValue {
-    pub fn new(message: &str) -> Self { NullSizedValue {
-        message: message.to_owned()
-    } }
+    pub fn invalid_size(param: &'static str, reason: &str) -> Self {
+        CipherError::InvalidSize { param, reason: reason.to_owned() }
+    }
 }
 
-impl Display for InvalidTextError {
+impl Display for CipherError {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(f, "Алфавит не содержит такой буквы")
+        match self {
+            CipherError::EmptyValue { param } => write!(f, "{param} отсутствует"),
+            CipherError::InvalidCharacter { param, letter, position } => write!(
+                f, "{param}: алфавит не содержит буквы '{letter}' (позиция {position})"
+            ),
+            CipherError::InvalidKey { reason, .. } => write!(f, "{reason}"),
+            CipherError::InvalidSize { reason, .. } => write!(f, "{reason}"),
+            CipherError::OutOfBounds { param, value } => write!(
+                f, "Указанный {param} {value} выходит за границы таблицы"
+            ),
+            CipherError::InvalidIndex => write!(f, "По такому индексу не найдено элемента"),
+            CipherError::InvalidNumber { param, .. } => write!(f, "{param} не является числом"),
+            CipherError::InvalidHex(_) => write!(f, "Строка не является шестнадцатеричной"),
+            CipherError::InvalidEncoding(encoding) => write!(
+                f, "Строка не соответствует кодировке {}", encoding.name()
+            ),
+            CipherError::InvalidUtf8(_) => write!(f, "Результат не является строкой UTF-8"),
+            CipherError::InvalidSignature => write!(f, "Некорректный формат подписи"),
+            CipherError::UnsupportedOperation => write!(f, "Алгоритм не поддерживает операцию"),
+            CipherError::CounterOverflow => write!(f, "Счетчик переполнен, данные слишком длинные"),
+            CipherError::InvalidPadding => write!(f, "Некорректное дополнение расшифрованных данных"),
+            CipherError::InvalidTag => write!(f, "Имитовставка не совпадает, данные были изменены"),
+        }
     }
 }
 
-impl Display for InvalidIndex {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(f, "По такому индексу не найдено элемента")
+impl Error for CipherError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            CipherError::InvalidNumber { source, .. } => Some(source),
+            CipherError::InvalidHex(source) => Some(source),
+            CipherError::InvalidUtf8(source) => Some(source),
+            _ => None
+        }
     }
 }
 
-impl Display for InvalidKeyError {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(f, "{}", self.message)
+impl From<FromHexError> for CipherError {
+    fn from(value: FromHexError) -> Self {
+        CipherError::InvalidHex(value)
     }
 }
 
-impl Display for InvalidSize {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(f, "{}", self.message)
+impl From<Utf8Error> for CipherError {
+    fn from(value: Utf8Error) -> Self {
+        CipherError::InvalidUtf8(value)
     }
 }
 
-impl Display for NullSizedValue {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(f, "{} отсутствует", self.message)
+#[cfg(test)]
+mod errors_tests {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        assert_eq!(CipherError::EmptyValue { param: "Фраза" }.to_string(), "Фраза отсутствует");
+        assert_eq!(
+            CipherError::InvalidCharacter { param: "Фраза", letter: 'q', position: 2 }.to_string(),
+            "Фраза: алфавит не содержит буквы 'q' (позиция 2)"
+        );
+        assert_eq!(
+            CipherError::OutOfBounds { param: "ряд", value: 7 }.to_string(),
+            "Указанный ряд 7 выходит за границы таблицы"
+        );
     }
-}
 
-impl Display for OutOfBounds {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(f, "Указанный {} выходит за границы таблицы", self.message)
+    #[test]
+    fn test_source() {
+        let err: CipherError = hex::decode("zz").unwrap_err().into();
+        assert!(matches!(err, CipherError::InvalidHex(_)));
+        assert!(err.source().is_some());
+        assert!(CipherError::InvalidIndex.source().is_none());
     }
 }
-
-impl Error for InvalidTextError {}
-impl Error for InvalidKeyError {}
-impl Error for NullSizedValue {}
-impl Error for InvalidIndex {}
-impl Error for InvalidSize {}
-impl Error for OutOfBounds {}
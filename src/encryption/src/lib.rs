@@ -5,16 +5,33 @@
 
 mod alphabet;
 mod asymmetric;
+mod cipher;
 pub mod diffie_hellman;
 mod digital_signature;
+mod encoding;
 mod errors;
 pub mod methods;
+mod normalizer;
+mod options;
+pub mod registry;
+pub mod schema;
+mod secret;
 mod symmetric;
 
 extern crate hex;
 extern crate itertools;
 extern crate num;
 
+pub use cipher::Cipher;
+pub use symmetric::combinational::BlockCipher;
+pub use symmetric::combinational::padding::Padding;
+pub use alphabet::Alphabet;
+pub use encoding::Encoding;
+pub use errors::CipherError;
+pub use digital_signature::Signature;
+pub use normalizer::Normalizer;
+pub use options::TextOptions;
+pub use secret::Secret;
 pub use asymmetric::ecc;
 pub use asymmetric::elgamal;
 pub use asymmetric::rsa;
@@ -24,9 +41,19 @@ pub use digital_signature::standarts::gost_r_34_10_2012;
 pub use digital_signature::standarts::gost_r_34_10_94;
 pub use symmetric::block::matrix;
 pub use symmetric::block::playfair;
+pub use symmetric::combinational::aead;
 pub use symmetric::combinational::aes;
+pub use symmetric::combinational::ct;
+pub use symmetric::combinational::des;
+pub use symmetric::combinational::feistel;
+pub use symmetric::combinational::gost28147;
+pub use symmetric::combinational::keywrap;
 pub use symmetric::combinational::kuznechik;
+pub use symmetric::combinational::mac;
 pub use symmetric::combinational::magma;
+pub use symmetric::combinational::modes;
+pub use symmetric::combinational::stream;
+pub use symmetric::combinational::triple_des;
 pub use symmetric::gamma::shenon;
 pub use symmetric::inline::a5_1;
 pub use symmetric::inline::a5_2;
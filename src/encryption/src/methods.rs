@@ -1,8 +1,10 @@
-use std::error::Error;
+use crate::errors::CipherError;
 use hex::{encode, decode};
+use std::num::ParseIntError;
 use std::str;
+use std::str::FromStr;
 use crate::alphabet::Alphabet;
-use crate::errors::NullSizedValue;
+use crate::symmetric::combinational::padding::Padding;
 
 pub fn modd(num: isize, limit: usize) -> usize {
     let limit = limit as isize;
@@ -17,7 +19,7 @@ pub fn modd(num: isize, limit: usize) -> usize {
     }
 }
 
-pub fn hex_to_str(text: &str) -> Result<String, Box<dyn Error>> {
+pub fn hex_to_str(text: &str) -> Result<String, CipherError> {
     bytes_to_string(&hex_to_bytes(text, 1)?)
 }
 
@@ -25,21 +27,29 @@ pub fn str_to_hex(text: &str, border: usize) -> String {
     bytes_to_hex(&str_to_bytes(text, border).unwrap())
 }
 
-pub fn str_to_bytes(text: &str, border: usize) -> Result<Vec<u8>, Box<dyn Error>> {
+pub fn str_to_bytes(text: &str, border: usize) -> Result<Vec<u8>, CipherError> {
     let bytes = hex_to_bytes(&encode(text), border)?;
     Ok(bytes)
 }
 
-pub fn bytes_to_string(buffer: &[u8]) -> Result<String, Box<dyn Error>> {
-    let result = str::from_utf8(buffer)?.to_owned();
-    Ok(result.trim_matches('\u{0000}').to_owned())
+/// Text completed by `padding` to a multiple of `size` bytes
+pub fn str_to_padded_hex(text: &str, padding: Padding, size: usize) -> Result<String, CipherError> {
+    Ok(bytes_to_hex(&padding.pad(text.as_bytes(), size)?))
+}
+
+pub fn padded_hex_to_str(text: &str, padding: Padding, size: usize) -> Result<String, CipherError> {
+    bytes_to_string(&padding.unpad(&hex_to_bytes(text, 1)?, size)?)
+}
+
+pub fn bytes_to_string(buffer: &[u8]) -> Result<String, CipherError> {
+    Ok(str::from_utf8(buffer)?.to_owned())
 }
 
 pub fn bytes_to_hex(buffer: &[u8]) -> String {
     encode(buffer)
 }
 
-pub fn hex_to_bytes(text: &str, border: usize) -> Result<Vec<u8>, Box<dyn Error>> {
+pub fn hex_to_bytes(text: &str, border: usize) -> Result<Vec<u8>, CipherError> {
     let mut bytes_vec = decode(text)?;
     let null_count = bytes_vec.len() % border;
     if null_count > 0 {
@@ -49,26 +59,33 @@ pub fn hex_to_bytes(text: &str, border: usize) -> Result<Vec<u8>, Box<dyn Error>
     Ok(bytes_vec)
 }
 
-pub fn validate_single(alphabet: &Alphabet, phrase: &str) -> Result<(), Box<dyn Error>> {
-    if phrase.is_empty() { Err(NullSizedValue::new("Фраза"))?; }
+pub fn validate_single(alphabet: &Alphabet, phrase: &str) -> Result<(), CipherError> {
+    if phrase.is_empty() { Err(CipherError::EmptyValue { param: "Фраза" })?; }
     alphabet.validate(phrase)
 }
 
-pub fn validate_two(alphabet: &Alphabet, text: &str, key: &str) -> Result<(), Box<dyn Error>> {
-    if text.is_empty() { Err(NullSizedValue::new("Фраза"))?; }
-    if key.is_empty() { Err(NullSizedValue::new("Ключ"))?; }
+pub fn validate_two(alphabet: &Alphabet, text: &str, key: &str) -> Result<(), CipherError> {
+    if text.is_empty() { Err(CipherError::EmptyValue { param: "Фраза" })?; }
+    if key.is_empty() { Err(CipherError::EmptyValue { param: "Ключ" })?; }
     alphabet.validate(text)?;
-    alphabet.validate(key)
+    alphabet.validate_param(key, "Ключ")
 }
 
-pub fn transform(val: &str, message: &str) -> Result<isize, Box<dyn Error>> {
-    if val.is_empty() { Err(NullSizedValue::new(message))?;}
-   Ok(val.parse::<isize>()?)
+pub fn transform(val: &str, message: &'static str) -> Result<isize, CipherError> {
+    if val.is_empty() { Err(CipherError::EmptyValue { param: message })?;}
+    parse_number(val, message)
+}
+
+pub fn parse_number<T>(val: &str, param: &'static str) -> Result<T, CipherError>
+    where T: FromStr<Err = ParseIntError>
+{
+    val.parse().map_err(|source| CipherError::InvalidNumber { param, source })
 }
 
 #[cfg(test)]
 mod method_tests {
-    use crate::methods::{bytes_to_hex, bytes_to_string, hex_to_bytes, str_to_bytes};
+    use crate::methods::{bytes_to_hex, bytes_to_string, hex_to_bytes, padded_hex_to_str, str_to_bytes, str_to_padded_hex};
+    use crate::symmetric::combinational::padding::Padding;
 
     #[test]
     fn test_bytes_to_hex() {
@@ -98,8 +115,16 @@ mod method_tests {
     fn test_bytes_to_string() {
         let test: Vec<u8> = vec![208, 176, 0, 0];
         let res = bytes_to_string(&test).unwrap();
-        let valid = String::from("а");
+        let valid = String::from("а\0\0");
         assert_eq!(res, valid);
+        assert_eq!(bytes_to_string(&[0, 208, 176]).unwrap(), "\0а");
+    }
+
+    #[test]
+    fn test_padded_hex() {
+        let hex = str_to_padded_hex("а\0", Padding::Pkcs7, 4).unwrap();
+        assert_eq!(hex, "d0b00001");
+        assert_eq!(padded_hex_to_str(&hex, Padding::Pkcs7, 4).unwrap(), "а\0");
     }
 
     #[test]
@@ -0,0 +1,99 @@
+/// Reversible preparation of a text for the classical ciphers.
+///
+/// Substitutions replace characters missing from the alphabet with letter
+/// combinations and are undone by `denormalize`, folds merge letters
+/// (e.g. ё into е) and can not be undone.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Normalizer {
+    substitutions: Vec<(String, String)>,
+    folds: Vec<(char, char)>,
+}
+
+impl Normalizer {
+    pub fn new() -> Self {
+        Normalizer::default()
+    }
+
+    /// Traditional Russian substitutions: тчк, зпт, тире and прб
+    pub fn russian() -> Self {
+        Normalizer::new()
+            .with_substitution(".", "тчк")
+            .with_substitution(",", "зпт")
+            .with_substitution("-", "тире")
+            .with_substitution(" ", "прб")
+    }
+
+    /// Adds a substitution or replaces the existing one for `from`,
+    /// an empty `to` removes `from` from the text
+    pub fn with_substitution(mut self, from: &str, to: &str) -> Self {
+        match self.substitutions.iter_mut().find(|(key, _)| key == from) {
+            Some(substitution) => substitution.1 = to.to_owned(),
+            None => self.substitutions.push((from.to_owned(), to.to_owned()))
+        }
+        self
+    }
+
+    pub fn with_fold(mut self, from: char, to: char) -> Self {
+        self.folds.retain(|(key, _)| *key != from);
+        self.folds.push((from, to));
+        self
+    }
+
+    pub fn fold_yo(self) -> Self {
+        self.with_fold('ё', 'е').with_fold('Ё', 'Е')
+    }
+
+    pub fn fold_short_i(self) -> Self {
+        self.with_fold('й', 'и').with_fold('Й', 'И')
+    }
+
+    pub fn fold(&self, letter: char) -> char {
+        self.folds.iter().find(|(from, _)| *from == letter).map_or(letter, |(_, to)| *to)
+    }
+
+    pub fn normalize(&self, text: &str) -> String {
+        let result = self.substitutions.iter()
+            .fold(text.to_owned(), |result, (from, to)| result.replace(from, to));
+        result.chars().map(|letter| self.fold(letter)).collect()
+    }
+
+    pub fn denormalize(&self, text: &str) -> String {
+        self.substitutions.iter()
+            .filter(|(_, to)| !to.is_empty())
+            .fold(text.to_owned(), |result, (from, to)| result.replace(to, from))
+    }
+}
+
+#[cfg(test)]
+mod normalizer_tests {
+    use super::*;
+
+    #[test]
+    fn test_russian() {
+        let normalizer = Normalizer::russian();
+        assert_eq!(normalizer.normalize("да, нет - ну."), "дазптпрбнетпрбтирепрбнутчк");
+        assert_eq!(normalizer.denormalize("дазптпрбнетпрбтирепрбнутчк"), "да, нет - ну.");
+    }
+
+    #[test]
+    fn test_with_substitution() {
+        let normalizer = Normalizer::russian().with_substitution(" ", "");
+        assert_eq!(normalizer.normalize("да, нет."), "дазптнеттчк");
+        assert_eq!(normalizer.denormalize("дазптнеттчк"), "да,нет.");
+    }
+
+    #[test]
+    fn test_folds() {
+        let normalizer = Normalizer::new().fold_yo().fold_short_i();
+        assert_eq!(normalizer.normalize("Ёжик йод"), "Ежик иод");
+        assert_eq!(normalizer.denormalize("Ежик иод"), "Ежик иод");
+        assert_eq!(normalizer.fold('ё'), 'е');
+        assert_eq!(normalizer.fold('а'), 'а');
+    }
+
+    #[test]
+    fn test_with_fold() {
+        let normalizer = Normalizer::new().with_fold('ь', 'ъ').with_fold('ь', 'б');
+        assert_eq!(normalizer.normalize("ь"), "б");
+    }
+}
@@ -0,0 +1,112 @@
+use crate::alphabet::Alphabet;
+use crate::errors::CipherError;
+
+/// Per-call text handling of the length preserving classical ciphers.
+///
+/// Supported by `atbash`, `caesar`, `trithemium`, `belazo`, `vigenere`,
+/// `shenon` and `vertical`. Ciphers changing the length of the text
+/// (`polybius`, `matrix`, `playfair`, `cardano`) have no such mode.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct TextOptions {
+    /// Characters missing from the alphabet are copied to the output unchanged
+    pub pass_through: bool,
+    /// Letters missing from the alphabet are looked up in lower case
+    /// and their case is restored on output
+    pub preserve_case: bool,
+}
+
+enum Slot {
+    Letter { upper: bool },
+    Other(char),
+}
+
+impl TextOptions {
+    /// Extracts the letters of the alphabet from `phrase`, passes them to `func`
+    /// and puts the result back in place of the original letters
+    pub(crate) fn apply<F>(&self, alphabet: &Alphabet, phrase: &str, func: F) -> Result<String, CipherError>
+        where F: FnOnce(&str) -> Result<String, CipherError>
+    {
+        let mut letters = String::new();
+        let mut slots = Vec::new();
+        for (position, letter) in phrase.chars().enumerate() {
+            if alphabet.contains(letter) {
+                letters.push(letter);
+                slots.push(Slot::Letter { upper: false });
+                continue;
+            }
+            let lower = self.lower(letter).filter(|lower| alphabet.contains(*lower));
+            if let Some(lower) = lower {
+                letters.push(lower);
+                slots.push(Slot::Letter { upper: true });
+            } else if self.pass_through {
+                slots.push(Slot::Other(letter));
+            } else {
+                Err(CipherError::InvalidCharacter { param: "Фраза", letter, position })?;
+            }
+        }
+        let processed = func(&letters)?;
+        let mut processed = processed.chars();
+        let mut result = String::new();
+        for slot in slots {
+            match slot {
+                Slot::Other(letter) => result.push(letter),
+                Slot::Letter { upper } => {
+                    let letter = processed.next().ok_or(CipherError::InvalidIndex)?;
+                    if upper { result.extend(letter.to_uppercase()); } else { result.push(letter); }
+                }
+            }
+        }
+        result.extend(processed);
+        Ok(result)
+    }
+
+    fn lower(&self, letter: char) -> Option<char> {
+        if !self.preserve_case { return None; }
+        let mut lower = letter.to_lowercase();
+        match (lower.next(), lower.next()) {
+            (Some(lower), None) if lower != letter => Some(lower),
+            _ => None
+        }
+    }
+}
+
+#[cfg(test)]
+mod options_tests {
+    use super::*;
+
+    fn reverse(letters: &str) -> Result<String, CipherError> {
+        Ok(letters.chars().rev().collect())
+    }
+
+    #[test]
+    fn test_apply() {
+        let alphabet = Alphabet::new();
+        let options = TextOptions { pass_through: true, preserve_case: true };
+        assert_eq!(options.apply(&alphabet, "Да, нет!", reverse).unwrap(), "Те, над!");
+    }
+
+    #[test]
+    fn test_apply_pass_through() {
+        let alphabet = Alphabet::new();
+        let options = TextOptions { pass_through: true, preserve_case: false };
+        assert_eq!(options.apply(&alphabet, "Да, нет!", reverse).unwrap(), "Дт, ена!");
+    }
+
+    #[test]
+    fn test_apply_preserve_case() {
+        let alphabet = Alphabet::new();
+        let options = TextOptions { pass_through: false, preserve_case: true };
+        assert_eq!(options.apply(&alphabet, "ДаНет", reverse).unwrap(), "ТеНад");
+        assert_eq!(
+            options.apply(&alphabet, "Да нет", reverse),
+            Err(CipherError::InvalidCharacter { param: "Фраза", letter: ' ', position: 2 })
+        );
+    }
+
+    #[test]
+    fn test_apply_default() {
+        let alphabet = Alphabet::new();
+        assert!(TextOptions::default().apply(&alphabet, "Да", reverse).is_err());
+        assert_eq!(TextOptions::default().apply(&alphabet, "да", reverse).unwrap(), "ад");
+    }
+}
@@ -0,0 +1,731 @@
+use crate::errors::CipherError;
+use crate::methods::{bytes_to_hex, hex_to_bytes, parse_number, transform};
+use crate::alphabet::RUSSIAN_LEN;
+use crate::ecc::Point;
+use crate::digital_signature::Signature;
+use crate::schema::{validate, Constraint, Param, ParamKind};
+use crate::{
+    a5_1, a5_2, aes, atbash, belazo, caesar, cardano, des, diffie_hellman, ecc, egsa, elgamal, feistel,
+    gost_r_34_10_2012, gost_r_34_10_94, kuznechik, magma, matrix, playfair, polybius, rsa,
+    rsa_sign, shenon, trithemium, triple_des, vertical, vigenere
+};
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Family {
+    MonoAlphabetic,
+    MultiAlphabetic,
+    Block,
+    Transposition,
+    Combinational,
+    Gamma,
+    Stream,
+    Asymmetric,
+    Signature,
+    KeyExchange,
+}
+
+impl Family {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Family::MonoAlphabetic => "Моноалфавитные",
+            Family::MultiAlphabetic => "Многоалфавитные",
+            Family::Block => "Блочные",
+            Family::Transposition => "Перестановочные",
+            Family::Combinational => "Комбинационные",
+            Family::Gamma => "Гаммирование",
+            Family::Stream => "Поточные",
+            Family::Asymmetric => "Асимметричные",
+            Family::Signature => "Цифровая подпись",
+            Family::KeyExchange => "Обмен ключами",
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Operation {
+    Encrypt,
+    Decrypt,
+    Sign,
+    /// Returns `true` or `false`
+    Verify,
+    /// Returns the shared key, the phrase is ignored
+    Exchange,
+}
+
+/// Entry point receiving the values of all `params` in order
+pub type Dispatch = fn(Operation, &str, &[&str]) -> Result<String, CipherError>;
+
+#[derive(Copy, Clone, Debug)]
+pub struct AlgorithmInfo {
+    pub id: &'static str,
+    pub name: &'static str,
+    pub family: Family,
+    pub operations: &'static [Operation],
+    pub params: &'static [Param],
+    pub dispatch: Dispatch,
+}
+
+impl AlgorithmInfo {
+    pub fn run(&self, operation: Operation, phrase: &str, args: &[&str]) -> Result<String, CipherError> {
+        if !self.operations.contains(&operation) {
+            Err(CipherError::UnsupportedOperation)?;
+        }
+        if let Err(mut errors) = self.validate(operation, args) {
+            Err(errors.swap_remove(0))?;
+        }
+        (self.dispatch)(operation, phrase, args)
+    }
+
+    pub fn param(&self, name: &str) -> Option<&'static Param> {
+        self.params.iter().find(|param| param.name == name)
+    }
+
+    /// Returns every violation of the parameter schema at once
+    pub fn validate(&self, operation: Operation, args: &[&str]) -> Result<(), Vec<CipherError>> {
+        validate(self.params, operation, args)
+    }
+}
+
+/// Every algorithm of the crate in the order of the application sidebar
+pub fn algorithms() -> &'static [AlgorithmInfo] {
+    ALGORITHMS
+}
+
+pub fn find(id: &str) -> Option<&'static AlgorithmInfo> {
+    ALGORITHMS.iter().find(|info| info.id == id)
+}
+
+const CIPHER: &[Operation] = &[Operation::Encrypt, Operation::Decrypt];
+const SIGNATURE: &[Operation] = &[Operation::Sign, Operation::Verify];
+
+const ENCRYPT: &[Operation] = &[Operation::Encrypt];
+const DECRYPT: &[Operation] = &[Operation::Decrypt];
+const SIGN_ONLY: &[Operation] = &[Operation::Sign];
+const VERIFY: &[Operation] = &[Operation::Verify];
+
+const fn param(name: &'static str, kind: ParamKind) -> Param {
+    Param::new(name, kind)
+}
+
+const fn integer(name: &'static str) -> Param {
+    Param::new(name, ParamKind::Number)
+}
+
+const KEY_TEXT: Param = param("Ключ", ParamKind::Text);
+const KEY_HEX: Param = param("Ключ", ParamKind::Hex);
+const KEY_AES: Param = KEY_HEX.with(&[Constraint::Lengths(&[32, 48, 64])]);
+const KEY_256: Param = KEY_HEX.with(&[Constraint::Length(64)]);
+const KEY_DES: Param = KEY_HEX.with(&[Constraint::Length(16)]);
+const KEY_TRIPLE_DES: Param = KEY_HEX.with(&[Constraint::Lengths(&[32, 48])]);
+const POSITIVE: &[Constraint] = &[Constraint::Min(1)];
+const HASH_MODULUS: Param = integer("Модуль хэша").with(&[Constraint::Min(2)]);
+const SIGN: Param = param("Подпись", ParamKind::Signature).only(VERIFY);
+const CURVE: [Param; 9] = [
+    integer("a"),
+    integer("b"),
+    integer("p").with(&[Constraint::Min(2)]),
+    integer("Gx").with(&[Constraint::Min(0)]),
+    integer("Gy").with(&[Constraint::Min(0)]),
+    integer("Dbx").with(&[Constraint::Min(0)]).only(&[Operation::Encrypt, Operation::Verify]),
+    integer("Dby").with(&[Constraint::Min(0)]).only(&[Operation::Encrypt, Operation::Verify]),
+    integer("q").with(&[Constraint::Min(2)]),
+    integer("Cb").with(POSITIVE).only(&[Operation::Decrypt, Operation::Sign]),
+];
+
+static ALGORITHMS: &[AlgorithmInfo] = &[
+    AlgorithmInfo {
+        id: "atbash", name: "Атбаш", family: Family::MonoAlphabetic,
+        operations: CIPHER, params: &[], dispatch: run_atbash
+    },
+    AlgorithmInfo {
+        id: "caesar", name: "Цезарь", family: Family::MonoAlphabetic,
+        operations: CIPHER, params: &[integer("Ключ")], dispatch: run_caesar
+    },
+    AlgorithmInfo {
+        id: "polybius", name: "Полибий", family: Family::MonoAlphabetic, operations: CIPHER,
+        params: &[
+            integer("Строки").with(&[Constraint::Range { min: 1, max: 9 }]),
+            integer("Столбцы").with(&[Constraint::Range { min: 1, max: 9 }]),
+        ],
+        dispatch: run_polybius
+    },
+    AlgorithmInfo {
+        id: "trithemium", name: "Тритемий", family: Family::MultiAlphabetic,
+        operations: CIPHER, params: &[], dispatch: run_trithemium
+    },
+    AlgorithmInfo {
+        id: "belazo", name: "Белазо", family: Family::MultiAlphabetic,
+        operations: CIPHER, params: &[KEY_TEXT], dispatch: run_belazo
+    },
+    AlgorithmInfo {
+        id: "vigenere", name: "Виженер", family: Family::MultiAlphabetic,
+        operations: CIPHER, params: &[KEY_TEXT], dispatch: run_vigenere
+    },
+    AlgorithmInfo {
+        id: "s_table", name: "S таблица", family: Family::Combinational,
+        operations: CIPHER, params: &[], dispatch: run_s_table
+    },
+    AlgorithmInfo {
+        id: "matrix", name: "Матричный", family: Family::Block, operations: CIPHER,
+        params: &[param("Матрица", ParamKind::Matrix)], dispatch: run_matrix
+    },
+    AlgorithmInfo {
+        id: "playfair", name: "Плейфер", family: Family::Block,
+        operations: CIPHER, params: &[KEY_TEXT], dispatch: run_playfair
+    },
+    AlgorithmInfo {
+        id: "vertical", name: "Вертикальный", family: Family::Transposition,
+        operations: CIPHER, params: &[KEY_TEXT], dispatch: run_vertical
+    },
+    AlgorithmInfo {
+        id: "cardano", name: "Кардано", family: Family::Transposition, operations: CIPHER,
+        params: &[param("Решетка", ParamKind::Grid)], dispatch: run_cardano
+    },
+    AlgorithmInfo {
+        id: "feistel", name: "Сеть Фейстеля", family: Family::Combinational, operations: CIPHER,
+        params: &[
+            KEY_HEX,
+            integer("Размер блока").with(&[
+                Constraint::Range { min: 2, max: 16 },
+                Constraint::Congruent { modulus: 2, remainder: 0 },
+            ]),
+            integer("Число раундов").with(&[Constraint::Range { min: 1, max: 64 }]),
+            integer("Раундовая функция").with(&[Constraint::Range { min: 0, max: 2 }]),
+            integer("Ключевое расписание").with(&[Constraint::Range { min: 0, max: 2 }]),
+            integer("Сдвиг").with(&[Constraint::Min(0)]),
+        ],
+        dispatch: run_feistel
+    },
+    AlgorithmInfo {
+        id: "shenon", name: "Шеннон", family: Family::Gamma, operations: CIPHER,
+        params: &[
+            integer("T0").with(&[Constraint::Range { min: 1, max: RUSSIAN_LEN as isize }]),
+            integer("a").with(&[Constraint::Congruent { modulus: 4, remainder: 1 }]),
+            integer("c").with(&[Constraint::Odd]),
+        ],
+        dispatch: run_shenon
+    },
+    AlgorithmInfo {
+        id: "ctr_magma", name: "CTR Магма", family: Family::Gamma, operations: CIPHER,
+        params: &[KEY_256, param("Вектор инициализации", ParamKind::Hex)],
+        dispatch: run_ctr_magma
+    },
+    AlgorithmInfo {
+        id: "a5_1", name: "A5/1", family: Family::Stream,
+        operations: CIPHER, params: &[KEY_HEX], dispatch: run_a5_1
+    },
+    AlgorithmInfo {
+        id: "a5_2", name: "A5/2", family: Family::Stream,
+        operations: CIPHER, params: &[KEY_HEX], dispatch: run_a5_2
+    },
+    AlgorithmInfo {
+        id: "magma", name: "Магма", family: Family::Combinational,
+        operations: CIPHER, params: &[KEY_256], dispatch: run_magma
+    },
+    AlgorithmInfo {
+        id: "des", name: "DES", family: Family::Combinational,
+        operations: CIPHER, params: &[KEY_DES], dispatch: run_des
+    },
+    AlgorithmInfo {
+        id: "triple_des", name: "3DES", family: Family::Combinational,
+        operations: CIPHER, params: &[KEY_TRIPLE_DES], dispatch: run_triple_des
+    },
+    AlgorithmInfo {
+        id: "aes", name: "AES", family: Family::Combinational,
+        operations: CIPHER, params: &[KEY_AES], dispatch: run_aes
+    },
+    AlgorithmInfo {
+        id: "kuznechik", name: "Кузнечик", family: Family::Combinational,
+        operations: CIPHER, params: &[KEY_256], dispatch: run_kuznechik
+    },
+    AlgorithmInfo {
+        id: "rsa", name: "RSA", family: Family::Asymmetric, operations: CIPHER,
+        params: &[
+            integer("n").with(&[Constraint::Min(33)]),
+            integer("e").with(POSITIVE).only(ENCRYPT),
+            integer("d").with(POSITIVE).only(DECRYPT),
+        ],
+        dispatch: run_rsa
+    },
+    AlgorithmInfo {
+        id: "elgamal", name: "Elgamal", family: Family::Asymmetric, operations: CIPHER,
+        params: &[
+            integer("p").with(&[Constraint::Min(33)]),
+            integer("g").with(&[Constraint::Min(2)]).only(ENCRYPT),
+            integer("y").with(POSITIVE).only(ENCRYPT),
+            integer("x").with(&[Constraint::Min(2)]).only(DECRYPT),
+        ],
+        dispatch: run_elgamal
+    },
+    AlgorithmInfo {
+        id: "ecc", name: "ECC", family: Family::Asymmetric,
+        operations: CIPHER, params: &CURVE, dispatch: run_ecc
+    },
+    AlgorithmInfo {
+        id: "rsa_sign", name: "ЭЦП RSA", family: Family::Signature, operations: SIGNATURE,
+        params: &[
+            integer("n").with(&[Constraint::Min(2)]),
+            integer("e").with(POSITIVE).only(VERIFY),
+            integer("d").with(POSITIVE).only(SIGN_ONLY),
+            HASH_MODULUS,
+            integer("Подпись").only(VERIFY),
+        ],
+        dispatch: run_rsa_sign
+    },
+    AlgorithmInfo {
+        id: "egsa", name: "EGSA", family: Family::Signature, operations: SIGNATURE,
+        params: &[
+            integer("p").with(&[Constraint::Min(3)]),
+            integer("g").with(&[Constraint::Min(2)]),
+            integer("x").with(POSITIVE).only(SIGN_ONLY),
+            integer("y").with(POSITIVE).only(VERIFY),
+            HASH_MODULUS,
+            SIGN,
+        ],
+        dispatch: run_egsa
+    },
+    AlgorithmInfo {
+        id: "gost_r_34_10_94", name: "ГОСТ Р34.10-94", family: Family::Signature, operations: SIGNATURE,
+        params: &[
+            integer("p").with(&[Constraint::Min(32)]),
+            integer("q").with(&[Constraint::Min(2)]),
+            integer("a").with(&[Constraint::Min(2)]),
+            integer("x").with(POSITIVE).only(SIGN_ONLY),
+            integer("y").with(POSITIVE).only(VERIFY),
+            HASH_MODULUS,
+            SIGN,
+        ],
+        dispatch: run_gost_94
+    },
+    AlgorithmInfo {
+        id: "gost_r_34_10_2012", name: "ГОСТ Р34.10-2012", family: Family::Signature, operations: SIGNATURE,
+        params: &[
+            CURVE[0], CURVE[1], CURVE[2], CURVE[3], CURVE[4], CURVE[5], CURVE[6], CURVE[7], CURVE[8],
+            HASH_MODULUS,
+            SIGN,
+        ],
+        dispatch: run_gost_2012
+    },
+    AlgorithmInfo {
+        id: "diffie_hellman", name: "Диффи-Хеллман", family: Family::KeyExchange,
+        operations: &[Operation::Exchange],
+        params: &[
+            integer("a").with(&[Constraint::Min(2)]),
+            integer("n").with(&[Constraint::Min(3)]),
+            integer("Ka").with(POSITIVE),
+            integer("Kb").with(POSITIVE),
+        ],
+        dispatch: run_diffie_hellman
+    },
+];
+
+fn number<T>(val: &str, param: &'static str) -> Result<T, CipherError>
+    where T: std::str::FromStr<Err = std::num::ParseIntError>
+{
+    if val.is_empty() { Err(CipherError::EmptyValue { param })?; }
+    parse_number(val.trim(), param)
+}
+
+fn parse_matrix(val: &str) -> Result<Vec<Vec<isize>>, CipherError> {
+    val.split(';')
+        .map(|row| row.split([' ', ','])
+            .filter(|value| !value.is_empty())
+            .map(|value| number::<isize>(value, "Матрица"))
+            .collect())
+        .collect()
+}
+
+fn parse_grid(val: &str) -> Result<(Vec<bool>, usize, usize), CipherError> {
+    let rows: Vec<&str> = val.split(';').map(str::trim).collect();
+    let cols = rows[0].chars().count();
+    if rows.iter().any(|row| row.chars().count() != cols) {
+        Err(CipherError::invalid_size("Решетка", "Строки решетки должны быть одной длины"))?;
+    }
+    let mut grid = Vec::new();
+    for (position, letter) in rows.concat().chars().enumerate() {
+        match letter {
+            '0' => grid.push(false),
+            '1' => grid.push(true),
+            letter => Err(CipherError::InvalidCharacter { param: "Решетка", letter, position })?
+        }
+    }
+    Ok((grid, rows.len(), cols))
+}
+
+/// Generator and its order, the keys are parsed by the operations that read them
+fn curve(args: &[&str]) -> Result<(Point, usize), CipherError> {
+    let a = number(args[0], "a")?;
+    let b = number(args[1], "b")?;
+    let p = number(args[2], "p")?;
+    let g = Point::new(a, b, number(args[3], "Gx")?, number(args[4], "Gy")?, p);
+    Ok((g, number(args[7], "q")?))
+}
+
+fn public_key(args: &[&str], g: &Point) -> Result<Point, CipherError> {
+    Ok(Point::new(g.a, g.b, number(args[5], "Dbx")?, number(args[6], "Dby")?, g.modula))
+}
+
+fn run_atbash(operation: Operation, phrase: &str, _: &[&str]) -> Result<String, CipherError> {
+    match operation {
+        Operation::Encrypt => atbash::encrypt(phrase),
+        _ => atbash::decrypt(phrase)
+    }
+}
+
+fn run_caesar(operation: Operation, phrase: &str, args: &[&str]) -> Result<String, CipherError> {
+    let shift = transform(args[0], "Ключ")?;
+    match operation {
+        Operation::Encrypt => caesar::encrypt(phrase, shift),
+        _ => caesar::decrypt(phrase, shift)
+    }
+}
+
+fn run_polybius(operation: Operation, phrase: &str, args: &[&str]) -> Result<String, CipherError> {
+    match operation {
+        Operation::Encrypt => polybius::encrypt(phrase, args[0], args[1]),
+        _ => polybius::decrypt(phrase, args[0], args[1])
+    }
+}
+
+fn run_trithemium(operation: Operation, phrase: &str, _: &[&str]) -> Result<String, CipherError> {
+    match operation {
+        Operation::Encrypt => trithemium::encrypt(phrase),
+        _ => trithemium::decrypt(phrase)
+    }
+}
+
+fn run_belazo(operation: Operation, phrase: &str, args: &[&str]) -> Result<String, CipherError> {
+    match operation {
+        Operation::Encrypt => belazo::encrypt(phrase, args[0]),
+        _ => belazo::decrypt(phrase, args[0])
+    }
+}
+
+fn run_vigenere(operation: Operation, phrase: &str, args: &[&str]) -> Result<String, CipherError> {
+    match operation {
+        Operation::Encrypt => vigenere::encrypt(phrase, args[0]),
+        _ => vigenere::decrypt(phrase, args[0])
+    }
+}
+
+fn run_s_table(operation: Operation, phrase: &str, _: &[&str]) -> Result<String, CipherError> {
+    let bytes = hex_to_bytes(phrase, 4)?;
+    let func = match operation {
+        Operation::Encrypt => magma::t,
+        _ => magma::t_reverse
+    };
+    let sbox = magma::SBox::default();
+    Ok(bytes_to_hex(&bytes.chunks(4).flat_map(|chunk| func(chunk, &sbox)).collect::<Vec<u8>>()))
+}
+
+fn run_matrix(operation: Operation, phrase: &str, args: &[&str]) -> Result<String, CipherError> {
+    let key = parse_matrix(args[0])?;
+    match operation {
+        Operation::Encrypt => matrix::encrypt(phrase, key),
+        _ => matrix::decrypt(phrase, key)
+    }
+}
+
+fn run_playfair(operation: Operation, phrase: &str, args: &[&str]) -> Result<String, CipherError> {
+    match operation {
+        Operation::Encrypt => playfair::encrypt(phrase, args[0]),
+        _ => playfair::decrypt(phrase, args[0])
+    }
+}
+
+fn run_vertical(operation: Operation, phrase: &str, args: &[&str]) -> Result<String, CipherError> {
+    match operation {
+        Operation::Encrypt => vertical::encrypt(phrase, args[0]),
+        _ => vertical::decrypt(phrase, args[0])
+    }
+}
+
+fn run_cardano(operation: Operation, phrase: &str, args: &[&str]) -> Result<String, CipherError> {
+    let (grid, rows, cols) = parse_grid(args[0])?;
+    let dirs = vec![true, false, true];
+    match operation {
+        Operation::Encrypt => cardano::encrypt(phrase, grid, rows, cols, dirs),
+        _ => cardano::decrypt(phrase, grid, rows, cols, dirs)
+    }
+}
+
+fn run_feistel(operation: Operation, phrase: &str, args: &[&str]) -> Result<String, CipherError> {
+    let network = feistel::network(
+        number(args[1], "Размер блока")?, number(args[2], "Число раундов")?,
+        number(args[3], "Раундовая функция")?, number(args[4], "Ключевое расписание")?,
+        number(args[5], "Сдвиг")?
+    )?;
+    match operation {
+        Operation::Encrypt => feistel::encrypt(phrase, args[0], &network),
+        _ => feistel::decrypt(phrase, args[0], &network)
+    }
+}
+
+fn run_shenon(operation: Operation, phrase: &str, args: &[&str]) -> Result<String, CipherError> {
+    match operation {
+        Operation::Encrypt => shenon::encrypt(phrase, args[0], args[1], args[2]),
+        _ => shenon::decrypt(phrase, args[0], args[1], args[2])
+    }
+}
+
+fn run_ctr_magma(_: Operation, phrase: &str, args: &[&str]) -> Result<String, CipherError> {
+    magma::ctr_magma(phrase, args[1], args[0])
+}
+
+fn run_a5_1(operation: Operation, phrase: &str, args: &[&str]) -> Result<String, CipherError> {
+    match operation {
+        Operation::Encrypt => a5_1::encrypt(phrase, args[0]),
+        _ => a5_1::decrypt(phrase, args[0])
+    }
+}
+
+fn run_a5_2(operation: Operation, phrase: &str, args: &[&str]) -> Result<String, CipherError> {
+    match operation {
+        Operation::Encrypt => a5_2::encrypt(phrase, args[0]),
+        _ => a5_2::decrypt(phrase, args[0])
+    }
+}
+
+fn run_magma(operation: Operation, phrase: &str, args: &[&str]) -> Result<String, CipherError> {
+    match operation {
+        Operation::Encrypt => magma::encrypt(phrase, args[0]),
+        _ => magma::decrypt(phrase, args[0])
+    }
+}
+
+fn run_des(operation: Operation, phrase: &str, args: &[&str]) -> Result<String, CipherError> {
+    match operation {
+        Operation::Encrypt => des::encrypt(phrase, args[0]),
+        _ => des::decrypt(phrase, args[0])
+    }
+}
+
+fn run_triple_des(operation: Operation, phrase: &str, args: &[&str]) -> Result<String, CipherError> {
+    match operation {
+        Operation::Encrypt => triple_des::encrypt(phrase, args[0]),
+        _ => triple_des::decrypt(phrase, args[0])
+    }
+}
+
+fn run_aes(operation: Operation, phrase: &str, args: &[&str]) -> Result<String, CipherError> {
+    match operation {
+        Operation::Encrypt => aes::encrypt(phrase, args[0]),
+        _ => aes::decrypt(phrase, args[0])
+    }
+}
+
+fn run_kuznechik(operation: Operation, phrase: &str, args: &[&str]) -> Result<String, CipherError> {
+    match operation {
+        Operation::Encrypt => kuznechik::encrypt(phrase, args[0]),
+        _ => kuznechik::decrypt(phrase, args[0])
+    }
+}
+
+fn run_rsa(operation: Operation, phrase: &str, args: &[&str]) -> Result<String, CipherError> {
+    let n = number(args[0], "n")?;
+    match operation {
+        Operation::Encrypt => rsa::encrypt(phrase, n, number(args[1], "e")?),
+        _ => rsa::decrypt(phrase, n, number(args[2], "d")?)
+    }
+}
+
+fn run_elgamal(operation: Operation, phrase: &str, args: &[&str]) -> Result<String, CipherError> {
+    let p = number(args[0], "p")?;
+    match operation {
+        Operation::Encrypt => elgamal::encrypt(
+            phrase, p, number(args[1], "g")?, number(args[2], "y")?, None
+        ),
+        _ => elgamal::decrypt(phrase, p, number(args[3], "x")?)
+    }
+}
+
+fn run_ecc(operation: Operation, phrase: &str, args: &[&str]) -> Result<String, CipherError> {
+    let (g, q) = curve(args)?;
+    match operation {
+        Operation::Encrypt => ecc::encrypt(phrase, public_key(args, &g)?, g, q),
+        _ => ecc::decrypt(phrase, number(args[8], "Cb")?, g.a, g.b, g.modula)
+    }
+}
+
+fn run_rsa_sign(operation: Operation, phrase: &str, args: &[&str]) -> Result<String, CipherError> {
+    let n = number(args[0], "n")?;
+    let modula = number(args[3], "Модуль хэша")?;
+    match operation {
+        Operation::Sign => Ok(rsa_sign::sign(phrase, n, number(args[2], "d")?, modula)?.to_string()),
+        _ => {
+            let s = number(args[4], "Подпись")?;
+            Ok(rsa_sign::check_sign(phrase, n, number(args[1], "e")?, s, modula)?.to_string())
+        }
+    }
+}
+
+fn run_egsa(operation: Operation, phrase: &str, args: &[&str]) -> Result<String, CipherError> {
+    let p = number(args[0], "p")?;
+    let g = number(args[1], "g")?;
+    let modula = number(args[4], "Модуль хэша")?;
+    match operation {
+        Operation::Sign => Ok(egsa::sign(phrase, p, g, number(args[2], "x")?, modula, None)?.to_string()),
+        _ => {
+            let sign: Signature = args[5].parse()?;
+            Ok(egsa::check_sign(phrase, p, g, number(args[3], "y")?, modula, &sign)?.to_string())
+        }
+    }
+}
+
+fn run_gost_94(operation: Operation, phrase: &str, args: &[&str]) -> Result<String, CipherError> {
+    let p = number(args[0], "p")?;
+    let q = number(args[1], "q")?;
+    let a = number(args[2], "a")?;
+    let modula = number(args[5], "Модуль хэша")?;
+    match operation {
+        Operation::Sign => Ok(gost_r_34_10_94::sign(phrase, a, p, number(args[3], "x")?, q, modula)?.to_string()),
+        _ => {
+            let sign: Signature = args[6].parse()?;
+            Ok(gost_r_34_10_94::check_sign(phrase, p, q, a, number(args[4], "y")?, modula, &sign).to_string())
+        }
+    }
+}
+
+fn run_gost_2012(operation: Operation, phrase: &str, args: &[&str]) -> Result<String, CipherError> {
+    let (g, q) = curve(args)?;
+    let modula = number(args[9], "Модуль хэша")?;
+    match operation {
+        Operation::Sign => Ok(gost_r_34_10_2012::sign(phrase, number(args[8], "Cb")?, g, q, modula)?.to_string()),
+        _ => {
+            let sign: Signature = args[10].parse()?;
+            let db = public_key(args, &g)?;
+            Ok(gost_r_34_10_2012::check_sign(phrase, db, g, q, &sign, modula)?.to_string())
+        }
+    }
+}
+
+fn run_diffie_hellman(_: Operation, _: &str, args: &[&str]) -> Result<String, CipherError> {
+    let a = number(args[0], "a")?;
+    let n = number(args[1], "n")?;
+    let ka = number(args[2], "Ka")?;
+    let kb = number(args[3], "Kb")?;
+    if a >= n || a <= 1 {
+        Err(CipherError::invalid_key("a", "Введены некорректные начальные значения"))?;
+    }
+    let yb = diffie_hellman::get_y(a, n, kb);
+    Ok(diffie_hellman::get_k(n, ka, yb).to_string())
+}
+
+#[cfg(test)]
+mod registry_tests {
+    use std::collections::HashSet;
+    use super::*;
+
+    #[test]
+    fn test_ids() {
+        let ids: HashSet<_> = algorithms().iter().map(|info| info.id).collect();
+        assert_eq!(ids.len(), algorithms().len());
+        assert_eq!(algorithms().len(), 29);
+        assert_eq!(find("aes").unwrap().name, "AES");
+        assert_eq!(find("des").unwrap().name, "DES");
+        assert_eq!(find("triple_des").unwrap().family, Family::Combinational);
+    }
+
+    #[test]
+    fn test_run() {
+        let caesar = find("caesar").unwrap();
+        assert_eq!(caesar.run(Operation::Encrypt, "абв", &["1"]).unwrap(), "бвг");
+        assert_eq!(caesar.run(Operation::Sign, "абв", &["1"]), Err(CipherError::UnsupportedOperation));
+        assert!(matches!(
+            caesar.run(Operation::Encrypt, "абв", &[]),
+            Err(CipherError::InvalidSize { param: "Параметры", .. })
+        ));
+        let rsa = find("rsa").unwrap();
+        assert_eq!(rsa.run(Operation::Decrypt, "6417", &["77", "23", "47"]).unwrap(), "от");
+    }
+
+    #[test]
+    fn test_validate() {
+        let shenon = find("shenon").unwrap();
+        assert_eq!(shenon.validate(Operation::Encrypt, &["0", "3", "2"]).unwrap_err().len(), 3);
+        assert_eq!(shenon.validate(Operation::Encrypt, &["32", "5", "7"]), Ok(()));
+        assert_eq!(shenon.validate(Operation::Encrypt, &["33", "5", "7"]).unwrap_err().len(), 1);
+        let exchange = find("diffie_hellman").unwrap();
+        assert_eq!(exchange.param("n").unwrap().constraints, &[Constraint::Min(3)]);
+        assert_eq!(exchange.param("Ya"), None);
+        let magma = find("magma").unwrap();
+        assert!(matches!(
+            magma.run(Operation::Encrypt, "fedcba9876543210", &["ffee"]),
+            Err(CipherError::InvalidSize { param: "Ключ", .. })
+        ));
+        let rsa = find("rsa").unwrap();
+        assert_eq!(rsa.validate(Operation::Encrypt, &["77", "23", ""]), Ok(()));
+        assert_eq!(
+            rsa.validate(Operation::Decrypt, &["77", "23", ""]),
+            Err(vec![CipherError::EmptyValue { param: "d" }])
+        );
+    }
+
+    #[test]
+    fn test_run_block() {
+        let key = "ffeeddccbbaa99887766554433221100f0f1f2f3f4f5f6f7f8f9fafbfcfdfeff";
+        let magma = find("magma").unwrap();
+        assert_eq!(magma.run(Operation::Encrypt, "fedcba9876543210", &[key]).unwrap(), "4ee901e5c2d8ca3d");
+        let s_table = find("s_table").unwrap();
+        let encrypted = s_table.run(Operation::Encrypt, "fdb97531", &[]).unwrap();
+        assert_eq!(encrypted, "2a196f34");
+        assert_eq!(s_table.run(Operation::Decrypt, &encrypted, &[]).unwrap(), "fdb97531");
+        let feistel = find("feistel").unwrap();
+        let args = [key, "8", "32", "1", "1", "0"];
+        assert_eq!(feistel.run(Operation::Encrypt, "fedcba9876543210", &args).unwrap(), "4ee901e5c2d8ca3d");
+        assert_eq!(feistel.validate(Operation::Encrypt, &[key, "7", "0", "3", "1", "0"]).unwrap_err().len(), 3);
+        let des = find("des").unwrap();
+        assert_eq!(des.run(Operation::Encrypt, "0123456789abcdef", &["133457799bbcdff1"]).unwrap(), "85e813540f0ab405");
+        assert_eq!(des.run(Operation::Decrypt, "85e813540f0ab405", &["133457799bbcdff1"]).unwrap(), "0123456789abcdef");
+        assert_eq!(des.validate(Operation::Encrypt, &[&key[..32]]).unwrap_err().len(), 1);
+        let triple_des = find("triple_des").unwrap();
+        let key = "0123456789abcdef23456789abcdef01456789abcdef0123";
+        assert_eq!(triple_des.run(Operation::Encrypt, "5468652071756663", &[key]).unwrap(), "a826fd8ce53b855f");
+        assert_eq!(triple_des.validate(Operation::Encrypt, &[&key[..32]]), Ok(()));
+        assert_eq!(triple_des.validate(Operation::Encrypt, &[&key[..40]]).unwrap_err().len(), 1);
+    }
+
+    #[test]
+    fn test_run_params() {
+        let matrix = find("matrix").unwrap();
+        let encrypted = matrix.run(Operation::Encrypt, "отодно", &["2 5 6; 4 3 2; 7 1 5"]).unwrap();
+        assert_eq!(matrix.run(Operation::Decrypt, &encrypted, &["2,5,6;4,3,2;7,1,5"]).unwrap(), "отодно");
+        let cardano = find("cardano").unwrap();
+        let grid = ["0101;1010;0000;0000"];
+        let encrypted = cardano.run(Operation::Encrypt, "окноокноокноокно", &grid).unwrap();
+        assert_eq!(cardano.run(Operation::Decrypt, &encrypted, &grid).unwrap(), "окноокноокноокно");
+        let exchange = find("diffie_hellman").unwrap();
+        assert_eq!(exchange.run(Operation::Exchange, "", &["3", "17", "5", "7"]).unwrap(), "10");
+    }
+
+    #[test]
+    fn test_run_signature() {
+        let phrase = "отодногопорченогояблокавесьвоззагниваеттчк";
+        let rsa_sign = find("rsa_sign").unwrap();
+        assert_eq!(rsa_sign.run(Operation::Sign, phrase, &["77", "13", "37", "11", ""]).unwrap(), "60");
+        assert_eq!(rsa_sign.run(Operation::Verify, phrase, &["77", "13", "37", "11", "60"]).unwrap(), "true");
+        let egsa = find("egsa").unwrap();
+        let args = ["37", "31", "3", "6", "11", "31,11"];
+        assert_eq!(egsa.run(Operation::Verify, phrase, &args).unwrap(), "true");
+        let args = ["37", "31", "3", "6", "11", "31"];
+        assert_eq!(egsa.run(Operation::Verify, phrase, &args), Err(CipherError::InvalidSignature));
+    }
+
+    #[test]
+    fn test_run_curve() {
+        let ecc = find("ecc").unwrap();
+        let args = ["2", "7", "47", "8", "26", "8", "21", "3", ""];
+        assert_eq!(ecc.validate(Operation::Encrypt, &args), Ok(()));
+        let encrypted = ecc.run(Operation::Encrypt, "отодно", &args).unwrap();
+        let args = ["2", "7", "47", "8", "26", "", "", "3", "2"];
+        assert_eq!(ecc.validate(Operation::Decrypt, &args), Ok(()));
+        assert_eq!(ecc.run(Operation::Decrypt, &encrypted, &args).unwrap(), "отодно");
+        let gost = find("gost_r_34_10_2012").unwrap();
+        let args = ["2", "7", "47", "8", "26", "", "", "3", "2", "11", ""];
+        assert_eq!(gost.validate(Operation::Sign, &args), Ok(()));
+        let sign = gost.run(Operation::Sign, "абв", &args).unwrap();
+        let args = ["2", "7", "47", "8", "26", "8", "21", "3", "", "11", &sign];
+        assert_eq!(gost.validate(Operation::Verify, &args), Ok(()));
+        assert!(gost.run(Operation::Verify, "абв", &args).is_ok());
+    }
+}
@@ -0,0 +1,174 @@
+use crate::errors::CipherError;
+use crate::methods::parse_number;
+use crate::registry::Operation;
+
+/// Format of a parameter value
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ParamKind {
+    Text,
+    /// Signed integer
+    Number,
+    Hex,
+    /// Rows separated by `;`, numbers by spaces or commas
+    Matrix,
+    /// Rows of `0` and `1` separated by `;`, `1` marks a hole
+    Grid,
+    /// Pair of numbers `r,s`
+    Signature,
+}
+
+/// Requirement checked by `validate` in addition to the kind of the parameter
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Constraint {
+    /// Exact number of characters
+    Length(usize),
+    /// One of the allowed numbers of characters
+    Lengths(&'static [usize]),
+    /// Number in the inclusive range
+    Range { min: isize, max: isize },
+    /// Number not less than the value
+    Min(isize),
+    /// Number gives `remainder` when divided by `modulus`
+    Congruent { modulus: isize, remainder: isize },
+    Odd,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Param {
+    pub name: &'static str,
+    pub kind: ParamKind,
+    pub constraints: &'static [Constraint],
+    /// Operations reading the parameter, empty means all of them
+    pub operations: &'static [Operation],
+}
+
+impl Param {
+    pub const fn new(name: &'static str, kind: ParamKind) -> Self {
+        Param { name, kind, constraints: &[], operations: &[] }
+    }
+
+    pub const fn with(self, constraints: &'static [Constraint]) -> Self {
+        Param { constraints, ..self }
+    }
+
+    pub const fn only(self, operations: &'static [Operation]) -> Self {
+        Param { operations, ..self }
+    }
+
+    pub fn is_used_by(&self, operation: Operation) -> bool {
+        self.operations.is_empty() || self.operations.contains(&operation)
+    }
+
+    /// Collects every violation of the value
+    pub fn check(&self, value: &str) -> Vec<CipherError> {
+        let param = self.name;
+        if value.is_empty() { return vec![CipherError::EmptyValue { param }]; }
+        let number = match self.kind {
+            ParamKind::Number => match parse_number::<isize>(value.trim(), param) {
+                Ok(number) => Some(number),
+                Err(err) => return vec![err]
+            },
+            ParamKind::Hex => match hex::decode(value) {
+                Ok(_) => None,
+                Err(err) => return vec![err.into()]
+            },
+            _ => None
+        };
+        self.constraints.iter()
+            .filter_map(|constraint| constraint.check(param, value, number))
+            .collect()
+    }
+}
+
+impl Constraint {
+    fn check(&self, param: &'static str, value: &str, number: Option<isize>) -> Option<CipherError> {
+        match (*self, number) {
+            (Constraint::Length(len), _) if value.chars().count() != len => Some(CipherError::invalid_size(
+                param, &format!("{param} должен состоять из {len} символов")
+            )),
+            (Constraint::Lengths(lens), _) if !lens.contains(&value.chars().count()) => {
+                let lens: Vec<String> = lens.iter().map(|len| len.to_string()).collect();
+                let lens = match lens.split_last() {
+                    Some((last, rest)) if !rest.is_empty() => format!("{} или {last}", rest.join(", ")),
+                    _ => lens.concat()
+                };
+                Some(CipherError::invalid_size(param, &format!("{param} должен состоять из {lens} символов")))
+            },
+            (Constraint::Range { min, max }, Some(number)) if number < min || number > max => Some(
+                CipherError::invalid_key(param, &format!("{param} должен быть в пределах от {min} до {max}"))
+            ),
+            (Constraint::Min(min), Some(number)) if number < min => Some(
+                CipherError::invalid_key(param, &format!("{param} должен быть не меньше {min}"))
+            ),
+            (Constraint::Congruent { modulus, remainder }, Some(number))
+                if number.rem_euclid(modulus) != remainder => Some(CipherError::invalid_key(
+                    param, &format!("{param} должен давать остаток {remainder} при делении на {modulus}")
+                )),
+            (Constraint::Odd, Some(number)) if number % 2 == 0 => Some(
+                CipherError::invalid_key(param, &format!("{param} должен быть нечетным"))
+            ),
+            _ => None
+        }
+    }
+}
+
+/// Checks the values of `params` used by `operation`, `args` follow the order of `params`
+pub fn validate(params: &[Param], operation: Operation, args: &[&str]) -> Result<(), Vec<CipherError>> {
+    if args.len() != params.len() {
+        return Err(vec![CipherError::invalid_size("Параметры", "Неверное количество параметров")]);
+    }
+    let errors: Vec<CipherError> = params.iter()
+        .zip(args)
+        .filter(|(param, _)| param.is_used_by(operation))
+        .flat_map(|(param, value)| param.check(value))
+        .collect();
+    if errors.is_empty() { Ok(()) } else { Err(errors) }
+}
+
+#[cfg(test)]
+mod schema_tests {
+    use super::*;
+
+    const SHENON: [Param; 3] = [
+        Param::new("T0", ParamKind::Number).with(&[Constraint::Range { min: 1, max: 32 }]),
+        Param::new("a", ParamKind::Number).with(&[Constraint::Congruent { modulus: 4, remainder: 1 }]),
+        Param::new("c", ParamKind::Number).with(&[Constraint::Odd]),
+    ];
+
+    #[test]
+    fn test_validate() {
+        assert_eq!(validate(&SHENON, Operation::Encrypt, &["3", "5", "7"]), Ok(()));
+        let errors = validate(&SHENON, Operation::Encrypt, &["33", "3", "4"]).unwrap_err();
+        assert_eq!(errors.len(), 3);
+        assert_eq!(errors[1].to_string(), "a должен давать остаток 1 при делении на 4");
+        let errors = validate(&SHENON, Operation::Encrypt, &["", "x", "-1"]).unwrap_err();
+        assert_eq!(errors[0], CipherError::EmptyValue { param: "T0" });
+        assert!(matches!(errors[1], CipherError::InvalidNumber { param: "a", .. }));
+        assert_eq!(errors.len(), 2);
+    }
+
+    #[test]
+    fn test_hex() {
+        let key = Param::new("Ключ", ParamKind::Hex).with(&[Constraint::Length(4)]);
+        assert!(key.check("0a1b").is_empty());
+        assert!(matches!(key.check("0a1z")[..], [CipherError::InvalidHex(_)]));
+        assert!(matches!(key.check("0a")[..], [CipherError::InvalidSize { param: "Ключ", .. }]));
+        let key = Param::new("Ключ", ParamKind::Hex).with(&[Constraint::Lengths(&[2, 4, 6])]);
+        assert!(key.check("0a1b").is_empty());
+        assert_eq!(key.check("0a1b2c3d")[0].to_string(), "Ключ должен состоять из 2, 4 или 6 символов");
+    }
+
+    #[test]
+    fn test_operations() {
+        let params = [
+            Param::new("e", ParamKind::Number).only(&[Operation::Encrypt]),
+            Param::new("d", ParamKind::Number).only(&[Operation::Decrypt]),
+        ];
+        assert_eq!(validate(&params, Operation::Encrypt, &["3", ""]), Ok(()));
+        assert_eq!(
+            validate(&params, Operation::Decrypt, &["3", ""]),
+            Err(vec![CipherError::EmptyValue { param: "d" }])
+        );
+        assert!(validate(&params, Operation::Decrypt, &["3"]).is_err());
+    }
+}
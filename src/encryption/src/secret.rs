@@ -0,0 +1,119 @@
+//! Key material that is wiped from memory when dropped.
+//!
+//! `Secret` never shows its contents in `Debug`, the value is reached
+//! explicitly through `expose`.
+
+use std::fmt;
+use zeroize::Zeroize;
+use crate::errors::CipherError;
+
+/// Value zeroed on drop and hidden from `Debug`
+#[derive(Clone, Default)]
+pub struct Secret<T: Zeroize>(T);
+
+impl<T: Zeroize> Secret<T> {
+    pub fn new(value: T) -> Self {
+        Secret(value)
+    }
+
+    pub fn expose(&self) -> &T {
+        &self.0
+    }
+
+    pub fn expose_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+impl Secret<Vec<u8>> {
+    pub fn from_hex(key: &str) -> Result<Self, CipherError> {
+        Ok(Secret(hex::decode(key)?))
+    }
+}
+
+impl<const N: usize> Secret<[u8; N]> {
+    pub fn from_hex(key: &str) -> Result<Self, CipherError> {
+        Self::try_from(Secret::<Vec<u8>>::from_hex(key)?.expose().as_slice())
+    }
+}
+
+impl<T: Zeroize> From<T> for Secret<T> {
+    fn from(value: T) -> Self {
+        Secret(value)
+    }
+}
+
+/// Takes the key typed by the user, e.g. the text of an entry
+impl From<&str> for Secret<String> {
+    fn from(value: &str) -> Self {
+        Secret(value.to_owned())
+    }
+}
+
+impl<const N: usize> TryFrom<&[u8]> for Secret<[u8; N]> {
+    type Error = CipherError;
+
+    fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
+        if value.len() != N {
+            Err(CipherError::invalid_size("Ключ", &format!("Ключ должен состоять из {N} байт")))?;
+        }
+        let mut result = Secret([0; N]);
+        result.0.copy_from_slice(value);
+        Ok(result)
+    }
+}
+
+impl<T: Zeroize> Drop for Secret<T> {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl<T: Zeroize> fmt::Debug for Secret<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Secret(***)")
+    }
+}
+
+#[cfg(test)]
+mod secret_tests {
+    use std::cell::Cell;
+    use std::rc::Rc;
+    use super::*;
+
+    #[test]
+    fn test_debug() {
+        let key = Secret::<[u8; 4]>::from_hex("deadbeef").unwrap();
+        assert_eq!(key.expose(), &[0xde, 0xad, 0xbe, 0xef]);
+        assert_eq!(format!("{key:?}"), "Secret(***)");
+        assert_eq!(format!("{:?}", Secret::<String>::from("ключ")), "Secret(***)");
+        assert_eq!(format!("{:?}", Secret::new(3_usize)), "Secret(***)");
+    }
+
+    struct Flag(Rc<Cell<bool>>);
+
+    impl Zeroize for Flag {
+        fn zeroize(&mut self) {
+            self.0.set(true);
+        }
+    }
+
+    #[test]
+    fn test_zeroize() {
+        let wiped = Rc::new(Cell::new(false));
+        let key = Secret::new(Flag(wiped.clone()));
+        assert!(!wiped.get());
+        drop(key);
+        assert!(wiped.get());
+        let mut key = Secret::new(vec![1_u8, 2]);
+        key.expose_mut().push(3);
+        assert_eq!(key.expose(), &[1, 2, 3]);
+    }
+
+    #[test]
+    fn test_size() {
+        assert!(Secret::<[u8; 4]>::from_hex("deadbe").is_err());
+        assert!(Secret::<[u8; 4]>::from_hex("zz").is_err());
+        assert_eq!(Secret::<[u8; 2]>::try_from([1_u8, 2].as_slice()).unwrap().expose(), &[1, 2]);
+    }
+}
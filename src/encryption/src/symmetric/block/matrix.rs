@@ -1,8 +1,8 @@
-use std::error::Error;
+use crate::errors::CipherError;
 use crate::alphabet::Alphabet;
-use crate::errors::{InvalidIndex, InvalidSize, NullSizedValue};
+use crate::cipher::Cipher;
 use num::cast::AsPrimitive;
-use crate::methods::validate_single;
+use crate::methods::{parse_number, validate_single};
 
 struct Matrix<T> where
     T: Copy
@@ -13,9 +13,9 @@ struct Matrix<T> where
 }
 
 impl Matrix<f32> {
-    fn det(&mut self) -> Result<f32, Box<dyn Error>>{
+    fn det(&mut self) -> Result<f32, CipherError>{
         if self.rows == 0 || self.cols == 0 {
-            Err(InvalidSize::new("Матрица пуста"))?;
+            Err(CipherError::invalid_size("Матрица", "Матрица пуста"))?;
         }
         if self.rows == 1 {
             return Ok(*self.data.first().unwrap());
@@ -41,9 +41,9 @@ impl Matrix<f32> {
         Ok(result)
     }
 
-    fn swap_zero(&mut self) -> Result<(), Box<dyn Error>> {
+    fn swap_zero(&mut self) -> Result<(), CipherError> {
         if self.rows == 0 || self.cols == 0 {
-            Err(InvalidSize::new("Матрица пуста"))?;
+            Err(CipherError::invalid_size("Матрица", "Матрица пуста"))?;
         }
         let mut flag = false;
         for i in 0..self.rows {
@@ -57,13 +57,13 @@ impl Matrix<f32> {
                 flag = true;
             }
         }
-        if !flag { Err(InvalidIndex)?; }
+        if !flag { Err(CipherError::InvalidIndex)?; }
         Ok(())
     }
 
-    fn get_minor(&self, iski: usize, iskj: usize) -> Result<Matrix<f32>, Box<dyn Error>>{
+    fn get_minor(&self, iski: usize, iskj: usize) -> Result<Matrix<f32>, CipherError>{
         if self.rows != self.cols {
-            Err(InvalidSize::new("Матрица не является квадратной"))?;
+            Err(CipherError::invalid_size("Матрица", "Матрица не является квадратной"))?;
         }
         let mut data = Vec::new();
         for (index, number) in self.data.iter().enumerate() {
@@ -80,8 +80,8 @@ impl Matrix<f32> {
 }
 
 impl Matrix<isize> {
-    fn max(&self) -> Result<isize, Box<dyn Error>> {
-        Ok(*self.data.iter().max().ok_or(InvalidSize::new("Матрица пуста"))?)
+    fn max(&self) -> Result<isize, CipherError> {
+        Ok(*self.data.iter().max().ok_or(CipherError::invalid_size("Матрица", "Матрица пуста"))?)
     }
 
     fn mul(&self, rhs: &Matrix<isize>) -> Matrix<isize> {
@@ -104,9 +104,9 @@ impl Matrix<isize> {
         }
     }
 
-    fn get_minor(&self, iski: usize, iskj: usize) -> Result<Matrix<isize>, Box<dyn Error>>{
+    fn get_minor(&self, iski: usize, iskj: usize) -> Result<Matrix<isize>, CipherError>{
         if self.rows != self.cols {
-            Err(InvalidSize::new("Матрица не является квадратной"))?;
+            Err(CipherError::invalid_size("Матрица", "Матрица не является квадратной"))?;
         }
         let mut data = Vec::new();
         for (count, elem) in self.data.iter().enumerate() {
@@ -121,9 +121,9 @@ impl Matrix<isize> {
         })
     }
 
-    fn partial_reverse(&self) -> Result<Matrix<isize>, Box<dyn Error>> {
+    fn partial_reverse(&self) -> Result<Matrix<isize>, CipherError> {
         if self.rows != self.cols {
-            Err(InvalidSize::new("Матрица не является квадратной"))?;
+            Err(CipherError::invalid_size("Матрица", "Матрица не является квадратной"))?;
         }
         let mut multiplicator: isize = 1;
         let mut data = Vec::new();
@@ -135,7 +135,7 @@ impl Matrix<isize> {
                 );
                 multiplicator *= -1;
             }
-            if self.rows % 2 == 0 { multiplicator *= -1; }
+            if self.rows.is_multiple_of(2) { multiplicator *= -1; }
         }
         Matrix {
             rows: self.rows,
@@ -144,9 +144,9 @@ impl Matrix<isize> {
         }.transp()
     }
 
-    fn transp(&self) -> Result<Matrix<isize>, Box<dyn Error>> {
+    fn transp(&self) -> Result<Matrix<isize>, CipherError> {
         if self.rows != self.cols {
-            Err(InvalidSize::new("Матрица не является квадратной"))?;
+            Err(CipherError::invalid_size("Матрица", "Матрица не является квадратной"))?;
         }
         let mut tmp;
         let mut data = Vec::new();
@@ -167,17 +167,17 @@ impl Matrix<isize> {
 impl<T> TryFrom<Vec<Vec<T>>> for Matrix<T> where
     T: Copy
 {
-    type Error = InvalidSize;
+    type Error = CipherError;
 
     fn try_from(value: Vec<Vec<T>>) -> Result<Self, Self::Error> {
         let rows = value.len();
-        if rows == 0 { return Err(InvalidSize::new("Матрица не может быть нулевой")); }
+        if rows == 0 { return Err(CipherError::invalid_size("Матрица", "Матрица не может быть нулевой")); }
         let cols = value.first().unwrap().len();
-        if cols == 0 { return Err(InvalidSize::new("Матрица не может быть нулевой")); }
+        if cols == 0 { return Err(CipherError::invalid_size("Матрица", "Матрица не может быть нулевой")); }
         let mut data = Vec::new();
         for row in value {
             if row.len() != cols {
-                return Err(InvalidSize::new("Исходный массив не может быть ступенчатым"));
+                return Err(CipherError::invalid_size("Матрица", "Исходный массив не может быть ступенчатым"));
             }
             for item in row {
                 data.push(item);
@@ -193,11 +193,11 @@ impl<T> TryFrom<Vec<Vec<T>>> for Matrix<T> where
 impl<T> TryFrom<&Vec<T>> for Matrix<T> where
     T: Copy
 {
-    type Error = InvalidSize;
+    type Error = CipherError;
 
     fn try_from(value: &Vec<T>) -> Result<Self, Self::Error> {
         let rows = value.len();
-        if rows == 0 { return Err(InvalidSize::new("Матрица не может быть нулевой")); }
+        if rows == 0 { return Err(CipherError::invalid_size("Матрица", "Матрица не может быть нулевой")); }
         let mut data = Vec::new();
         for item in value {
             data.push(*item);
@@ -225,13 +225,12 @@ impl<T> From<&Matrix<T>> for Matrix<f32> where
     }
 }
 
-fn split_string(phrase: &str, border: usize) -> Result<Vec<Matrix<isize>>, Box<dyn Error>> {
-    let alphabet = Alphabet::new();
+fn split_string(alphabet: &Alphabet, phrase: &str, border: usize) -> Result<Vec<Matrix<isize>>, CipherError> {
     let mut result = Vec::new();
     let mut phrase = phrase.to_owned();
-    if phrase.chars().count() % border != 0 {
+    if !phrase.chars().count().is_multiple_of(border) {
         for _ in 0..(border - phrase.chars().count() % border) {
-            phrase.push('\u{0444}');
+            phrase.push(alphabet.filler());
         }
     }
     let length = phrase.chars().count();
@@ -249,15 +248,15 @@ fn count_digits(number: usize) -> usize {
 }
 
 fn get_numbers(phrase: &str, avg_length: usize, n: usize)
-    -> Result<Vec<Matrix<isize>>, Box<dyn Error>>
+    -> Result<Vec<Matrix<isize>>, CipherError>
 {
     let mut result = Vec::new();
-    if (phrase.chars().count() / avg_length) % n != 0 {
-        Err(InvalidSize::new("Некорректная фраза"))?
+    if !(phrase.chars().count() / avg_length).is_multiple_of(n) {
+        Err(CipherError::invalid_size("Фраза", "Некорректная фраза"))?
     }
     let mut buffer = Vec::new();
     for i in 0..phrase.chars().count() / avg_length {
-        let number: isize = phrase[i*avg_length..i*avg_length+avg_length].parse()?;
+        let number: isize = parse_number(&phrase[i*avg_length..i*avg_length+avg_length], "Фраза")?;
         buffer.push(number);
         if (i + 1) % n == 0 {
             result.push(Matrix::try_from(&buffer)?);
@@ -267,14 +266,23 @@ fn get_numbers(phrase: &str, avg_length: usize, n: usize)
     Ok(result)
 }
 
-pub fn encrypt(phrase: &str, matrix: Vec<Vec<isize>>) -> Result<String, Box<dyn Error>> {
-    let alphabet = Alphabet::new();
-    validate_single(&alphabet, phrase)?;
+pub fn encrypt(phrase: &str, matrix: Vec<Vec<isize>>) -> Result<String, CipherError> {
+    encrypt_with_alphabet(&Alphabet::new(), phrase, matrix)
+}
+
+pub fn decrypt(phrase: &str, matrix: Vec<Vec<isize>>) -> Result<String, CipherError> {
+    decrypt_with_alphabet(&Alphabet::new(), phrase, matrix)
+}
+
+pub fn encrypt_with_alphabet(alphabet: &Alphabet, phrase: &str, matrix: Vec<Vec<isize>>)
+    -> Result<String, CipherError>
+{
+    validate_single(alphabet, phrase)?;
     let matrix = Matrix::try_from(matrix)?;
     if Matrix::from(&matrix).det()?.round() == 0. {
-        Err(NullSizedValue::new("Определитель равен 0"))?;
+        Err(CipherError::EmptyValue { param: "Определитель равен 0" })?;
     }
-    let letter_boxes = split_string(phrase, matrix.rows)?;
+    let letter_boxes = split_string(alphabet, phrase, matrix.rows)?;
     let result_boxes: Vec<Matrix<isize>> = letter_boxes.iter().map(
         |letter_box| matrix.mul(letter_box)).collect();
     let count = count_digits(
@@ -291,13 +299,14 @@ pub fn encrypt(phrase: &str, matrix: Vec<Vec<isize>>) -> Result<String, Box<dyn
     Ok(result)
 }
 
-pub fn decrypt(phrase: &str, matrix: Vec<Vec<isize>>) -> Result<String, Box<dyn Error>> {
-    let alphabet = Alphabet::new();
+pub fn decrypt_with_alphabet(alphabet: &Alphabet, phrase: &str, matrix: Vec<Vec<isize>>)
+    -> Result<String, CipherError>
+{
     validate_decrypt(phrase)?;
     let matrix = Matrix::try_from(matrix)?;
     let det = Matrix::from(&matrix).det()?.round() as isize;
     if det == 0 {
-        Err(NullSizedValue::new("Определитель равен 0"))?;
+        Err(CipherError::EmptyValue { param: "Определитель равен 0" })?;
     }
     let count = count_digits(
         matrix.max()? as usize *
@@ -311,11 +320,11 @@ pub fn decrypt(phrase: &str, matrix: Vec<Vec<isize>>) -> Result<String, Box<dyn
     for result_box in result_boxes {
         for number in result_box.data {
             if number % det != 0 {
-                Err(InvalidSize::new("Некорректная фраза"))?
+                Err(CipherError::invalid_size("Фраза", "Некорректная фраза"))?
             }
             let number = number / det;
             if number > alphabet.len() as isize || number <= 0 {
-                Err(InvalidSize::new("Некорректная фраза"))?
+                Err(CipherError::invalid_size("Фраза", "Некорректная фраза"))?
             }
             result.push(alphabet.get(number as usize - 1));
         }
@@ -323,8 +332,22 @@ pub fn decrypt(phrase: &str, matrix: Vec<Vec<isize>>) -> Result<String, Box<dyn
     Ok(result)
 }
 
-fn validate_decrypt(phrase: &str) -> Result<(), Box<dyn Error>> {
-    if phrase.is_empty() { return Err(Box::new(NullSizedValue::new("Фраза"))); }
+pub struct MatrixCipher;
+
+impl Cipher for MatrixCipher {
+    type Key = Vec<Vec<isize>>;
+
+    fn encrypt(&self, phrase: &str, key: &Vec<Vec<isize>>) -> Result<String, CipherError> {
+        encrypt(phrase, key.clone())
+    }
+
+    fn decrypt(&self, phrase: &str, key: &Vec<Vec<isize>>) -> Result<String, CipherError> {
+        decrypt(phrase, key.clone())
+    }
+}
+
+fn validate_decrypt(phrase: &str) -> Result<(), CipherError> {
+    if phrase.is_empty() { return Err(CipherError::EmptyValue { param: "Фраза" }); }
     let alphabet = Alphabet::from("0123456789".to_string());
     alphabet.validate(phrase)
 }
@@ -393,4 +416,12 @@ mod matrix_test {
         let elems: Vec<Vec<isize>> = vec![vec![2, 5, 6], vec![4, 3, 2], vec![7, 1, 5]];
         decrypt("222222222", elems).unwrap();
     }
+
+    #[test]
+    fn test_with_alphabet() {
+        let alphabet = Alphabet::latin();
+        let matrix = vec![vec![2, 5, 6], vec![4, 3, 2], vec![7, 1, 5]];
+        let result = encrypt_with_alphabet(&alphabet, "hello", matrix.clone()).unwrap();
+        assert_eq!(decrypt_with_alphabet(&alphabet, &result, matrix).unwrap(), "hellox");
+    }
 }
@@ -1,23 +1,28 @@
 use std::collections::HashSet;
-use std::error::Error;
+use crate::errors::CipherError;
 use crate::alphabet::Alphabet;
-use crate::errors::{InvalidKeyError, NullSizedValue};
+use crate::cipher::Cipher;
 use crate::methods::modd;
+use crate::normalizer::Normalizer;
 use itertools::Itertools;
 
+/// Letters merged with their neighbours to fit the alphabet into a rectangle
+const FOLDS: [(char, char); 4] = [('ь', 'ъ'), ('ё', 'е'), ('й', 'и'), ('j', 'i')];
+
 struct PlayfairTable {
+    rows: usize,
     cols: usize,
     data: Vec<char>
 }
 
 impl PlayfairTable {
-    fn new(key: &str) -> Self {
-        let alphabet = "абвгдежзиклмнопрстуфхцчшщъыэюя".to_string();
+    fn new(letters: &Alphabet, key: &str) -> Result<Self, CipherError> {
+        let (rows, cols) = dimensions(letters.len())?;
         let mut data: Vec<char> = key.chars().collect();
-        for letter in alphabet.chars() {
+        for letter in letters.alphabet().chars() {
             if !data.contains(&letter) { data.push(letter); }
         }
-        PlayfairTable { cols: 6, data }
+        Ok(PlayfairTable { rows, cols, data })
     }
 
     fn find_letter(&self, letter: char) -> (usize, usize) {
@@ -36,8 +41,8 @@ impl PlayfairTable {
              *self.data.get(i2 * self.cols + j2).unwrap())
         }
         else if j1 == j2 {
-            let i1 = modd(i1 as isize + dir, self.cols-1);
-            let i2 = modd(i2 as isize + dir, self.cols-1);
+            let i1 = modd(i1 as isize + dir, self.rows);
+            let i2 = modd(i2 as isize + dir, self.rows);
             (*self.data.get(i1 * self.cols + j1).unwrap(),
              *self.data.get(i2 * self.cols + j2).unwrap())
         }
@@ -48,46 +53,66 @@ impl PlayfairTable {
     }
 }
 
-fn validate_key(key: &str) -> Result<(), Box<dyn Error>> {
+fn folds(alphabet: &Alphabet) -> Normalizer {
+    FOLDS.iter()
+        .filter(|(_, to)| alphabet.contains(*to))
+        .fold(Normalizer::new(), |normalizer, (from, to)| normalizer.with_fold(*from, *to))
+}
+
+fn table_letters(alphabet: &Alphabet) -> Alphabet {
+    let folds = folds(alphabet);
+    Alphabet::from(
+        alphabet.alphabet().chars().filter(|letter| folds.fold(*letter) == *letter).collect::<String>()
+    )
+}
+
+fn dimensions(len: usize) -> Result<(usize, usize), CipherError> {
+    match (2..).take_while(|rows| rows * rows <= len).filter(|rows| len.is_multiple_of(*rows)).last() {
+        Some(rows) => Ok((rows, len / rows)),
+        None => Err(CipherError::invalid_size(
+            "Алфавит", "Алфавит должен раскладываться в прямоугольную таблицу"
+        ))
+    }
+}
+
+fn validate_key(key: &str) -> Result<(), CipherError> {
     let mut set = HashSet::new();
     for letter in key.chars() {
         if !set.contains(&letter) { set.insert(letter); }
         else {
-            Err(InvalidKeyError::new("Ключ должен состоять из уникальных значений"))?;
+            Err(CipherError::invalid_key("Ключ", "Ключ должен состоять из уникальных значений"))?;
         }
     }
     Ok(())
 }
 
-fn prepare_phrase(phrase: &str) -> String {
+fn prepare_phrase(alphabet: &Alphabet, phrase: &str) -> String {
+    let filler = table_letters(alphabet).filler();
+    let phrase = folds(alphabet).normalize(phrase);
+    let mut letters = phrase.chars();
     let mut result = String::new();
-    let mut prev_letter = phrase.chars().next().unwrap();
+    let mut prev_letter = letters.next().unwrap();
     let mut i = 1;
     result.push(prev_letter);
-    for letter in phrase.chars().skip(1) {
+    for letter in letters {
         if letter == prev_letter && (i+1)%2 == 0 {
-            result.push('\u{0444}');
+            result.push(filler);
             i = (i + 1) % 2;
         }
-        result.push(match letter {
-            '\u{044C}' => '\u{044A}',
-            '\u{0451}' => '\u{0435}',
-            '\u{0439}' => '\u{0438}',
-            letter => letter
-        });
+        result.push(letter);
         prev_letter = letter;
         i = (i + 1) % 2;
     }
-    if result.chars().count() % 2 != 0 {
-        result.push('\u{0444}');
+    if !result.chars().count().is_multiple_of(2) {
+        result.push(filler);
     }
     result
 }
 
-fn proto(phrase: &str, key: &str, rev: bool) -> Result<String, Box<dyn Error>> {
-    validate(phrase, key)?;
-    let table = PlayfairTable::new(key);
-    let phrase = prepare_phrase(phrase);
+fn proto(alphabet: &Alphabet, phrase: &str, key: &str, rev: bool) -> Result<String, CipherError> {
+    validate(alphabet, phrase, key)?;
+    let table = PlayfairTable::new(&table_letters(alphabet), key)?;
+    let phrase = prepare_phrase(alphabet, phrase);
     let mut result = String::new();
     for (prev, next) in phrase.chars().tuples() {
         let (prev, next) = table.get_pair(prev, next, rev);
@@ -97,21 +122,44 @@ fn proto(phrase: &str, key: &str, rev: bool) -> Result<String, Box<dyn Error>> {
     Ok(result)
 }
 
-pub fn encrypt(phrase: &str, key: &str) -> Result<String, Box<dyn Error>> {
-    proto(phrase, key, false)
+pub fn encrypt(phrase: &str, key: &str) -> Result<String, CipherError> {
+    proto(&Alphabet::new(), phrase, key, false)
+}
+
+pub fn decrypt(phrase: &str, key: &str) -> Result<String, CipherError> {
+    proto(&Alphabet::new(), phrase, key, true)
 }
 
-pub fn decrypt(phrase: &str, key: &str) -> Result<String, Box<dyn Error>> {
-    proto(phrase, key, true)
+/// Letters `ь`, `ё`, `й` and `j` are merged with `ъ`, `е`, `и` and `i`
+/// when the alphabet contains both of them, the rest of the alphabet
+/// must fill a rectangular table.
+pub fn encrypt_with_alphabet(alphabet: &Alphabet, phrase: &str, key: &str) -> Result<String, CipherError> {
+    proto(alphabet, phrase, key, false)
 }
 
-fn validate(phrase: &str, key: &str) -> Result<(), Box<dyn Error>> {
-    let polybius_alp = Alphabet::from("абвгдежзиклмнопрстуфхцчшщъыэюя".to_string());
-    let alphabet = Alphabet::new();
-    if phrase.is_empty() { Err(NullSizedValue::new("Фраза"))?; }
-    if key.is_empty() { Err(NullSizedValue::new("Ключ"))?; }
+pub fn decrypt_with_alphabet(alphabet: &Alphabet, phrase: &str, key: &str) -> Result<String, CipherError> {
+    proto(alphabet, phrase, key, true)
+}
+
+pub struct Playfair;
+
+impl Cipher for Playfair {
+    type Key = String;
+
+    fn encrypt(&self, phrase: &str, key: &String) -> Result<String, CipherError> {
+        encrypt(phrase, key)
+    }
+
+    fn decrypt(&self, phrase: &str, key: &String) -> Result<String, CipherError> {
+        decrypt(phrase, key)
+    }
+}
+
+fn validate(alphabet: &Alphabet, phrase: &str, key: &str) -> Result<(), CipherError> {
+    if phrase.is_empty() { Err(CipherError::EmptyValue { param: "Фраза" })?; }
+    if key.is_empty() { Err(CipherError::EmptyValue { param: "Ключ" })?; }
     alphabet.validate(phrase)?;
-    polybius_alp.validate(key)?;
+    table_letters(alphabet).validate_param(key, "Ключ")?;
     validate_key(key)
 }
 
@@ -123,7 +171,7 @@ mod playfair_tests {
     fn test_prepare_phrase() {
         let phrase = "отодногопорченогояблокавесьвоззагниваеттчк";
         let valid = "отодногопорченогояблокавесъвоззагниваетфтчкф";
-        assert_eq!(prepare_phrase(phrase), valid);
+        assert_eq!(prepare_phrase(&Alphabet::new(), phrase), valid);
     }
 
     #[test]
@@ -144,19 +192,19 @@ mod playfair_tests {
 
     #[test]
     fn test_validate() {
-        validate("аф", "ре").unwrap();
+        validate(&Alphabet::new(), "аф", "ре").unwrap();
     }
 
     #[test]
     #[should_panic]
     fn test_validate_phrase_panic() {
-        validate("z", "респавн").unwrap();
+        validate(&Alphabet::new(), "z", "респавн").unwrap();
     }
 
     #[test]
     #[should_panic]
     fn test_validate_key_panic() {
-        validate("аф", "z").unwrap();
+        validate(&Alphabet::new(), "аф", "z").unwrap();
     }
 
     #[test]
@@ -164,4 +212,23 @@ mod playfair_tests {
     fn test_validate_key_non_unique_panic() {
         validate_key("респавнн").unwrap();
     }
+
+    #[test]
+    fn test_table_letters() {
+        assert_eq!(table_letters(&Alphabet::new()).alphabet(), "абвгдежзиклмнопрстуфхцчшщъыэюя");
+        assert_eq!(table_letters(&Alphabet::russian_with_yo()).len(), 30);
+        assert_eq!(table_letters(&Alphabet::latin()), Alphabet::latin_25());
+        assert_eq!(dimensions(30).unwrap(), (5, 6));
+        assert_eq!(dimensions(25).unwrap(), (5, 5));
+        assert!(dimensions(31).is_err());
+    }
+
+    #[test]
+    fn test_latin() {
+        let alphabet = Alphabet::latin();
+        let result = encrypt_with_alphabet(&alphabet, "hidethegoldinthetreestump", "playfirexm").unwrap();
+        assert_eq!(result, "bmodzbxdnabekudmuixmmouvif");
+        let result = decrypt_with_alphabet(&alphabet, &result, "playfirexm").unwrap();
+        assert_eq!(result, "hidethegoldinthetrexestump");
+    }
 }
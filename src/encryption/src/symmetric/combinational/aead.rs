@@ -0,0 +1,307 @@
+//! Authenticated encryption: Multilinear Galois Mode of RFC 9058 for any
+//! `BlockCipher` and GCM of NIST SP 800-38D for 128-bit ciphers.
+//!
+//! Encryption returns the ciphertext followed by the tag of `tag_len` bytes,
+//! decryption takes the same layout and fails with `InvalidTag` before
+//! returning anything when the data or the associated data were changed.
+
+use crate::errors::CipherError;
+use super::{ct, increment, xor, BlockCipher};
+
+pub fn mgm_encrypt<C: BlockCipher>(
+    data: &[u8], associated: &[u8], nonce: &[u8], tag_len: usize, key: &C
+) -> Result<Vec<u8>, CipherError> {
+    let n = C::BLOCK_SIZE;
+    check_tag(tag_len, n)?;
+    let (counter, auth) = mgm_counters(nonce, key)?;
+    let mut result = mgm_gamma(data, counter, key);
+    let tag = mgm_tag(associated, &result, auth, key);
+    result.extend_from_slice(&tag[..tag_len]);
+    Ok(result)
+}
+
+pub fn mgm_decrypt<C: BlockCipher>(
+    data: &[u8], associated: &[u8], nonce: &[u8], tag_len: usize, key: &C
+) -> Result<Vec<u8>, CipherError> {
+    let n = C::BLOCK_SIZE;
+    check_tag(tag_len, n)?;
+    let (counter, auth) = mgm_counters(nonce, key)?;
+    let (data, tag) = split_tag(data, tag_len)?;
+    if !ct::compare(&mgm_tag(associated, data, auth, key)[..tag_len], tag) { Err(CipherError::InvalidTag)?; }
+    Ok(mgm_gamma(data, counter, key))
+}
+
+/// `nonce` may be of any length, 12 bytes are recommended
+pub fn gcm_encrypt<C: BlockCipher>(
+    data: &[u8], associated: &[u8], nonce: &[u8], tag_len: usize, key: &C
+) -> Result<Vec<u8>, CipherError> {
+    check_tag(tag_len, C::BLOCK_SIZE)?;
+    let (hash, counter) = gcm_counter(nonce, key)?;
+    let mut result = gcm_gamma(data, &counter, key);
+    let tag = gcm_tag(associated, &result, hash, &counter, key);
+    result.extend_from_slice(&tag[..tag_len]);
+    Ok(result)
+}
+
+pub fn gcm_decrypt<C: BlockCipher>(
+    data: &[u8], associated: &[u8], nonce: &[u8], tag_len: usize, key: &C
+) -> Result<Vec<u8>, CipherError> {
+    check_tag(tag_len, C::BLOCK_SIZE)?;
+    let (hash, counter) = gcm_counter(nonce, key)?;
+    let (data, tag) = split_tag(data, tag_len)?;
+    if !ct::compare(&gcm_tag(associated, data, hash, &counter, key)[..tag_len], tag) { Err(CipherError::InvalidTag)?; }
+    Ok(gcm_gamma(data, &counter, key))
+}
+
+fn check_tag(tag_len: usize, n: usize) -> Result<(), CipherError> {
+    if tag_len < 4 || tag_len > n {
+        Err(CipherError::invalid_size(
+            "Имитовставка", &format!("Длина имитовставки должна быть от 4 до {n} байт")
+        ))?;
+    }
+    Ok(())
+}
+
+fn split_tag(data: &[u8], tag_len: usize) -> Result<(&[u8], &[u8]), CipherError> {
+    if data.len() < tag_len {
+        Err(CipherError::invalid_size("Данные", "Данные короче имитовставки"))?;
+    }
+    Ok(data.split_at(data.len() - tag_len))
+}
+
+/// Initial values `Y1` and `Z1` of the encryption and authentication counters
+fn mgm_counters<C: BlockCipher>(nonce: &[u8], key: &C) -> Result<(Vec<u8>, Vec<u8>), CipherError> {
+    let n = C::BLOCK_SIZE;
+    if nonce.len() != n || nonce[0] & 0x80 != 0 {
+        Err(CipherError::invalid_size(
+            "Nonce", &format!("Nonce должен состоять из {n} байт и начинаться с нулевого бита")
+        ))?;
+    }
+    let mut counter = nonce.to_vec();
+    let mut auth = nonce.to_vec();
+    auth[0] |= 0x80;
+    key.encrypt_block(&mut counter);
+    key.encrypt_block(&mut auth);
+    Ok((counter, auth))
+}
+
+/// The right half of the counter is incremented
+fn mgm_gamma<C: BlockCipher>(data: &[u8], mut counter: Vec<u8>, key: &C) -> Vec<u8> {
+    let n = C::BLOCK_SIZE;
+    let mut gamma = vec![0; n];
+    let mut result = Vec::with_capacity(data.len() + n);
+    result.extend_from_slice(data);
+    for part in result.chunks_mut(n) {
+        gamma.copy_from_slice(&counter);
+        key.encrypt_block(&mut gamma);
+        xor(part, &gamma);
+        increment(&mut counter[n / 2..]);
+    }
+    result
+}
+
+/// The left half of the counter is incremented
+fn mgm_tag<C: BlockCipher>(associated: &[u8], data: &[u8], mut counter: Vec<u8>, key: &C) -> Vec<u8> {
+    let n = C::BLOCK_SIZE;
+    let bits = (n * 8) as u32;
+    let mut lengths = to_bytes((associated.len() * 8) as u128, n / 2);
+    lengths.extend(to_bytes((data.len() * 8) as u128, n / 2));
+    let mut hash = vec![0; n];
+    let sum = associated.chunks(n)
+        .chain(data.chunks(n))
+        .chain([lengths.as_slice()])
+        .fold(0, |sum, block| {
+            hash.copy_from_slice(&counter);
+            key.encrypt_block(&mut hash);
+            increment(&mut counter[..n / 2]);
+            sum ^ multiply(to_number(&hash), to_number(&padded(block, n)), bits)
+        });
+    let mut tag = to_bytes(sum, n);
+    key.encrypt_block(&mut tag);
+    tag
+}
+
+/// Hash key `H` and the pre-counter block `J0`
+fn gcm_counter<C: BlockCipher>(nonce: &[u8], key: &C) -> Result<(u128, Vec<u8>), CipherError> {
+    if C::BLOCK_SIZE != 16 { Err(CipherError::UnsupportedOperation)?; }
+    if nonce.is_empty() { Err(CipherError::EmptyValue { param: "Nonce" })?; }
+    let mut hash = [0; 16];
+    key.encrypt_block(&mut hash);
+    let hash = to_number(&hash);
+    let counter = if nonce.len() == 12 {
+        [nonce, &[0, 0, 0, 1]].concat()
+    } else {
+        to_bytes(ghash(hash, &[], nonce), 16)
+    };
+    Ok((hash, counter))
+}
+
+/// Only the last 32 bits of the counter are incremented
+fn gcm_gamma<C: BlockCipher>(data: &[u8], counter: &[u8], key: &C) -> Vec<u8> {
+    let mut counter = counter.to_vec();
+    let mut gamma = [0; 16];
+    let mut result = Vec::with_capacity(data.len() + 16);
+    result.extend_from_slice(data);
+    for part in result.chunks_mut(16) {
+        increment(&mut counter[12..]);
+        gamma.copy_from_slice(&counter);
+        key.encrypt_block(&mut gamma);
+        xor(part, &gamma);
+    }
+    result
+}
+
+fn gcm_tag<C: BlockCipher>(associated: &[u8], data: &[u8], hash: u128, counter: &[u8], key: &C) -> Vec<u8> {
+    let mut tag = counter.to_vec();
+    key.encrypt_block(&mut tag);
+    xor(&mut tag, &to_bytes(ghash(hash, associated, data), 16));
+    tag
+}
+
+fn ghash(hash: u128, associated: &[u8], data: &[u8]) -> u128 {
+    let lengths = (associated.len() as u128 * 8) << 64 | (data.len() as u128 * 8);
+    associated.chunks(16)
+        .chain(data.chunks(16))
+        .map(|block| to_number(&padded(block, 16)))
+        .chain([lengths])
+        .fold(0, |sum, block| gcm_multiply(sum ^ block, hash))
+}
+
+/// Multiplication in GF(2^n) with the polynomials of RFC 9058
+fn multiply(mut left: u128, mut right: u128, bits: u32) -> u128 {
+    let polynomial = if bits == 64 { 0x1b } else { 0x87 };
+    let top = 1 << (bits - 1);
+    let mut result = 0;
+    while right != 0 {
+        if right & 1 == 1 { result ^= left; }
+        left = if left & top != 0 { (left << 1 ^ polynomial) & (top << 1).wrapping_sub(1) } else { left << 1 };
+        right >>= 1;
+    }
+    result
+}
+
+/// Multiplication in GF(2^128) with the reflected bit order of GCM
+fn gcm_multiply(left: u128, right: u128) -> u128 {
+    let mut value = right;
+    let mut result = 0;
+    for bit in (0..128).rev() {
+        if left >> bit & 1 == 1 { result ^= value; }
+        value = if value & 1 == 1 { value >> 1 ^ 0xe1 << 120 } else { value >> 1 };
+    }
+    result
+}
+
+fn padded(block: &[u8], n: usize) -> Vec<u8> {
+    let mut block = block.to_vec();
+    block.resize(n, 0);
+    block
+}
+
+fn to_number(block: &[u8]) -> u128 {
+    block.iter().fold(0, |number, byte| number << 8 | *byte as u128)
+}
+
+fn to_bytes(number: u128, len: usize) -> Vec<u8> {
+    number.to_be_bytes()[16 - len..].to_vec()
+}
+
+#[cfg(test)]
+mod aead_tests {
+    use crate::aes::AesKey;
+    use crate::kuznechik::KuznechikKey;
+    use crate::magma::MagmaKey;
+    use super::*;
+
+    const GCM_KEY: &str = "feffe9928665731c6d6a8f9467308308";
+    const GCM_TEXT: &str = "d9313225f88406e5a55909c5aff5269a86a7a9531534f7da2e4c303d8a318a72\
+                            1c3c0c95956809532fcf0e2449a6b525b16aedf5aa0de657ba637b391aafd255";
+    const GCM_ASSOCIATED: &str = "feedfacedeadbeeffeedfacedeadbeefabaddad2";
+
+    fn bytes(value: &str) -> Vec<u8> {
+        hex::decode(value).unwrap()
+    }
+
+    #[test]
+    fn test_mgm_kuznechik() {
+        let key = KuznechikKey::from_hex("8899aabbccddeeff0011223344556677fedcba98765432100123456789abcdef").unwrap();
+        let nonce = bytes("1122334455667700ffeeddccbbaa9988");
+        let associated = bytes("0202020202020202010101010101010104040404040404040303030303030303ea0505050505050505");
+        let data = bytes("1122334455667700ffeeddccbbaa998800112233445566778899aabbcceeff0a\
+                          112233445566778899aabbcceeff0a002233445566778899aabbcceeff0a0011aabbcc");
+        let result = mgm_encrypt(&data, &associated, &nonce, 16, &key).unwrap();
+        assert_eq!(hex::encode(&result), "a9757b8147956e9055b8a33de89f42fc8075d2212bf9fd5bd3f7069aadc16b39\
+                                          497ab15915a6ba85936b5d0ea9f6851cc60c14d4d3f883d0ab94420695c76deb2c7552\
+                                          cf5d656f40c34f5c46e8bb0e29fcdb4c");
+        assert_eq!(mgm_decrypt(&result, &associated, &nonce, 16, &key).unwrap(), data);
+    }
+
+    #[test]
+    fn test_mgm_magma() {
+        let key = MagmaKey::from_hex("ffeeddccbbaa99887766554433221100f0f1f2f3f4f5f6f7f8f9fafbfcfdfeff").unwrap();
+        let nonce = bytes("12def06b3c130a59");
+        let associated = bytes("01010101010101010202020202020202030303030303030304040404040404040505050505050505ea");
+        let data = bytes("ffeeddccbbaa998811223344556677008899aabbcceeff0a0011223344556677\
+                          99aabbcceeff0a001122334455667788aabbcceeff0a00112233445566778899aabbcc");
+        let result = mgm_encrypt(&data, &associated, &nonce, 8, &key).unwrap();
+        assert_eq!(hex::encode(&result), "c795066c5f9ea03b85113342459185ae1f2e00d6bf2b785d940470b8bb9c8e7d\
+                                          9a5dd3731f7ddc70ec27cb0ace6fa57670f65c646abb75d547aa37c3bcb5c34e03bb9c\
+                                          a7928069aa10fd10");
+        assert_eq!(mgm_decrypt(&result, &associated, &nonce, 8, &key).unwrap(), data);
+    }
+
+    #[test]
+    fn test_mgm_errors() {
+        let key = MagmaKey::from_hex("ffeeddccbbaa99887766554433221100f0f1f2f3f4f5f6f7f8f9fafbfcfdfeff").unwrap();
+        let nonce = bytes("12def06b3c130a59");
+        let mut result = mgm_encrypt(b"data", b"header", &nonce, 4, &key).unwrap();
+        assert_eq!(result.len(), 8);
+        assert_eq!(mgm_decrypt(&result, b"header", &nonce, 4, &key).unwrap(), b"data");
+        assert_eq!(mgm_decrypt(&result, b"Header", &nonce, 4, &key), Err(CipherError::InvalidTag));
+        result[0] ^= 1;
+        assert_eq!(mgm_decrypt(&result, b"header", &nonce, 4, &key), Err(CipherError::InvalidTag));
+        assert!(mgm_encrypt(b"data", b"", &bytes("92def06b3c130a59"), 4, &key).is_err());
+        assert!(mgm_encrypt(b"data", b"", &nonce, 9, &key).is_err());
+        assert!(mgm_decrypt(&result[..3], b"", &nonce, 4, &key).is_err());
+    }
+
+    #[test]
+    fn test_gcm() {
+        let key = AesKey::new([0; 16]);
+        let nonce = [0; 12];
+        assert_eq!(hex::encode(gcm_encrypt(&[], &[], &nonce, 16, &key).unwrap()), "58e2fccefa7e3061367f1d57a4e7455a");
+        assert_eq!(
+            hex::encode(gcm_encrypt(&[0; 16], &[], &nonce, 16, &key).unwrap()),
+            "0388dace60b6a392f328c2b971b2fe78ab6e47d42cec13bdf53a67b21257bddf"
+        );
+        let key = AesKey::from_hex(GCM_KEY).unwrap();
+        let nonce = bytes("cafebabefacedbaddecaf888");
+        let result = gcm_encrypt(&bytes(GCM_TEXT), &[], &nonce, 16, &key).unwrap();
+        assert_eq!(hex::encode(&result[64..]), "4d5c2af327cd64a62cf35abd2ba6fab4");
+        assert_eq!(hex::encode(&result[..16]), "42831ec2217774244b7221b784d0d49c");
+        let data = &bytes(GCM_TEXT)[..60];
+        let result = gcm_encrypt(data, &bytes(GCM_ASSOCIATED), &nonce, 16, &key).unwrap();
+        assert_eq!(hex::encode(&result[60..]), "5bc94fbc3221a5db94fae95ae7121a47");
+        assert_eq!(gcm_decrypt(&result, &bytes(GCM_ASSOCIATED), &nonce, 16, &key).unwrap(), data);
+    }
+
+    #[test]
+    fn test_gcm_nonce() {
+        let key = AesKey::from_hex(GCM_KEY).unwrap();
+        let data = &bytes(GCM_TEXT)[..60];
+        let result = gcm_encrypt(data, &bytes(GCM_ASSOCIATED), &bytes("cafebabefacedbad"), 16, &key).unwrap();
+        assert_eq!(hex::encode(&result[..8]), "61353b4c2806934a");
+        assert_eq!(hex::encode(&result[60..]), "3612d2e79e3b0785561be14aaca2fccb");
+    }
+
+    #[test]
+    fn test_gcm_errors() {
+        let key = AesKey::from_hex(GCM_KEY).unwrap();
+        let nonce = bytes("cafebabefacedbaddecaf888");
+        let mut result = gcm_encrypt(b"data", b"header", &nonce, 12, &key).unwrap();
+        assert_eq!(gcm_decrypt(&result, b"header", &nonce, 12, &key).unwrap(), b"data");
+        result[15] ^= 1;
+        assert_eq!(gcm_decrypt(&result, b"header", &nonce, 12, &key), Err(CipherError::InvalidTag));
+        assert_eq!(gcm_encrypt(b"data", b"", &nonce, 8, &MagmaKey::new([0; 32])), Err(CipherError::UnsupportedOperation));
+        assert_eq!(gcm_encrypt(b"data", b"", &[], 16, &key), Err(CipherError::EmptyValue { param: "Nonce" }));
+    }
+}
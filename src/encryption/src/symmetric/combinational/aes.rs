@@ -1,9 +1,14 @@
-use std::error::Error;
+use std::fmt;
+use std::sync::OnceLock;
+use zeroize::Zeroize;
+use crate::errors::CipherError;
+use crate::cipher::Cipher;
 use crate::methods::{bytes_to_hex, hex_to_bytes};
+use crate::secret::Secret;
+use super::{aead, gf_mul, key_from_hex, keywrap, lookup, mac, modes, process_blocks, BlockCipher};
+use super::padding::Padding;
 
-const NK: usize = 4;
 const NB: usize = 4;
-const NR: usize = 10;
 
 const S: [u8; 256] = [
     0x63, 0x7c, 0x77, 0x7b, 0xf2, 0x6b, 0x6f, 0xc5, 0x30, 0x01, 0x67, 0x2b, 0xfe, 0xd7, 0xab, 0x76,
@@ -59,164 +64,451 @@ const R_CON: [[u8; 4]; 10] = [
 const MIX: [u8; 4] = [0x02, 0x03, 0x01, 0x01];
 const INV_MIX: [u8; 4] = [0x0e, 0x0b, 0x0d, 0x09];
 
-fn key_expansion(key: &[u8]) -> Vec<Vec<u8>> {
-    let mut key: Vec<u8> = key.to_vec();
-    key.append(&mut vec![0x01u8; 4*NK-key.len()]);
-    let mut result: Vec<Vec<u8>> = fill_state(&key);
-    for col in NK..NB*(NR+1) {
-        if col % NK == 0 {
-            let mut tmp: Vec<u8> = (1..4usize).map(|row| result[row][col-1]).collect();
-            tmp.push(result[0][col-1]);
-            for item in tmp.iter_mut() {
-                let sbox_elem =  S[*item as usize];
-                *item = sbox_elem;
-            }
+/// Length of the key in words and the number of rounds
+fn key_params(size: KeySize) -> (usize, usize) {
+    let nk = size.bytes() / 4;
+    (nk, nk + 6)
+}
+
+fn sub_word(word: u32) -> u32 {
+    u32::from_be_bytes(word.to_be_bytes().map(|byte| lookup(&S, byte as usize)))
+}
+
+/// Words of the key schedule, a word is a column of the round key
+fn key_expansion(key: &[u8], size: KeySize) -> Vec<u32> {
+    let (nk, nr) = key_params(size);
+    let mut result: Vec<u32> = key.chunks(4).take(nk)
+        .map(|word| u32::from_be_bytes(word.try_into().unwrap()))
+        .collect();
+    for col in nk..NB*(nr+1) {
+        let mut tmp = result[col - 1];
+        if col % nk == 0 {
+            tmp = sub_word(tmp.rotate_left(8)) ^ u32::from_be_bytes(R_CON[col/nk - 1]);
+        } else if nk > 6 && col % nk == 4 {
+            tmp = sub_word(tmp);
+        }
+        result.push(result[col - nk] ^ tmp);
+    }
+    result
+}
+
+/// Key schedule of the equivalent inverse cipher: the round keys in reverse order
+/// with InvMixColumns applied to all of them except the first and the last
+fn decryption_keys(key_schedule: &[u32]) -> Vec<u32> {
+    let tables = tables();
+    let nr = rounds(key_schedule);
+    (0..=nr).rev().flat_map(|rnd| (0..NB).map(move |col| {
+        let word = key_schedule[NB*rnd + col];
+        if rnd == 0 || rnd == nr { return word; }
+        word.to_be_bytes().iter().enumerate()
+            .fold(0, |acc, (row, byte)| acc ^ lookup(&tables.decrypt[row], lookup(&S, *byte as usize) as usize))
+    })).collect()
+}
+
+fn rounds(key_schedule: &[u32]) -> usize {
+    key_schedule.len() / NB - 1
+}
+
+/// T-tables: SubBytes and MixColumns of a single byte in every row of a column
+struct Tables {
+    encrypt: [[u32; 256]; 4],
+    decrypt: [[u32; 256]; 4]
+}
+
+fn tables() -> &'static Tables {
+    static TABLES: OnceLock<Tables> = OnceLock::new();
+    TABLES.get_or_init(|| {
+        // The column of the circulant matrix is its first row read backwards
+        let column = |value: u8, coef: &[u8; 4]| u32::from_be_bytes(
+            [coef[0], coef[3], coef[2], coef[1]].map(|coef| gf_mul(value, coef, 0x1b))
+        );
+        let mut tables = Tables { encrypt: [[0; 256]; 4], decrypt: [[0; 256]; 4] };
+        for byte in 0..256 {
+            let encrypt = column(S[byte], &MIX);
+            let decrypt = column(S_REVERSE[byte], &INV_MIX);
             for row in 0..4 {
-                let s = result[row][col - 4]^tmp[row]^R_CON[col/NK - 1][row];
-                result[row].push(s)
-            }
-        } else {
-            for row in result[0..4].iter_mut() {
-                let s = row[col - 4]^row[col - 1];
-                row.push(s);
+                tables.encrypt[row][byte] = encrypt.rotate_right(8 * row as u32);
+                tables.decrypt[row][byte] = decrypt.rotate_right(8 * row as u32);
             }
         }
+        tables
+    })
+}
+
+/// Combines the bytes of one column after ShiftRows,
+/// `step` is 1 for ShiftRows and 3 for InvShiftRows
+fn column<F>(state: &[u32; NB], col: usize, step: usize, func: F) -> u32
+    where F: Fn(usize, usize) -> u32
+{
+    (0..4).fold(0, |acc, row| {
+        let byte = state[(col + row * step) % NB] >> (24 - 8 * row) & 0xff;
+        acc ^ func(row, byte as usize)
+    })
+}
+
+fn transform(input: &[u8; 16], keys: &[u32], step: usize, table: &[[u32; 256]; 4], sbox: &[u8; 256]) -> [u8; 16] {
+    let nr = rounds(keys);
+    let mut state: [u32; NB] = std::array::from_fn(|col| {
+        u32::from_be_bytes(input[4*col..4*col + 4].try_into().unwrap()) ^ keys[col]
+    });
+    for rnd in 1..nr {
+        state = std::array::from_fn(|col| {
+            column(&state, col, step, |row, byte| lookup(&table[row], byte)) ^ keys[NB*rnd + col]
+        });
+    }
+    state = std::array::from_fn(|col| {
+        column(&state, col, step, |row, byte| (lookup(sbox, byte) as u32) << (24 - 8 * row)) ^ keys[NB*nr + col]
+    });
+    let mut result = [0; 16];
+    for (chunk, word) in result.chunks_mut(4).zip(state) {
+        chunk.copy_from_slice(&word.to_be_bytes());
     }
     result
 }
 
-fn add_round_key(state: &[Vec<u8>], key_schedule: &[Vec<u8>], round: usize) -> Vec<Vec<u8>> {
-    let mut result = vec![
-        vec![0; NK],
-        vec![0; NK],
-        vec![0; NK],
-        vec![0; NK],
-    ];
-    for col in 0..NK {
-        let s0 = state[0][col]^key_schedule[0][NB*round + col];
-        let s1 = state[1][col]^key_schedule[1][NB*round + col];
-        let s2 = state[2][col]^key_schedule[2][NB*round + col];
-        let s3 = state[3][col]^key_schedule[3][NB*round + col];
-        result[0][col] = s0;
-        result[1][col] = s1;
-        result[2][col] = s2;
-        result[3][col] = s3;
+fn enc(input: &[u8; 16], key_schedule: &[u32]) -> [u8; 16] {
+    transform(input, key_schedule, 1, &tables().encrypt, &S)
+}
+
+/// `key_schedule` comes from `decryption_keys`
+fn dec(input: &[u8; 16], key_schedule: &[u32]) -> [u8; 16] {
+    transform(input, key_schedule, 3, &tables().decrypt, &S_REVERSE)
+}
+
+/// Length of the AES key
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum KeySize {
+    #[default]
+    Aes128,
+    Aes192,
+    Aes256,
+}
+
+impl KeySize {
+    pub const ALL: [KeySize; 3] = [KeySize::Aes128, KeySize::Aes192, KeySize::Aes256];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            KeySize::Aes128 => "AES-128",
+            KeySize::Aes192 => "AES-192",
+            KeySize::Aes256 => "AES-256",
+        }
+    }
+
+    /// Length of the key in bytes
+    pub fn bytes(&self) -> usize {
+        match self {
+            KeySize::Aes128 => 16,
+            KeySize::Aes192 => 24,
+            KeySize::Aes256 => 32,
+        }
+    }
+
+    pub fn from_bytes(len: usize) -> Option<Self> {
+        KeySize::ALL.into_iter().find(|size| size.bytes() == len)
     }
-    result
 }
 
-fn sub_bytes(state: &[Vec<u8>], sbox: &[u8]) -> Vec<Vec<u8>> {
-    state.iter().map(|line| (*line).iter().map(|item| sbox[*item as usize]
-    ).collect()).collect()
+/// Aes key with the round keys expanded once
+#[derive(Clone)]
+pub struct AesKey {
+    keys: Vec<u32>,
+    inv_keys: Vec<u32>,
+    size: KeySize
 }
 
-fn left_shift(line: &[u8], count: usize) -> Vec<u8> {
-    let mut result = line[count..].to_vec();
-    if count != 0 {
-        result.append(&mut line[0..count].to_vec());
+impl AesKey {
+    pub fn new(key: [u8; 16]) -> Self {
+        AesKey::expand(&key, KeySize::Aes128)
+    }
+
+    fn expand(key: &[u8], size: KeySize) -> Self {
+        let keys = key_expansion(key, size);
+        AesKey { inv_keys: decryption_keys(&keys), keys, size }
+    }
+
+    pub fn from_hex(key: &str) -> Result<Self, CipherError> {
+        Self::try_from(Secret::<Vec<u8>>::from_hex(key)?.expose().as_slice())
+    }
+
+    pub fn size(&self) -> KeySize {
+        self.size
     }
-    result
 }
 
-fn right_shift(line: &[u8], count: usize) -> Vec<u8> {
-    let mut result = line[line.len()-count..].to_vec();
-    result.append(&mut line[0..line.len()-count].to_vec());
-    result
+impl Drop for AesKey {
+    fn drop(&mut self) {
+        self.keys.zeroize();
+        self.inv_keys.zeroize();
+    }
 }
 
-fn shift_rows(state: &[Vec<u8>]) -> Vec<Vec<u8>> {
-    state.iter().enumerate().map(|(i, line)| left_shift(line, i)).collect()
+impl fmt::Debug for AesKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AesKey").field("size", &self.size).finish_non_exhaustive()
+    }
 }
 
-fn inv_shift_rows(state: &[Vec<u8>]) -> Vec<Vec<u8>> {
-    state.iter().enumerate().map(|(i, line)| right_shift(line, i)).collect()
+impl From<[u8; 16]> for AesKey {
+    fn from(value: [u8; 16]) -> Self {
+        AesKey::new(value)
+    }
 }
 
-fn gf_mul(mut left: u8, mut right: u8) -> u8 {
-    let mut result: u8 = 0;
-    let mut hi_bit: u8;
-    for _ in 0..8 {
-        if right & 1 != 0 { result ^= left }
-        hi_bit = left & 0x80;
-        left <<= 1;
-        if hi_bit != 0 { left ^= 0x1b }
-        right >>= 1;
+impl From<[u8; 24]> for AesKey {
+    fn from(value: [u8; 24]) -> Self {
+        AesKey::expand(&value, KeySize::Aes192)
     }
-    result
 }
 
-fn mix_columns(state: &[Vec<u8>], coef: &[u8]) -> Vec<Vec<u8>> {
-    (0..4).map(|i| (0..NB).map(|j| {
-        let mut s = 0;
-        for (k, coef) in right_shift(coef, i).iter().enumerate() {
-            s ^= gf_mul(state[k][j], *coef);
-        }
-        s
-    }).collect()).collect()
+impl From<[u8; 32]> for AesKey {
+    fn from(value: [u8; 32]) -> Self {
+        AesKey::expand(&value, KeySize::Aes256)
+    }
 }
 
-fn fill_state(input: &[u8]) -> Vec<Vec<u8>> {
-    (0..4).map(|r| (0..NB).map(|c| input[r+4*c]).collect()).collect()
+/// Expands the key without copying it out of the `Secret`
+impl TryFrom<&Secret<Vec<u8>>> for AesKey {
+    type Error = CipherError;
+
+    fn try_from(value: &Secret<Vec<u8>>) -> Result<Self, Self::Error> {
+        AesKey::try_from(value.expose().as_slice())
+    }
 }
 
-fn fill_result(state: &[Vec<u8>]) -> Vec<u8> {
-    let mut result = Vec::new();
-    for r in 0..4 {
-        result.append(&mut (0..NB).map(|c| state[c][r]).collect());
+/// Accepts keys of 16, 24 and 32 bytes
+impl TryFrom<&[u8]> for AesKey {
+    type Error = CipherError;
+
+    fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
+        match KeySize::from_bytes(value.len()) {
+            Some(size) => Ok(AesKey::expand(value, size)),
+            None => Err(CipherError::invalid_size("Ключ", "Ключ должен состоять из 16, 24 или 32 байт"))
+        }
     }
-    result
 }
 
-fn enc(input: &[u8], key_schedule: &[Vec<u8>]) -> Vec<u8> {
-    let mut state = fill_state(input);
-    state = add_round_key(&state, key_schedule, 0);
-    for rnd in 1..NR {
-        state = sub_bytes(&state, &S);
-        state = shift_rows(&state);
-        state = mix_columns(&state, &MIX);
-        state = add_round_key(&state, key_schedule, rnd);
+/// Keys shorter than 16 bytes are padded with zeros the way the string functions always did
+fn key_from_str(key: &str) -> Result<AesKey, CipherError> {
+    let bytes = Secret::<Vec<u8>>::from_hex(key)?;
+    if bytes.expose().len() < 16 {
+        AesKey::try_from(key_from_hex::<16>(key)?.expose().as_slice())
+    } else {
+        AesKey::try_from(&bytes)
     }
-    state = sub_bytes(&state, &S);
-    state = shift_rows(&state);
-    state = add_round_key(&state, key_schedule, NR);
-    fill_result(&state)
 }
 
-fn dec(input: &[u8], key_schedule: &[Vec<u8>]) -> Vec<u8> {
-    let mut state = fill_state(input);
-    state = add_round_key(&state, key_schedule, NR);
-    for rnd in (1..NR).rev() {
-        state = inv_shift_rows(&state);
-        state = sub_bytes(&state, &S_REVERSE);
-        state = add_round_key(&state, key_schedule, rnd);
-        state = mix_columns(&state, &INV_MIX);
+
+impl BlockCipher for AesKey {
+    const BLOCK_SIZE: usize = 16;
+
+    fn encrypt_block(&self, block: &mut [u8]) {
+        let block: &mut [u8; 16] = block.try_into().unwrap();
+        *block = enc(block, &self.keys);
+    }
+
+    fn decrypt_block(&self, block: &mut [u8]) {
+        let block: &mut [u8; 16] = block.try_into().unwrap();
+        *block = dec(block, &self.inv_keys);
     }
-    state = inv_shift_rows(&state);
-    state = sub_bytes(&state, &S_REVERSE);
-    state = add_round_key(&state, key_schedule, 0);
-    fill_result(&state)
 }
 
-fn proto<T>(input: &[u8], key: &str, func: T) -> Result<Vec<u8>, Box<dyn Error>>
-    where T: Fn(&[u8], &[Vec<u8>]) -> Vec<u8>
-{
-    let key = hex_to_bytes(key, 16)?;
-    let key_schedule = key_expansion(&key);
-    let mut result = Vec::new();
-    for part in input.windows(16).step_by(16) {
-        result.append(&mut func(part, &key_schedule));
+pub fn encrypt_block(block: &[u8; 16], key: &AesKey) -> [u8; 16] {
+    enc(block, &key.keys)
+}
+
+pub fn decrypt_block(block: &[u8; 16], key: &AesKey) -> [u8; 16] {
+    dec(block, &key.inv_keys)
+}
+
+pub fn encrypt_bytes(data: &[u8], key: &AesKey) -> Result<Vec<u8>, CipherError> {
+    process_blocks(data, 16, |block| key.encrypt_block(block))
+}
+
+pub fn decrypt_bytes(data: &[u8], key: &AesKey) -> Result<Vec<u8>, CipherError> {
+    process_blocks(data, 16, |block| key.decrypt_block(block))
+}
+
+/// CTR mode, the counter starts with `init_v` followed by zeros
+pub fn ctr(data: &[u8], init_v: &[u8; 8], key: &AesKey) -> Result<Vec<u8>, CipherError> {
+    modes::ctr(data, init_v, 16, key)
+}
+
+/// CTR mode with the whole initial counter block (NIST SP 800-38A)
+pub fn ctr_from(data: &[u8], counter: &[u8; 16], key: &AesKey) -> Result<Vec<u8>, CipherError> {
+    modes::ctr_from(data, counter, 16, key)
+}
+
+pub fn encrypt_padded(data: &[u8], padding: Padding, key: &AesKey) -> Result<Vec<u8>, CipherError> {
+    modes::ecb_encrypt(data, padding, key)
+}
+
+pub fn decrypt_padded(data: &[u8], padding: Padding, key: &AesKey) -> Result<Vec<u8>, CipherError> {
+    modes::ecb_decrypt(data, padding, key)
+}
+
+/// AES-CMAC of RFC 4493
+pub fn cmac(data: &[u8], key: &AesKey) -> [u8; 16] {
+    mac::mac(data, 16, key).unwrap().try_into().unwrap()
+}
+
+/// GCM of NIST SP 800-38D, the tag of `tag_len` bytes follows the ciphertext
+pub fn gcm_encrypt(
+    data: &[u8], associated: &[u8], nonce: &[u8], tag_len: usize, key: &AesKey
+) -> Result<Vec<u8>, CipherError> {
+    aead::gcm_encrypt(data, associated, nonce, tag_len, key)
+}
+
+pub fn gcm_decrypt(
+    data: &[u8], associated: &[u8], nonce: &[u8], tag_len: usize, key: &AesKey
+) -> Result<Vec<u8>, CipherError> {
+    aead::gcm_decrypt(data, associated, nonce, tag_len, key)
+}
+
+/// AES Key Wrap of RFC 3394
+pub fn wrap(data: &[u8], key: &AesKey) -> Result<Vec<u8>, CipherError> {
+    keywrap::wrap(data, key)
+}
+
+pub fn unwrap(data: &[u8], key: &AesKey) -> Result<Vec<u8>, CipherError> {
+    keywrap::unwrap(data, key)
+}
+
+/// AES Key Wrap with Padding of RFC 5649
+pub fn wrap_padded(data: &[u8], key: &AesKey) -> Result<Vec<u8>, CipherError> {
+    keywrap::wrap_padded(data, key)
+}
+
+pub fn unwrap_padded(data: &[u8], key: &AesKey) -> Result<Vec<u8>, CipherError> {
+    keywrap::unwrap_padded(data, key)
+}
+
+/// The phrase is completed with zeros to the block border
+pub fn encrypt(phrase: &str, key: &str) -> Result<String, CipherError> {
+    encrypt_with_padding(phrase, key, Padding::Zero)
+}
+
+pub fn decrypt(phrase: &str, key: &str) -> Result<String, CipherError> {
+    decrypt_with_padding(phrase, key, Padding::Zero)
+}
+
+pub fn encrypt_with_padding(phrase: &str, key: &str, padding: Padding) -> Result<String, CipherError> {
+    let key = key_from_str(key)?;
+    Ok(bytes_to_hex(&encrypt_padded(&hex_to_bytes(phrase, 1)?, padding, &key)?))
+}
+
+pub fn decrypt_with_padding(phrase: &str, key: &str, padding: Padding) -> Result<String, CipherError> {
+    let key = key_from_str(key)?;
+    Ok(bytes_to_hex(&decrypt_padded(&hex_to_bytes(phrase, 1)?, padding, &key)?))
+}
+
+/// State of the block after every step of a round
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Round {
+    pub sub_bytes: [u8; 16],
+    pub shift_rows: [u8; 16],
+    /// Absent in the last round
+    pub mix_columns: Option<[u8; 16]>,
+    pub add_round_key: [u8; 16],
+}
+
+/// Log of the encryption of one block
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Trace {
+    /// Round keys of the key expansion, the first one is added to the input
+    pub round_keys: Vec<[u8; 16]>,
+    /// State after the initial AddRoundKey
+    pub initial: [u8; 16],
+    pub rounds: Vec<Round>,
+}
+
+impl fmt::Display for Trace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Раундовые ключи:")?;
+        for (i, key) in self.round_keys.iter().enumerate() {
+            writeln!(f, "K{i}: {}", bytes_to_hex(key))?;
+        }
+        writeln!(f, "AddRoundKey: {}", bytes_to_hex(&self.initial))?;
+        for (i, round) in self.rounds.iter().enumerate() {
+            writeln!(f, "Раунд {}", i + 1)?;
+            writeln!(f, "SubBytes: {}", bytes_to_hex(&round.sub_bytes))?;
+            writeln!(f, "ShiftRows: {}", bytes_to_hex(&round.shift_rows))?;
+            if let Some(mix_columns) = &round.mix_columns {
+                writeln!(f, "MixColumns: {}", bytes_to_hex(mix_columns))?;
+            }
+            writeln!(f, "AddRoundKey: {}", bytes_to_hex(&round.add_round_key))?;
+        }
+        Ok(())
     }
-    Ok(result)
 }
 
-pub fn encrypt(input: &str, key: &str) -> Result<String, Box<dyn Error>> {
-    let input = hex_to_bytes(input, 16)?;
-    Ok(bytes_to_hex(&proto(&input, key, enc)?))
+/// The state is stored column by column, the byte of `row` and `col` is `state[4*col + row]`
+fn add_round_key(state: &[u8; 16], key: &[u8; 16]) -> [u8; 16] {
+    std::array::from_fn(|i| state[i] ^ key[i])
 }
 
-pub fn decrypt(input: &str, key: &str) -> Result<String, Box<dyn Error>> {
-    let input = hex_to_bytes(input, 16)?;
-    Ok(bytes_to_hex(&proto(&input, key, dec)?))
+fn sub_bytes(state: &[u8; 16]) -> [u8; 16] {
+    state.map(|byte| lookup(&S, byte as usize))
+}
+
+fn shift_rows(state: &[u8; 16]) -> [u8; 16] {
+    std::array::from_fn(|i| {
+        let (col, row) = (i / 4, i % 4);
+        state[4*((col + row) % NB) + row]
+    })
+}
+
+fn mix_columns(state: &[u8; 16]) -> [u8; 16] {
+    std::array::from_fn(|i| {
+        let (col, row) = (i / 4, i % 4);
+        (0..4).fold(0, |acc, j| acc ^ gf_mul(state[4*col + j], MIX[(j + 4 - row) % 4], 0x1b))
+    })
+}
+
+/// Encrypts the block step by step without the T-tables
+pub fn encrypt_block_traced(block: &[u8; 16], key: &AesKey) -> ([u8; 16], Trace) {
+    let round_keys: Vec<[u8; 16]> = key.keys.chunks(NB).map(|words| {
+        let mut result = [0; 16];
+        for (chunk, word) in result.chunks_mut(4).zip(words) {
+            chunk.copy_from_slice(&word.to_be_bytes());
+        }
+        result
+    }).collect();
+    let initial = add_round_key(block, &round_keys[0]);
+    let mut state = initial;
+    let mut rounds = Vec::with_capacity(round_keys.len() - 1);
+    for (rnd, round_key) in round_keys.iter().enumerate().skip(1) {
+        let sub_bytes = sub_bytes(&state);
+        let shift_rows = shift_rows(&sub_bytes);
+        let mix_columns = (rnd + 1 < round_keys.len()).then(|| mix_columns(&shift_rows));
+        state = add_round_key(mix_columns.as_ref().unwrap_or(&shift_rows), round_key);
+        rounds.push(Round { sub_bytes, shift_rows, mix_columns, add_round_key: state });
+    }
+    (state, Trace { round_keys, initial, rounds })
+}
+
+/// Same as `encrypt` with the log of every block
+pub fn encrypt_traced(phrase: &str, key: &str) -> Result<(String, Vec<Trace>), CipherError> {
+    let key = key_from_str(key)?;
+    let data = Padding::Zero.pad(&hex_to_bytes(phrase, 1)?, 16)?;
+    let (result, traces): (Vec<[u8; 16]>, Vec<Trace>) = data.chunks(16)
+        .map(|block| encrypt_block_traced(block.try_into().unwrap(), &key))
+        .unzip();
+    Ok((bytes_to_hex(result.as_flattened()), traces))
+}
+
+pub struct Aes;
+
+impl Cipher for Aes {
+    type Key = AesKey;
+
+    fn encrypt(&self, phrase: &str, key: &AesKey) -> Result<String, CipherError> {
+        Ok(bytes_to_hex(&encrypt_padded(&hex_to_bytes(phrase, 1)?, Padding::Zero, key)?))
+    }
+
+    fn decrypt(&self, phrase: &str, key: &AesKey) -> Result<String, CipherError> {
+        Ok(bytes_to_hex(&decrypt_padded(&hex_to_bytes(phrase, 1)?, Padding::Zero, key)?))
+    }
 }
 
 #[cfg(test)]
@@ -274,12 +566,9 @@ mod aes_tests {
             [0xe1, 0x3f, 0x0c, 0xc8],
             [0xb6, 0x63, 0x0c, 0xa6],
         ];
-        let mut result: [[u8; 4]; 44] = [[0; 4]; 44];
-        for (i, line) in key_expansion(&key).into_iter().enumerate() {
-            for (j, item) in line.into_iter().enumerate() {
-                result[j][i] = item;
-            }
-        }
+        let result: Vec<[u8; 4]> = key_expansion(&key, KeySize::Aes128).iter()
+            .map(|word| word.to_be_bytes())
+            .collect();
         assert_eq!(result, validate);
     }
 
@@ -291,7 +580,7 @@ mod aes_tests {
             0xab, 0xf7, 0x15, 0x88, 0x09, 0xcf, 0x4f, 0x3c];
         let valid = [0x39, 0x25, 0x84, 0x1d, 0x02, 0xdc, 0x09, 0xfb,
             0xdc, 0x11, 0x85, 0x97, 0x19, 0x6a, 0x0b, 0x32];
-        let key_sch = key_expansion(&key);
+        let key_sch = key_expansion(&key, KeySize::Aes128);
         let result = enc(&phrase, &key_sch);
         assert_eq!(result, valid);
     }
@@ -304,7 +593,7 @@ mod aes_tests {
             0xab, 0xf7, 0x15, 0x88, 0x09, 0xcf, 0x4f, 0x3c];
         let valid = [0x32, 0x43, 0xf6, 0xa8, 0x88, 0x5a, 0x30, 0x8d,
             0x31, 0x31, 0x98, 0xa2, 0xe0, 0x37, 0x07, 0x34];
-        let key_sch = key_expansion(&key);
+        let key_sch = decryption_keys(&key_expansion(&key, KeySize::Aes128));
         let result = dec(&phrase, &key_sch);
         assert_eq!(result, valid);
     }
@@ -321,6 +610,118 @@ mod aes_tests {
         let phrase = "8d07448105b69375e77ae4826efa2cd95bbd55430f1a894adf175ad47741caf2f03d3b8f32eede0a578380d4578bfe9ff284f34bf3d995fe80d17d323cd8d1e7b628fca087c7a232f48ce6d172b8770bd198b67d94f76958180cc9c32c2b3ec3";
         let key = "2b7e151628aed2a6abf7158809cf4f3c";
         let valid = "От одного порченого яблока весь воз загнивает.";
-        assert_eq!(valid, hex_to_str(&decrypt(phrase, key).unwrap()).unwrap());
+        assert_eq!(format!("{valid}{}", "\0".repeat(11)), hex_to_str(&decrypt(phrase, key).unwrap()).unwrap());
+    }
+
+    #[test]
+    fn test_with_padding() {
+        let key = "2b7e151628aed2a6abf7158809cf4f3c";
+        let phrase = "00112233445566778899aabbccddee00";
+        for padding in [Padding::Pkcs7, Padding::Iso7816] {
+            let result = encrypt_with_padding(phrase, key, padding).unwrap();
+            assert_eq!(result.len(), 64);
+            assert_eq!(decrypt_with_padding(&result, key, padding).unwrap(), phrase);
+        }
+        assert!(encrypt_with_padding(&phrase[..30], key, Padding::None).is_err());
+        assert_eq!(encrypt_with_padding(phrase, key, Padding::None).unwrap(), encrypt(phrase, key).unwrap());
+    }
+
+    #[test]
+    fn test_bytes() {
+        let key = AesKey::from_hex("000102030405060708090a0b0c0d0e0f").unwrap();
+        let data = hex_to_bytes("00112233445566778899aabbccddeeff", 1).unwrap();
+        let result = encrypt_bytes(&data, &key).unwrap();
+        assert_eq!(bytes_to_hex(&result), "69c4e0d86a7b0430d8cdb78070b4c55a");
+        assert_eq!(decrypt_bytes(&result, &key).unwrap(), data);
+        let block: [u8; 16] = data.clone().try_into().unwrap();
+        assert_eq!(decrypt_block(&encrypt_block(&block, &key), &key), block);
+        assert!(encrypt_bytes(&data[1..], &key).is_err());
+        assert!(AesKey::try_from([0u8; 15].as_slice()).is_err());
+    }
+
+    #[test]
+    fn test_ctr() {
+        let key = AesKey::from_hex("000102030405060708090a0b0c0d0e0f").unwrap();
+        let init_v = [0x00, 0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77];
+        let data = hex_to_bytes("00000000000000000000000000000000ffff", 1).unwrap();
+        let result = ctr(&data, &init_v, &key).unwrap();
+        let gamma = encrypt_block(&[0x00, 0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77, 0, 0, 0, 0, 0, 0, 0, 0], &key);
+        assert_eq!(result[..16], gamma);
+        assert_eq!(result.len(), 18);
+        assert_eq!(ctr(&result, &init_v, &key).unwrap(), data);
+    }
+
+    #[test]
+    fn test_ctr_from() {
+        // NIST SP 800-38A F.5.1 and F.5.2
+        let key = AesKey::from_hex("2b7e151628aed2a6abf7158809cf4f3c").unwrap();
+        let counter = [0xf0, 0xf1, 0xf2, 0xf3, 0xf4, 0xf5, 0xf6, 0xf7, 0xf8, 0xf9, 0xfa, 0xfb, 0xfc, 0xfd, 0xfe, 0xff];
+        let data = hex_to_bytes(
+            "6bc1bee22e409f96e93d7e117393172aae2d8a571e03ac9c9eb76fac45af8e51\
+             30c81c46a35ce411e5fbc1191a0a52eff69f2445df4f9b17ad2b417be66c3710", 1
+        ).unwrap();
+        let result = ctr_from(&data, &counter, &key).unwrap();
+        assert_eq!(
+            bytes_to_hex(&result),
+            "874d6191b620e3261bef6864990db6ce9806f66b7970fdff8617187bb9fffdff\
+             5ae4df3edbd5d35e5b4f09020db03eab1e031dda2fbe03d1792170a0f3009cee"
+        );
+        assert_eq!(ctr_from(&result, &counter, &key).unwrap(), data);
+    }
+
+    #[test]
+    fn test_cmac() {
+        let key = AesKey::from_hex("2b7e151628aed2a6abf7158809cf4f3c").unwrap();
+        let data = hex_to_bytes("6bc1bee22e409f96e93d7e117393172a", 1).unwrap();
+        assert_eq!(bytes_to_hex(&cmac(&data, &key)), "070a16b46b4d4144f79bdd9dd04a287c");
+    }
+
+    #[test]
+    fn test_key_sizes() {
+        let phrase = "00112233445566778899aabbccddeeff";
+        let vectors = [
+            ("000102030405060708090a0b0c0d0e0f", "69c4e0d86a7b0430d8cdb78070b4c55a"),
+            ("000102030405060708090a0b0c0d0e0f1011121314151617", "dda97ca4864cdfe06eaf70a0ec0d7191"),
+            ("000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f", "8ea2b7ca516745bfeafc49904b496089"),
+        ];
+        for ((key, valid), size) in vectors.iter().zip(KeySize::ALL) {
+            assert_eq!(AesKey::from_hex(key).unwrap().size(), size);
+            assert_eq!(encrypt(phrase, key).unwrap(), *valid);
+            assert_eq!(decrypt(valid, key).unwrap(), phrase);
+        }
+        let key: [u8; 32] = hex_to_bytes(vectors[2].0, 1).unwrap().try_into().unwrap();
+        let key = AesKey::from(key);
+        let block = encrypt_block(&hex_to_bytes(phrase, 1).unwrap().try_into().unwrap(), &key);
+        assert_eq!(bytes_to_hex(&block), vectors[2].1);
+        assert!(AesKey::from_hex("000102030405060708090a0b0c0d0e0f10").is_err());
+        assert!(encrypt(phrase, "000102030405060708090a0b0c0d0e0f10").is_err());
+        let key = AesKey::try_from(&Secret::<Vec<u8>>::from_hex(vectors[1].0).unwrap()).unwrap();
+        assert_eq!(format!("{key:?}"), "AesKey { size: Aes192, .. }");
+    }
+
+    #[test]
+    fn test_traced() {
+        let key = AesKey::from_hex("2b7e151628aed2a6abf7158809cf4f3c").unwrap();
+        let block: [u8; 16] = hex_to_bytes("3243f6a8885a308d313198a2e0370734", 1).unwrap().try_into().unwrap();
+        let (result, trace) = encrypt_block_traced(&block, &key);
+        assert_eq!(result, encrypt_block(&block, &key));
+        assert_eq!(bytes_to_hex(&result), "3925841d02dc09fbdc118597196a0b32");
+        assert_eq!(trace.round_keys.len(), 11);
+        assert_eq!(bytes_to_hex(&trace.round_keys[1]), "a0fafe1788542cb123a339392a6c7605");
+        assert_eq!(bytes_to_hex(&trace.initial), "193de3bea0f4e22b9ac68d2ae9f84808");
+        let round = &trace.rounds[0];
+        assert_eq!(bytes_to_hex(&round.sub_bytes), "d42711aee0bf98f1b8b45de51e415230");
+        assert_eq!(bytes_to_hex(&round.shift_rows), "d4bf5d30e0b452aeb84111f11e2798e5");
+        assert_eq!(bytes_to_hex(&round.mix_columns.unwrap()), "046681e5e0cb199a48f8d37a2806264c");
+        assert_eq!(trace.rounds.len(), 10);
+        assert_eq!(trace.rounds[9].mix_columns, None);
+        assert_eq!(trace.rounds[9].add_round_key, result);
+        let phrase = "00112233445566778899aabbccddeeff0011";
+        let key = "000102030405060708090a0b0c0d0e0f1011121314151617";
+        let (result, traces) = encrypt_traced(phrase, key).unwrap();
+        assert_eq!(result, encrypt(phrase, key).unwrap());
+        assert_eq!(traces.len(), 2);
+        assert_eq!(traces[0].rounds.len(), 12);
+        assert!(traces[0].to_string().starts_with("Раундовые ключи:\nK0: 000102030405060708090a0b0c0d0e0f\n"));
     }
 }
@@ -0,0 +1,80 @@
+//! Table lookups, multiplication in GF(2^8) and comparison that do not
+//! branch on or index memory with their arguments.
+//!
+//! Authentication tags are always checked with `compare`. With the
+//! `constant-time` feature Magma, AES and Kuznechik also use these
+//! functions for every substitution, which makes them noticeably slower.
+
+use num::traits::{PrimInt, WrappingSub};
+
+/// 1 when the values are equal and 0 otherwise
+fn equals(left: usize, right: usize) -> u8 {
+    let diff = (left ^ right) as u64;
+    (((diff | diff.wrapping_neg()) >> 63) ^ 1) as u8
+}
+
+/// `table[index]` computed by reading the whole table
+pub fn lookup<T: PrimInt + WrappingSub + From<u8>>(table: &[T], index: usize) -> T {
+    table.iter().enumerate().fold(T::zero(), |acc, (i, value)| {
+        let mask = T::zero().wrapping_sub(&<T as From<u8>>::from(equals(i, index)));
+        acc | (*value & mask)
+    })
+}
+
+/// Equality of the slices that does not stop at the first mismatch,
+/// only the lengths may be compared early
+pub fn compare(left: &[u8], right: &[u8]) -> bool {
+    if left.len() != right.len() {
+        return false;
+    }
+    let diff = left.iter().zip(right).fold(0, |acc, (left, right)| acc | (left ^ right));
+    equals(diff as usize, 0) == 1
+}
+
+/// Product modulo `x^8 + poly`, e.g. `0x1b` for AES and `0xc3` for Kuznechik
+pub fn gf_mul(mut left: u8, mut right: u8, poly: u8) -> u8 {
+    let mut result = 0;
+    for _ in 0..8 {
+        result ^= left & (right & 1).wrapping_neg();
+        let hi_bit = (left >> 7).wrapping_neg();
+        left = (left << 1) ^ (hi_bit & poly);
+        right >>= 1;
+    }
+    result
+}
+
+#[cfg(test)]
+mod ct_tests {
+    use super::*;
+
+    #[test]
+    fn test_lookup() {
+        let table: Vec<u8> = (0..=255).map(|i: u8| i.wrapping_mul(7) ^ 0x5a).collect();
+        for (i, value) in table.iter().enumerate() {
+            assert_eq!(lookup(&table, i), *value);
+        }
+        let table: Vec<u128> = (0..16).map(|i| u128::MAX / (i + 1)).collect();
+        assert_eq!(lookup(&table, 5), table[5]);
+        assert_eq!(lookup(&table, 16), 0);
+    }
+
+    #[test]
+    fn test_compare() {
+        assert!(compare(&[1, 2, 3], &[1, 2, 3]));
+        assert!(compare(&[], &[]));
+        assert!(!compare(&[1, 2, 3], &[1, 2, 4]));
+        assert!(!compare(&[0x80, 2, 3], &[0, 2, 3]));
+        assert!(!compare(&[1, 2], &[1, 2, 3]));
+    }
+
+    #[test]
+    fn test_gf_mul() {
+        assert_eq!(gf_mul(0x57, 0x83, 0x1b), 0xc1);
+        assert_eq!(gf_mul(0x57, 0x13, 0x1b), 0xfe);
+        assert_eq!(gf_mul(0x80, 0x02, 0xc3), 0xc3);
+        for value in 0..=255 {
+            assert_eq!(gf_mul(value, 1, 0x1b), value);
+            assert_eq!(gf_mul(value, 0, 0xc3), 0);
+        }
+    }
+}
@@ -0,0 +1,431 @@
+use std::fmt;
+use zeroize::Zeroize;
+use crate::errors::CipherError;
+use crate::cipher::Cipher;
+use crate::methods::{bytes_to_hex, hex_to_bytes};
+use crate::secret::Secret;
+use super::{key_from_hex, lookup, mac, modes, process_blocks, BlockCipher};
+use super::padding::Padding;
+
+/// Permutation tables number the bits from 1 starting with the most significant one
+const IP: [u8; 64] = [
+    58, 50, 42, 34, 26, 18, 10, 2, 60, 52, 44, 36, 28, 20, 12, 4,
+    62, 54, 46, 38, 30, 22, 14, 6, 64, 56, 48, 40, 32, 24, 16, 8,
+    57, 49, 41, 33, 25, 17,  9, 1, 59, 51, 43, 35, 27, 19, 11, 3,
+    61, 53, 45, 37, 29, 21, 13, 5, 63, 55, 47, 39, 31, 23, 15, 7,
+];
+
+const IP_REVERSE: [u8; 64] = inverse(&IP);
+
+const E: [u8; 48] = [
+    32,  1,  2,  3,  4,  5,  4,  5,  6,  7,  8,  9,
+     8,  9, 10, 11, 12, 13, 12, 13, 14, 15, 16, 17,
+    16, 17, 18, 19, 20, 21, 20, 21, 22, 23, 24, 25,
+    24, 25, 26, 27, 28, 29, 28, 29, 30, 31, 32,  1,
+];
+
+const P: [u8; 32] = [
+    16,  7, 20, 21, 29, 12, 28, 17,  1, 15, 23, 26,  5, 18, 31, 10,
+     2,  8, 24, 14, 32, 27,  3,  9, 19, 13, 30,  6, 22, 11,  4, 25,
+];
+
+const PC1: [u8; 56] = [
+    57, 49, 41, 33, 25, 17,  9,  1, 58, 50, 42, 34, 26, 18,
+    10,  2, 59, 51, 43, 35, 27, 19, 11,  3, 60, 52, 44, 36,
+    63, 55, 47, 39, 31, 23, 15,  7, 62, 54, 46, 38, 30, 22,
+    14,  6, 61, 53, 45, 37, 29, 21, 13,  5, 28, 20, 12,  4,
+];
+
+const PC2: [u8; 48] = [
+    14, 17, 11, 24,  1,  5,  3, 28, 15,  6, 21, 10,
+    23, 19, 12,  4, 26,  8, 16,  7, 27, 20, 13,  2,
+    41, 52, 31, 37, 47, 55, 30, 40, 51, 45, 33, 48,
+    44, 49, 39, 56, 34, 53, 46, 42, 50, 36, 29, 32,
+];
+
+const SHIFTS: [u32; 16] = [1, 1, 2, 2, 2, 2, 2, 2, 1, 2, 2, 2, 2, 2, 2, 1];
+
+/// Rows of every S-box one after another
+const S: [[u8; 64]; 8] = [
+    [
+        14,  4, 13,  1,  2, 15, 11,  8,  3, 10,  6, 12,  5,  9,  0,  7,
+         0, 15,  7,  4, 14,  2, 13,  1, 10,  6, 12, 11,  9,  5,  3,  8,
+         4,  1, 14,  8, 13,  6,  2, 11, 15, 12,  9,  7,  3, 10,  5,  0,
+        15, 12,  8,  2,  4,  9,  1,  7,  5, 11,  3, 14, 10,  0,  6, 13,
+    ],
+    [
+        15,  1,  8, 14,  6, 11,  3,  4,  9,  7,  2, 13, 12,  0,  5, 10,
+         3, 13,  4,  7, 15,  2,  8, 14, 12,  0,  1, 10,  6,  9, 11,  5,
+         0, 14,  7, 11, 10,  4, 13,  1,  5,  8, 12,  6,  9,  3,  2, 15,
+        13,  8, 10,  1,  3, 15,  4,  2, 11,  6,  7, 12,  0,  5, 14,  9,
+    ],
+    [
+        10,  0,  9, 14,  6,  3, 15,  5,  1, 13, 12,  7, 11,  4,  2,  8,
+        13,  7,  0,  9,  3,  4,  6, 10,  2,  8,  5, 14, 12, 11, 15,  1,
+        13,  6,  4,  9,  8, 15,  3,  0, 11,  1,  2, 12,  5, 10, 14,  7,
+         1, 10, 13,  0,  6,  9,  8,  7,  4, 15, 14,  3, 11,  5,  2, 12,
+    ],
+    [
+         7, 13, 14,  3,  0,  6,  9, 10,  1,  2,  8,  5, 11, 12,  4, 15,
+        13,  8, 11,  5,  6, 15,  0,  3,  4,  7,  2, 12,  1, 10, 14,  9,
+        10,  6,  9,  0, 12, 11,  7, 13, 15,  1,  3, 14,  5,  2,  8,  4,
+         3, 15,  0,  6, 10,  1, 13,  8,  9,  4,  5, 11, 12,  7,  2, 14,
+    ],
+    [
+         2, 12,  4,  1,  7, 10, 11,  6,  8,  5,  3, 15, 13,  0, 14,  9,
+        14, 11,  2, 12,  4,  7, 13,  1,  5,  0, 15, 10,  3,  9,  8,  6,
+         4,  2,  1, 11, 10, 13,  7,  8, 15,  9, 12,  5,  6,  3,  0, 14,
+        11,  8, 12,  7,  1, 14,  2, 13,  6, 15,  0,  9, 10,  4,  5,  3,
+    ],
+    [
+        12,  1, 10, 15,  9,  2,  6,  8,  0, 13,  3,  4, 14,  7,  5, 11,
+        10, 15,  4,  2,  7, 12,  9,  5,  6,  1, 13, 14,  0, 11,  3,  8,
+         9, 14, 15,  5,  2,  8, 12,  3,  7,  0,  4, 10,  1, 13, 11,  6,
+         4,  3,  2, 12,  9,  5, 15, 10, 11, 14,  1,  7,  6,  0,  8, 13,
+    ],
+    [
+         4, 11,  2, 14, 15,  0,  8, 13,  3, 12,  9,  7,  5, 10,  6,  1,
+        13,  0, 11,  7,  4,  9,  1, 10, 14,  3,  5, 12,  2, 15,  8,  6,
+         1,  4, 11, 13, 12,  3,  7, 14, 10, 15,  6,  8,  0,  5,  9,  2,
+         6, 11, 13,  8,  1,  4, 10,  7,  9,  5,  0, 15, 14,  2,  3, 12,
+    ],
+    [
+        13,  2,  8,  4,  6, 15, 11,  1, 10,  9,  3, 14,  5,  0, 12,  7,
+         1, 15, 13,  8, 10,  3,  7,  4, 12,  5,  6, 11,  0, 14,  9,  2,
+         7, 11,  4,  1,  9, 12, 14,  2,  0,  6, 10, 13, 15,  3,  5,  8,
+         2,  1, 14,  7,  4, 10,  8, 13, 15, 12,  9,  0,  3,  5,  6, 11,
+    ],
+];
+
+/// Every round key is the same, encryption and decryption coincide
+const WEAK_KEYS: [u64; 4] = [
+    0x0101010101010101, 0xfefefefefefefefe, 0xe0e0e0e0f1f1f1f1, 0x1f1f1f1f0e0e0e0e,
+];
+
+/// Pairs of keys where one decrypts what the other encrypts
+const SEMI_WEAK_KEYS: [(u64, u64); 6] = [
+    (0x011f011f010e010e, 0x1f011f010e010e01),
+    (0x01e001e001f101f1, 0xe001e001f101f101),
+    (0x01fe01fe01fe01fe, 0xfe01fe01fe01fe01),
+    (0x1fe01fe00ef10ef1, 0xe01fe01ff10ef10e),
+    (0x1ffe1ffe0efe0efe, 0xfe1ffe1ffe0efe0e),
+    (0xe0fee0fef1fef1fe, 0xfee0fee0fef1fef1),
+];
+
+/// The least significant bit of every byte is the parity bit, the cipher ignores it
+const PARITY: u64 = 0x0101010101010101;
+
+const fn inverse<const N: usize>(table: &[u8; N]) -> [u8; N] {
+    let mut result = [0; N];
+    let mut i = 0;
+    while i < N {
+        result[table[i] as usize - 1] = i as u8 + 1;
+        i += 1;
+    }
+    result
+}
+
+/// Takes the bits of the `width` bit `value` in the order of `table`
+fn permute(value: u64, width: u32, table: &[u8]) -> u64 {
+    table.iter().fold(0, |acc, bit| acc << 1 | (value >> (width - *bit as u32)) & 1)
+}
+
+fn f(right: u32, key: u64) -> u32 {
+    let value = permute(right as u64, 32, &E) ^ key;
+    let result = S.iter().enumerate().fold(0, |acc, (i, sbox)| {
+        let six = (value >> (42 - 6 * i)) & 0x3f;
+        let row = (six >> 4 & 0b10) | (six & 1);
+        let col = six >> 1 & 0xf;
+        acc << 4 | lookup(sbox, (row * 16 + col) as usize) as u64
+    });
+    permute(result, 32, &P) as u32
+}
+
+fn expand_key(key: &[u8; 8]) -> [u64; 16] {
+    let value = permute(u64::from_be_bytes(*key), 64, &PC1);
+    let (mut c, mut d) = ((value >> 28) as u32, (value & 0xfffffff) as u32);
+    let rotate = |half: u32, shift: u32| (half << shift | half >> (28 - shift)) & 0xfffffff;
+    SHIFTS.map(|shift| {
+        (c, d) = (rotate(c, shift), rotate(d, shift));
+        permute((c as u64) << 28 | d as u64, 56, &PC2)
+    })
+}
+
+/// Sixteen rounds between the initial permutation and its inverse
+fn feistel_net<'a, K>(val: &[u8], keys: K) -> [u8; 8]
+    where K: IntoIterator<Item = &'a u64>
+{
+    let value = permute(u64::from_be_bytes(val.try_into().unwrap()), 64, &IP);
+    let (left, right) = keys.into_iter()
+        .fold(((value >> 32) as u32, value as u32), |(left, right), key| (right, left ^ f(right, *key)));
+    permute((right as u64) << 32 | left as u64, 64, &IP_REVERSE).to_be_bytes()
+}
+
+fn without_parity(key: &[u8; 8]) -> u64 {
+    u64::from_be_bytes(*key) & !PARITY
+}
+
+/// One of the four keys with all round keys equal, the parity bits are ignored
+pub fn is_weak(key: &[u8; 8]) -> bool {
+    WEAK_KEYS.iter().any(|weak| weak & !PARITY == without_parity(key))
+}
+
+/// One of the twelve keys with only two different round keys
+pub fn is_semi_weak(key: &[u8; 8]) -> bool {
+    semi_weak_pair(key).is_some()
+}
+
+/// Pair of a semi-weak key, `None` for other keys
+pub fn semi_weak_pair(key: &[u8; 8]) -> Option<[u8; 8]> {
+    SEMI_WEAK_KEYS.iter().find_map(|(first, second)| match without_parity(key) {
+        key if key == first & !PARITY => Some(second.to_be_bytes()),
+        key if key == second & !PARITY => Some(first.to_be_bytes()),
+        _ => None
+    })
+}
+
+/// Rejects the weak and the semi-weak keys
+pub fn check_key(key: &[u8; 8]) -> Result<(), CipherError> {
+    if is_weak(key) || is_semi_weak(key) {
+        Err(CipherError::invalid_key("Ключ", "Ключ является слабым ключом DES"))?;
+    }
+    Ok(())
+}
+
+/// DES key with the round keys expanded once, weak keys are accepted
+#[derive(Clone)]
+pub struct DesKey {
+    keys: [u64; 16]
+}
+
+impl DesKey {
+    pub fn new(key: [u8; 8]) -> Self {
+        DesKey::expand(&key)
+    }
+
+    fn expand(key: &[u8; 8]) -> Self {
+        DesKey { keys: expand_key(key) }
+    }
+
+    pub fn from_hex(key: &str) -> Result<Self, CipherError> {
+        Self::try_from(Secret::<Vec<u8>>::from_hex(key)?.expose().as_slice())
+    }
+}
+
+impl fmt::Debug for DesKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DesKey").finish_non_exhaustive()
+    }
+}
+
+impl From<[u8; 8]> for DesKey {
+    fn from(value: [u8; 8]) -> Self {
+        DesKey::new(value)
+    }
+}
+
+/// Expands the key without copying it out of the `Secret`
+impl From<&Secret<[u8; 8]>> for DesKey {
+    fn from(value: &Secret<[u8; 8]>) -> Self {
+        DesKey::expand(value.expose())
+    }
+}
+
+impl TryFrom<&[u8]> for DesKey {
+    type Error = CipherError;
+
+    fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
+        Ok(DesKey::from(&Secret::try_from(value)?))
+    }
+}
+
+impl Drop for DesKey {
+    fn drop(&mut self) {
+        self.keys.zeroize();
+    }
+}
+
+impl BlockCipher for DesKey {
+    const BLOCK_SIZE: usize = 8;
+
+    fn encrypt_block(&self, block: &mut [u8]) {
+        block.copy_from_slice(&feistel_net(block, &self.keys));
+    }
+
+    fn decrypt_block(&self, block: &mut [u8]) {
+        block.copy_from_slice(&feistel_net(block, self.keys.iter().rev()));
+    }
+}
+
+pub fn encrypt_block(block: &[u8; 8], key: &DesKey) -> [u8; 8] {
+    feistel_net(block, &key.keys)
+}
+
+pub fn decrypt_block(block: &[u8; 8], key: &DesKey) -> [u8; 8] {
+    feistel_net(block, key.keys.iter().rev())
+}
+
+pub fn encrypt_bytes(data: &[u8], key: &DesKey) -> Result<Vec<u8>, CipherError> {
+    process_blocks(data, 8, |block| key.encrypt_block(block))
+}
+
+pub fn decrypt_bytes(data: &[u8], key: &DesKey) -> Result<Vec<u8>, CipherError> {
+    process_blocks(data, 8, |block| key.decrypt_block(block))
+}
+
+pub fn encrypt_padded(data: &[u8], padding: Padding, key: &DesKey) -> Result<Vec<u8>, CipherError> {
+    modes::ecb_encrypt(data, padding, key)
+}
+
+pub fn decrypt_padded(data: &[u8], padding: Padding, key: &DesKey) -> Result<Vec<u8>, CipherError> {
+    modes::ecb_decrypt(data, padding, key)
+}
+
+/// CMAC with the 64-bit block, `len` is the length of the tag in bytes
+pub fn mac(data: &[u8], len: usize, key: &DesKey) -> Result<Vec<u8>, CipherError> {
+    mac::mac(data, len, key)
+}
+
+fn key_from_str(key: &str) -> Result<DesKey, CipherError> {
+    let key = key_from_hex::<8>(key)?;
+    check_key(key.expose())?;
+    Ok(DesKey::from(&key))
+}
+
+/// The phrase is completed with zeros to the block border, weak keys are rejected
+pub fn encrypt(phrase: &str, key: &str) -> Result<String, CipherError> {
+    encrypt_with_padding(phrase, key, Padding::Zero)
+}
+
+pub fn decrypt(phrase: &str, key: &str) -> Result<String, CipherError> {
+    decrypt_with_padding(phrase, key, Padding::Zero)
+}
+
+pub fn encrypt_with_padding(phrase: &str, key: &str, padding: Padding) -> Result<String, CipherError> {
+    Ok(bytes_to_hex(&encrypt_padded(&hex_to_bytes(phrase, 1)?, padding, &key_from_str(key)?)?))
+}
+
+pub fn decrypt_with_padding(phrase: &str, key: &str, padding: Padding) -> Result<String, CipherError> {
+    Ok(bytes_to_hex(&decrypt_padded(&hex_to_bytes(phrase, 1)?, padding, &key_from_str(key)?)?))
+}
+
+pub struct Des;
+
+impl Cipher for Des {
+    type Key = DesKey;
+
+    fn encrypt(&self, phrase: &str, key: &DesKey) -> Result<String, CipherError> {
+        Ok(bytes_to_hex(&encrypt_padded(&hex_to_bytes(phrase, 1)?, Padding::Zero, key)?))
+    }
+
+    fn decrypt(&self, phrase: &str, key: &DesKey) -> Result<String, CipherError> {
+        Ok(bytes_to_hex(&decrypt_padded(&hex_to_bytes(phrase, 1)?, Padding::Zero, key)?))
+    }
+}
+
+#[cfg(test)]
+mod des_tests {
+    use super::*;
+
+    fn block(value: &str) -> [u8; 8] {
+        hex_to_bytes(value, 1).unwrap().try_into().unwrap()
+    }
+
+    #[test]
+    fn test_tables() {
+        for sbox in S {
+            for row in sbox.chunks(16) {
+                let mut row = row.to_vec();
+                row.sort();
+                assert_eq!(row, (0..16).collect::<Vec<u8>>());
+            }
+        }
+        assert_eq!(IP_REVERSE[..8], [40, 8, 48, 16, 56, 24, 64, 32]);
+    }
+
+    #[test]
+    fn test_block() {
+        let vectors = [
+            ("133457799bbcdff1", "0123456789abcdef", "85e813540f0ab405"),
+            ("0e329232ea6d0d73", "8787878787878787", "0000000000000000"),
+        ];
+        for (key, data, valid) in vectors {
+            let key = DesKey::from_hex(key).unwrap();
+            let result = encrypt_block(&block(data), &key);
+            assert_eq!(bytes_to_hex(&result), valid);
+            assert_eq!(decrypt_block(&result, &key), block(data));
+        }
+    }
+
+    /// Known answer tests of NIST SP 800-20: variable plaintext, variable key,
+    /// permutation operation and substitution table
+    const NIST_VECTORS: [(&str, &str, &str); 7] = [
+        ("0101010101010101", "8000000000000000", "95f8a5e5dd31d900"),
+        ("0101010101010101", "4000000000000000", "dd7f121ca5015619"),
+        ("0101010101010101", "2000000000000000", "2e8653104f3834ea"),
+        ("8001010101010101", "0000000000000000", "95a8d72813daa94d"),
+        ("4001010101010101", "0000000000000000", "0eec1487dd8c26d5"),
+        ("1046913489980131", "0000000000000000", "88d55e54f54c97b4"),
+        ("7ca110454a1a6e57", "01a1d6d039776742", "690f5b0d9a26939b"),
+    ];
+
+    #[test]
+    fn test_nist_encrypt() {
+        for (key, data, valid) in NIST_VECTORS {
+            let key = DesKey::from_hex(key).unwrap();
+            assert_eq!(bytes_to_hex(&encrypt_block(&block(data), &key)), valid);
+        }
+    }
+
+    #[test]
+    fn test_nist_decrypt() {
+        for (key, valid, data) in NIST_VECTORS {
+            let key = DesKey::from_hex(key).unwrap();
+            assert_eq!(bytes_to_hex(&decrypt_block(&block(data), &key)), valid);
+        }
+    }
+
+    #[test]
+    fn test_weak_keys() {
+        let data = block("0123456789abcdef");
+        for weak in WEAK_KEYS {
+            let key = weak.to_be_bytes();
+            assert!(is_weak(&key));
+            let key = DesKey::new(key);
+            assert_eq!(encrypt_block(&encrypt_block(&data, &key), &key), data);
+        }
+        for (first, second) in SEMI_WEAK_KEYS {
+            let (first, second) = (first.to_be_bytes(), second.to_be_bytes());
+            assert!(is_semi_weak(&first) && !is_weak(&first));
+            assert_eq!(semi_weak_pair(&first), Some(second));
+            assert_eq!(semi_weak_pair(&second), Some(first));
+            let result = encrypt_block(&data, &DesKey::new(first));
+            assert_eq!(encrypt_block(&result, &DesKey::new(second)), data);
+        }
+        assert!(is_weak(&block("0000000000000000")));
+        assert!(!is_weak(&block("133457799bbcdff1")) && !is_semi_weak(&block("133457799bbcdff1")));
+        assert_eq!(semi_weak_pair(&block("133457799bbcdff1")), None);
+        assert!(matches!(
+            encrypt("0123456789abcdef", "fefefefefefefefe"),
+            Err(CipherError::InvalidKey { param: "Ключ", .. })
+        ));
+    }
+
+    #[test]
+    fn test_encrypt() {
+        let key = "133457799bbcdff1";
+        let phrase = "0123456789abcdef0123";
+        let result = encrypt(phrase, key).unwrap();
+        assert_eq!(&result[..16], "85e813540f0ab405");
+        assert_eq!(decrypt(&result, key).unwrap(), "0123456789abcdef0123000000000000");
+        let key = DesKey::from_hex(key).unwrap();
+        assert_eq!(Des.encrypt("0123456789abcdef", &key).unwrap(), "85e813540f0ab405");
+        assert_eq!(Des.decrypt("85e813540f0ab405", &key).unwrap(), "0123456789abcdef");
+        assert_eq!(Des.encrypt("0123", &key).unwrap(), encrypt("0123", "133457799bbcdff1").unwrap());
+        let result = encrypt_with_padding("0123456789abcdef", "133457799bbcdff1", Padding::Pkcs7).unwrap();
+        assert_eq!(result.len(), 32);
+        assert_eq!(decrypt_with_padding(&result, "133457799bbcdff1", Padding::Pkcs7).unwrap(), "0123456789abcdef");
+        assert!(DesKey::from_hex("1334577999").is_err());
+        assert_eq!(mac(b"data", 4, &key).unwrap().len(), 4);
+    }
+}
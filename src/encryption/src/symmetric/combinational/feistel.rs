@@ -0,0 +1,394 @@
+//! Feistel network with a chosen block size, number of rounds, round
+//! function and key schedule for experiments with the construction.
+//!
+//! Decryption is the same network run with the round keys in reverse order.
+
+use std::fmt;
+use crate::errors::CipherError;
+use crate::methods::{bytes_to_hex, hex_to_bytes};
+use super::magma::{self, GTable, SBox};
+use super::padding::Padding;
+use super::process_blocks;
+
+/// Block sizes offered on the page, `Network` accepts any even size up to 16 bytes
+pub const BLOCK_SIZES: [usize; 4] = [2, 4, 8, 16];
+
+/// Names of the round functions in the order of `network`
+pub const FUNCTIONS: [&str; 3] = ["S-блок и перестановка", "Функция g Магмы", "XOR и циклический сдвиг"];
+
+/// Names of the key schedules in the order of `network`
+pub const SCHEDULES: [&str; 3] = ["Циклический", "Как в Магме", "Сдвиг ключа"];
+
+/// Function of the right half and the round key
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RoundFunction {
+    /// `P(S(R ^ K))`: the S-box replaces every 4 bits, then `permutation[i]` gives
+    /// the input bit of the output bit `i`, bits are numbered from 1 starting with the most significant one
+    Substitution { sbox: [u8; 16], permutation: Vec<u8> },
+    /// `g` of Magma with the given substitution, the halves must be 32 bits long
+    Magma(SBox),
+    /// `(R ^ K) <<< shift`
+    XorRotate { shift: u32 },
+}
+
+impl RoundFunction {
+    /// The first row of the Magma S-box and the permutation sending
+    /// the bits of every 4-bit group to different groups
+    pub fn substitution(half_bits: u32) -> Self {
+        let groups = (half_bits / 4).max(1);
+        let permutation = (0..half_bits).map(|i| ((i % groups) * 4 + i / groups + 1) as u8).collect();
+        RoundFunction::Substitution { sbox: SBox::default().table()[0], permutation }
+    }
+}
+
+/// Derivation of the round keys from the key
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum KeySchedule {
+    /// The key is split into words of the half size used one after another
+    #[default]
+    Cyclic,
+    /// The same words, the last pass takes them in reverse order like `K8..K1` of Magma
+    Magma,
+    /// Round `i` takes the leading bits of the key rotated left by `i * shift` bits
+    Rotate { shift: u32 },
+}
+
+/// Halves of the block after a round
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Round {
+    pub key: u64,
+    pub left: u64,
+    pub right: u64,
+}
+
+/// Log of one block, the halves are not swapped after the last round
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Trace {
+    /// Keys in the order of the rounds, reversed for decryption
+    pub round_keys: Vec<u64>,
+    pub rounds: Vec<Round>,
+    /// Number of hex digits in a half
+    width: usize,
+}
+
+impl fmt::Display for Trace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let width = self.width;
+        for (i, round) in self.rounds.iter().enumerate() {
+            writeln!(
+                f, "Раунд {}: K = {:0width$x}, L = {:0width$x}, R = {:0width$x}",
+                i + 1, round.key, round.left, round.right
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone)]
+pub struct Network {
+    block_size: usize,
+    rounds: usize,
+    function: RoundFunction,
+    schedule: KeySchedule,
+    table: Option<Box<GTable>>
+}
+
+impl Network {
+    pub fn new(
+        block_size: usize, rounds: usize, function: RoundFunction, schedule: KeySchedule
+    ) -> Result<Self, CipherError> {
+        if !(2..=16).contains(&block_size) || !block_size.is_multiple_of(2) {
+            Err(CipherError::invalid_size("Размер блока", "Размер блока должен быть четным числом от 2 до 16 байт"))?;
+        }
+        if rounds == 0 {
+            Err(CipherError::invalid_size("Число раундов", "Сеть должна содержать хотя бы один раунд"))?;
+        }
+        let bits = block_size as u32 * 4;
+        match &function {
+            RoundFunction::Substitution { sbox, permutation } => {
+                if sbox.iter().any(|value| *value > 15) {
+                    Err(CipherError::invalid_key("S-блок", "Значения S-блока должны быть меньше 16"))?;
+                }
+                let mut sorted = permutation.clone();
+                sorted.sort();
+                if !sorted.iter().copied().eq(1..=bits as u8) {
+                    Err(CipherError::invalid_key(
+                        "Перестановка", &format!("Перестановка должна содержать номера битов от 1 до {bits}")
+                    ))?;
+                }
+            },
+            RoundFunction::Magma(_) if bits != 32 => {
+                Err(CipherError::invalid_size("Размер блока", "Функция g Магмы работает с блоком из 8 байт"))?;
+            },
+            RoundFunction::XorRotate { shift } if *shift >= bits => {
+                Err(CipherError::invalid_key("Сдвиг", &format!("Сдвиг должен быть меньше {bits}")))?;
+            },
+            _ => {}
+        }
+        let table = match &function {
+            RoundFunction::Magma(sbox) => Some(magma::g_table(sbox)),
+            _ => None
+        };
+        Ok(Network { block_size, rounds, function, schedule, table })
+    }
+
+    pub fn block_size(&self) -> usize {
+        self.block_size
+    }
+
+    pub fn rounds(&self) -> usize {
+        self.rounds
+    }
+
+    fn half_bits(&self) -> u32 {
+        self.block_size as u32 * 4
+    }
+
+    fn mask(&self) -> u64 {
+        u64::MAX >> (64 - self.half_bits())
+    }
+
+    /// Round keys in the order of encryption
+    pub fn round_keys(&self, key: &[u8]) -> Result<Vec<u64>, CipherError> {
+        if key.is_empty() { Err(CipherError::EmptyValue { param: "Ключ" })?; }
+        let half = self.block_size / 2;
+        let bits = self.half_bits() as usize;
+        match self.schedule {
+            KeySchedule::Cyclic | KeySchedule::Magma => {
+                if !key.len().is_multiple_of(half) {
+                    Err(CipherError::invalid_size("Ключ", &format!("Длина ключа должна быть кратна {half} байтам")))?;
+                }
+                let words: Vec<u64> = key.chunks(half).map(word).collect();
+                let n = words.len();
+                Ok((0..self.rounds).map(|i| match self.schedule {
+                    KeySchedule::Magma if i + n >= self.rounds => words[self.rounds - 1 - i],
+                    _ => words[i % n]
+                }).collect())
+            },
+            KeySchedule::Rotate { shift } => {
+                let len = key.len() * 8;
+                if len < bits {
+                    Err(CipherError::invalid_size("Ключ", &format!("Ключ должен быть не короче {half} байт")))?;
+                }
+                Ok((0..self.rounds).map(|i| (0..bits).fold(0, |acc, t| {
+                    let j = (i * shift as usize + t) % len;
+                    acc << 1 | (key[j / 8] >> (7 - j % 8) & 1) as u64
+                })).collect())
+            },
+        }
+    }
+
+    fn f(&self, right: u64, key: u64) -> u64 {
+        let bits = self.half_bits();
+        match &self.function {
+            RoundFunction::Substitution { sbox, permutation } => {
+                let value = right ^ key;
+                let substituted = (0..bits / 4)
+                    .fold(0, |acc, i| acc << 4 | sbox[(value >> (bits - 4 - 4 * i) & 0xf) as usize] as u64);
+                permutation.iter().fold(0, |acc, bit| acc << 1 | substituted >> (bits - *bit as u32) & 1)
+            },
+            RoundFunction::Magma(_) => magma::g(key as u32, right as u32, self.table.as_ref().unwrap()) as u64,
+            RoundFunction::XorRotate { shift } => {
+                let value = right ^ key;
+                (value << shift | value.checked_shr(bits - shift).unwrap_or(0)) & self.mask()
+            },
+        }
+    }
+
+    fn transform<'a, K>(&self, block: &[u8], keys: K) -> (Vec<u8>, Vec<Round>)
+        where K: IntoIterator<Item = &'a u64>
+    {
+        let half = self.block_size / 2;
+        let (mut left, mut right) = (word(&block[..half]), word(&block[half..]));
+        let rounds = keys.into_iter().map(|key| {
+            (left, right) = (right, left ^ self.f(right, *key));
+            Round { key: *key, left, right }
+        }).collect();
+        let result = [right, left].iter().flat_map(|half_value| half_value.to_be_bytes()[8 - half..].to_vec()).collect();
+        (result, rounds)
+    }
+
+    fn check_block(&self, block: &[u8]) -> Result<(), CipherError> {
+        if block.len() != self.block_size {
+            Err(CipherError::invalid_size("Блок", &format!("Блок должен состоять из {} байт", self.block_size)))?;
+        }
+        Ok(())
+    }
+
+    pub fn encrypt_block_traced(&self, block: &[u8], key: &[u8]) -> Result<(Vec<u8>, Trace), CipherError> {
+        self.check_block(block)?;
+        let round_keys = self.round_keys(key)?;
+        let (result, rounds) = self.transform(block, &round_keys);
+        Ok((result, Trace { round_keys, rounds, width: self.block_size }))
+    }
+
+    /// The same network with the round keys reversed
+    pub fn decrypt_block_traced(&self, block: &[u8], key: &[u8]) -> Result<(Vec<u8>, Trace), CipherError> {
+        self.check_block(block)?;
+        let mut round_keys = self.round_keys(key)?;
+        round_keys.reverse();
+        let (result, rounds) = self.transform(block, &round_keys);
+        Ok((result, Trace { round_keys, rounds, width: self.block_size }))
+    }
+
+    pub fn encrypt_bytes(&self, data: &[u8], key: &[u8]) -> Result<Vec<u8>, CipherError> {
+        let keys = self.round_keys(key)?;
+        process_blocks(data, self.block_size, |block| block.copy_from_slice(&self.transform(block, &keys).0))
+    }
+
+    pub fn decrypt_bytes(&self, data: &[u8], key: &[u8]) -> Result<Vec<u8>, CipherError> {
+        let keys = self.round_keys(key)?;
+        process_blocks(data, self.block_size, |block| block.copy_from_slice(&self.transform(block, keys.iter().rev()).0))
+    }
+}
+
+impl fmt::Debug for Network {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Network")
+            .field("block_size", &self.block_size)
+            .field("rounds", &self.rounds)
+            .field("function", &self.function)
+            .field("schedule", &self.schedule)
+            .finish()
+    }
+}
+
+fn word(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0, |acc, byte| acc << 8 | *byte as u64)
+}
+
+/// Builds the network from the options of the page: the indices of
+/// `FUNCTIONS` and `SCHEDULES` and the shift used by the rotating variants
+pub fn network(
+    block_size: usize, rounds: usize, function: usize, schedule: usize, shift: u32
+) -> Result<Network, CipherError> {
+    let function = match function {
+        0 => RoundFunction::substitution(block_size as u32 * 4),
+        1 => RoundFunction::Magma(SBox::default()),
+        2 => RoundFunction::XorRotate { shift },
+        _ => Err(CipherError::invalid_key("Раундовая функция", "Неизвестная раундовая функция"))?
+    };
+    let schedule = match schedule {
+        0 => KeySchedule::Cyclic,
+        1 => KeySchedule::Magma,
+        2 => KeySchedule::Rotate { shift },
+        _ => Err(CipherError::invalid_key("Ключевое расписание", "Неизвестное ключевое расписание"))?
+    };
+    Network::new(block_size, rounds, function, schedule)
+}
+
+/// The phrase is completed with zeros to the block border
+pub fn encrypt(phrase: &str, key: &str, network: &Network) -> Result<String, CipherError> {
+    let data = Padding::Zero.pad(&hex_to_bytes(phrase, 1)?, network.block_size())?;
+    Ok(bytes_to_hex(&network.encrypt_bytes(&data, &hex_to_bytes(key, 1)?)?))
+}
+
+pub fn decrypt(phrase: &str, key: &str, network: &Network) -> Result<String, CipherError> {
+    Ok(bytes_to_hex(&network.decrypt_bytes(&hex_to_bytes(phrase, 1)?, &hex_to_bytes(key, 1)?)?))
+}
+
+/// Same as `encrypt` with the log of every block
+pub fn encrypt_traced(phrase: &str, key: &str, network: &Network) -> Result<(String, Vec<Trace>), CipherError> {
+    let data = Padding::Zero.pad(&hex_to_bytes(phrase, 1)?, network.block_size())?;
+    traced(&data, &hex_to_bytes(key, 1)?, network.block_size(), |block, key| network.encrypt_block_traced(block, key))
+}
+
+pub fn decrypt_traced(phrase: &str, key: &str, network: &Network) -> Result<(String, Vec<Trace>), CipherError> {
+    let data = hex_to_bytes(phrase, 1)?;
+    if !data.len().is_multiple_of(network.block_size()) {
+        Err(CipherError::invalid_size(
+            "Данные", &format!("Длина данных должна быть кратна {} байтам", network.block_size())
+        ))?;
+    }
+    traced(&data, &hex_to_bytes(key, 1)?, network.block_size(), |block, key| network.decrypt_block_traced(block, key))
+}
+
+fn traced<F>(data: &[u8], key: &[u8], size: usize, func: F) -> Result<(String, Vec<Trace>), CipherError>
+    where F: Fn(&[u8], &[u8]) -> Result<(Vec<u8>, Trace), CipherError>
+{
+    let mut result = Vec::with_capacity(data.len());
+    let mut traces = Vec::new();
+    for block in data.chunks(size) {
+        let (block, trace) = func(block, key)?;
+        result.extend(block);
+        traces.push(trace);
+    }
+    Ok((bytes_to_hex(&result), traces))
+}
+
+#[cfg(test)]
+mod feistel_tests {
+    use super::*;
+
+    const KEY: &str = "ffeeddccbbaa99887766554433221100f0f1f2f3f4f5f6f7f8f9fafbfcfdfeff";
+
+    #[test]
+    fn test_magma() {
+        let network = Network::new(8, 32, RoundFunction::Magma(SBox::default()), KeySchedule::Magma).unwrap();
+        assert_eq!(encrypt("fedcba9876543210", KEY, &network).unwrap(), "4ee901e5c2d8ca3d");
+        assert_eq!(decrypt("4ee901e5c2d8ca3d", KEY, &network).unwrap(), "fedcba9876543210");
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let phrase = "0123456789abcdeffedcba9876543210";
+        for block_size in BLOCK_SIZES {
+            for function in 0..FUNCTIONS.len() {
+                for schedule in 0..SCHEDULES.len() {
+                    let Ok(network) = network(block_size, 5, function, schedule, 3) else {
+                        assert!(function == 1 && block_size != 8);
+                        continue;
+                    };
+                    let result = encrypt(phrase, &KEY[..32], &network).unwrap();
+                    assert_ne!(result, phrase);
+                    assert_eq!(decrypt(&result, &KEY[..32], &network).unwrap(), phrase);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_trace() {
+        let network = network(4, 6, 0, 0, 0).unwrap();
+        let (result, traces) = encrypt_traced("01234567", "a1b2c3d4e5f6", &network).unwrap();
+        let trace = &traces[0];
+        assert_eq!(trace.round_keys, [0xa1b2, 0xc3d4, 0xe5f6, 0xa1b2, 0xc3d4, 0xe5f6]);
+        assert_eq!(trace.rounds[0].left, 0x4567);
+        for pair in trace.rounds.windows(2) {
+            assert_eq!(pair[1].left, pair[0].right);
+        }
+        let last = trace.rounds.last().unwrap();
+        assert_eq!(result, format!("{:04x}{:04x}", last.right, last.left));
+        let (phrase, traces) = decrypt_traced(&result, "a1b2c3d4e5f6", &network).unwrap();
+        assert_eq!(phrase, "01234567");
+        let mut keys = trace.round_keys.clone();
+        keys.reverse();
+        assert_eq!(traces[0].round_keys, keys);
+        assert!(trace.to_string().starts_with("Раунд 1: K = a1b2, L = 4567"));
+    }
+
+    #[test]
+    fn test_schedule() {
+        let network = Network::new(4, 3, RoundFunction::XorRotate { shift: 1 }, KeySchedule::Rotate { shift: 4 }).unwrap();
+        assert_eq!(network.round_keys(&[0x12, 0x34]).unwrap(), [0x1234, 0x2341, 0x3412]);
+        let network = Network::new(4, 10, RoundFunction::XorRotate { shift: 1 }, KeySchedule::Magma).unwrap();
+        assert_eq!(
+            network.round_keys(&[0, 1, 0, 2, 0, 3]).unwrap(),
+            [1, 2, 3, 1, 2, 3, 1, 3, 2, 1]
+        );
+    }
+
+    #[test]
+    fn test_validation() {
+        assert!(matches!(network(3, 8, 0, 0, 0), Err(CipherError::InvalidSize { .. })));
+        assert!(matches!(network(8, 0, 0, 0, 0), Err(CipherError::InvalidSize { .. })));
+        assert!(matches!(network(4, 8, 1, 0, 0), Err(CipherError::InvalidSize { .. })));
+        assert!(matches!(network(4, 8, 2, 0, 16), Err(CipherError::InvalidKey { .. })));
+        assert!(matches!(network(4, 8, 3, 0, 0), Err(CipherError::InvalidKey { .. })));
+        let function = RoundFunction::Substitution { sbox: [0; 16], permutation: vec![1; 16] };
+        assert!(matches!(Network::new(4, 8, function, KeySchedule::Cyclic), Err(CipherError::InvalidKey { .. })));
+        let network = network(4, 8, 0, 0, 0).unwrap();
+        assert!(matches!(encrypt("00", "abcdef", &network), Err(CipherError::InvalidSize { .. })));
+        assert!(matches!(decrypt("00", "abcd", &network), Err(CipherError::InvalidSize { .. })));
+    }
+}
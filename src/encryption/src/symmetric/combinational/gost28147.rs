@@ -0,0 +1,299 @@
+//! Modes of GOST 28147-89 over the Magma transformation: simple replacement,
+//! gamming, gamming with feedback and the imitovstavka.
+//!
+//! Every mode may change its key with the CryptoPro key meshing of RFC 4357
+//! after each kilobyte of processed data.
+//!
+//! Keys and blocks use the byte order of GOST 28147-89 implementations, where
+//! every 32-bit word is little-endian, so the results match the legacy software.
+
+use crate::errors::CipherError;
+use crate::magma::{self, MagmaKey, SBox};
+use super::{process_blocks, xor};
+
+/// Key meshing interval of RFC 4357 in bytes
+pub const MESHING_INTERVAL: usize = 1024;
+
+const MESHING_CONSTANT: [u8; 32] = [
+    0x69, 0x00, 0x72, 0x22, 0x64, 0xc9, 0x04, 0x23, 0x8d, 0x3a, 0xdb, 0x96, 0x46, 0xe9, 0x2a, 0xc4,
+    0x18, 0xfe, 0xac, 0x94, 0x00, 0xed, 0x07, 0x12, 0xc0, 0x86, 0xdc, 0xc2, 0xef, 0x4c, 0xa9, 0x2b
+];
+
+const C1: u32 = 0x01010104;
+const C2: u32 = 0x01010101;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum KeyMeshing {
+    #[default]
+    None,
+    /// CryptoPro key meshing of RFC 4357
+    CryptoPro
+}
+
+/// Current key of a mode, changed every `MESHING_INTERVAL` bytes when meshing is on
+struct MeshedKey {
+    key: MagmaKey,
+    meshing: KeyMeshing,
+    processed: usize
+}
+
+impl MeshedKey {
+    fn new(key: &MagmaKey, meshing: KeyMeshing) -> Self {
+        MeshedKey { key: key.clone(), meshing, processed: 0 }
+    }
+
+    /// Key for the next block, `state` is encrypted with the new key after the meshing
+    fn next(&mut self, state: Option<&mut [u8; 8]>) -> &MagmaKey {
+        if self.meshing == KeyMeshing::CryptoPro
+            && self.processed != 0 && self.processed.is_multiple_of(MESHING_INTERVAL)
+        {
+            self.key = mesh(&self.key);
+            if let Some(state) = state {
+                encrypt_block(state, &self.key);
+            }
+        }
+        self.processed += 8;
+        &self.key
+    }
+}
+
+/// Key in the byte order of GOST 28147-89, `K1` is made of the first four bytes
+/// taken from the least significant one
+pub fn key(key: [u8; 32], sbox: SBox) -> MagmaKey {
+    let mut key = key;
+    key.chunks_mut(4).for_each(<[u8]>::reverse);
+    MagmaKey::with_sbox(key, sbox)
+}
+
+/// `N1` is the first half of the block and `N2` the second one, both little-endian,
+/// while Magma reads the block as one big-endian number
+fn transform<F>(block: &mut [u8], func: F, key: &MagmaKey)
+    where F: Fn(&[u8; 8], &MagmaKey) -> [u8; 8]
+{
+    let block: &mut [u8; 8] = block.try_into().unwrap();
+    block.reverse();
+    *block = func(block, key);
+    block.reverse();
+}
+
+fn encrypt_block(block: &mut [u8], key: &MagmaKey) {
+    transform(block, magma::encrypt_block, key)
+}
+
+fn decrypt_block(block: &mut [u8], key: &MagmaKey) {
+    transform(block, magma::decrypt_block, key)
+}
+
+/// New key `K' = D(K, C)`, the substitution stays the same
+pub fn mesh(key: &MagmaKey) -> MagmaKey {
+    let mut next = MESHING_CONSTANT;
+    for block in next.chunks_mut(8) {
+        decrypt_block(block, key);
+    }
+    self::key(next, *key.sbox())
+}
+
+/// Simple replacement, the length of `data` must be a multiple of 8 bytes
+pub fn ecb_encrypt(data: &[u8], meshing: KeyMeshing, key: &MagmaKey) -> Result<Vec<u8>, CipherError> {
+    let mut key = MeshedKey::new(key, meshing);
+    process_blocks(data, 8, |block| encrypt_block(block, key.next(None)))
+}
+
+pub fn ecb_decrypt(data: &[u8], meshing: KeyMeshing, key: &MagmaKey) -> Result<Vec<u8>, CipherError> {
+    let mut key = MeshedKey::new(key, meshing);
+    process_blocks(data, 8, |block| decrypt_block(block, key.next(None)))
+}
+
+/// Gamming, `N3` and `N4` of the encrypted synchro message are increased
+/// by `C2` modulo 2^32 and `C1` modulo 2^32 - 1 before every block
+pub fn gamma(data: &[u8], synchro: &[u8; 8], meshing: KeyMeshing, key: &MagmaKey) -> Vec<u8> {
+    let mut key = MeshedKey::new(key, meshing);
+    let mut state = *synchro;
+    encrypt_block(&mut state, &key.key);
+    let mut result = data.to_vec();
+    for block in result.chunks_mut(8) {
+        let key = key.next(Some(&mut state));
+        let n3 = u32::from_le_bytes(state[..4].try_into().unwrap()).wrapping_add(C2);
+        let (n4, carry) = u32::from_le_bytes(state[4..].try_into().unwrap()).overflowing_add(C1);
+        state[..4].copy_from_slice(&n3.to_le_bytes());
+        state[4..].copy_from_slice(&(n4 + carry as u32).to_le_bytes());
+        let mut gamma = state;
+        encrypt_block(&mut gamma, key);
+        xor(block, &gamma);
+    }
+    result
+}
+
+/// Gamming with feedback, the last block may be incomplete
+pub fn gamma_feedback_encrypt(data: &[u8], synchro: &[u8; 8], meshing: KeyMeshing, key: &MagmaKey) -> Vec<u8> {
+    let mut key = MeshedKey::new(key, meshing);
+    let mut state = *synchro;
+    let mut result = data.to_vec();
+    for block in result.chunks_mut(8) {
+        let key = key.next(Some(&mut state));
+        let mut gamma = state;
+        encrypt_block(&mut gamma, key);
+        xor(block, &gamma);
+        state[..block.len()].copy_from_slice(block);
+    }
+    result
+}
+
+pub fn gamma_feedback_decrypt(data: &[u8], synchro: &[u8; 8], meshing: KeyMeshing, key: &MagmaKey) -> Vec<u8> {
+    let mut key = MeshedKey::new(key, meshing);
+    let mut state = *synchro;
+    let mut result = data.to_vec();
+    for block in result.chunks_mut(8) {
+        let key = key.next(Some(&mut state));
+        let mut gamma = state;
+        encrypt_block(&mut gamma, key);
+        state[..block.len()].copy_from_slice(block);
+        xor(block, &gamma);
+    }
+    result
+}
+
+/// Imitovstavka of `len` bytes taken from the lowest bytes of `N1`,
+/// the data is completed with zeros to at least two blocks
+pub fn imitovstavka(data: &[u8], len: usize, meshing: KeyMeshing, key: &MagmaKey) -> Result<Vec<u8>, CipherError> {
+    if len == 0 || len > 4 {
+        Err(CipherError::invalid_size(
+            "Имитовставка", "Длина имитовставки должна быть от 1 до 4 байт"
+        ))?;
+    }
+    let mut data = data.to_vec();
+    data.resize(data.len().max(16).div_ceil(8) * 8, 0);
+    let mut key = MeshedKey::new(key, meshing);
+    let mut state = [0; 8];
+    for block in data.chunks(8) {
+        xor(&mut state, block);
+        transform(&mut state, |block, key| key.encrypt_16(block), key.next(None));
+    }
+    Ok(state[..len].to_vec())
+}
+
+#[cfg(test)]
+mod gost28147_tests {
+    use super::*;
+
+    // Expected values are the outputs of libgcrypt and GnuTLS, the latter
+    // always meshes the key in its CNT, CFB and IMIT implementations
+    const KEY: &str = "ffeeddccbbaa99887766554433221100f0f1f2f3f4f5f6f7f8f9fafbfcfdfeff";
+    const SYNCHRO: [u8; 8] = [0x12, 0x34, 0x56, 0x78, 0x90, 0xab, 0xcd, 0xef];
+
+    fn key(sbox: SBox) -> MagmaKey {
+        super::key(hex::decode(KEY).unwrap().try_into().unwrap(), sbox)
+    }
+
+    fn data(len: usize) -> Vec<u8> {
+        (0..len).map(|i| (i * 7 + 3) as u8).collect()
+    }
+
+    /// The first two blocks, the blocks around the first meshing and the last block
+    fn parts(data: &[u8]) -> (String, String, String) {
+        (hex::encode(&data[..16]), hex::encode(&data[1016..1040]), hex::encode(&data[2048..]))
+    }
+
+    #[test]
+    fn test_ecb() {
+        // GOST R 34.12-2015 A.2 with the words of the key and the block in the byte order of GOST 28147-89
+        let standard = super::key(
+            hex::decode("ccddeeff8899aabb4455667700112233f3f2f1f0f7f6f5f4fbfaf9f8fffefdfc").unwrap().try_into().unwrap(),
+            SBox::Tc26Z
+        );
+        let result = ecb_encrypt(&hex::decode("1032547698badcfe").unwrap(), KeyMeshing::None, &standard).unwrap();
+        assert_eq!(hex::encode(&result), "3dcad8c2e501e94e");
+        let data = data(16);
+        let result = ecb_encrypt(&data, KeyMeshing::None, &key(SBox::GostR3411Test)).unwrap();
+        assert_eq!(hex::encode(&result), "03ead237dee27d089a99a9b9e0db69aa");
+        assert_eq!(ecb_decrypt(&result, KeyMeshing::None, &key(SBox::GostR3411Test)).unwrap(), data);
+        assert!(ecb_encrypt(&data[..7], KeyMeshing::None, &key(SBox::GostR3411Test)).is_err());
+        let result = ecb_encrypt(&data, KeyMeshing::None, &key(SBox::Test)).unwrap();
+        assert_eq!(hex::encode(&result), "3a4fa5ceea3a108c59677cd578045869");
+        assert_eq!(ecb_decrypt(&result, KeyMeshing::None, &key(SBox::Test)).unwrap(), data);
+    }
+
+    #[test]
+    fn test_ecb_meshing() {
+        let data = data(3 * MESHING_INTERVAL);
+        let key = key(SBox::CryptoProA);
+        let plain = ecb_encrypt(&data, KeyMeshing::None, &key).unwrap();
+        let meshed = ecb_encrypt(&data, KeyMeshing::CryptoPro, &key).unwrap();
+        assert_eq!(meshed[..MESHING_INTERVAL], plain[..MESHING_INTERVAL]);
+        let second = ecb_encrypt(&data[MESHING_INTERVAL..2 * MESHING_INTERVAL], KeyMeshing::None, &mesh(&key)).unwrap();
+        assert_eq!(meshed[MESHING_INTERVAL..2 * MESHING_INTERVAL], second);
+        assert_eq!(ecb_decrypt(&meshed, KeyMeshing::CryptoPro, &key).unwrap(), data);
+    }
+
+    #[test]
+    fn test_gamma() {
+        let data = data(2 * MESHING_INTERVAL + 8);
+        let result = gamma(&data, &SYNCHRO, KeyMeshing::None, &key(SBox::Tc26Z));
+        assert_eq!(parts(&result), (
+            "c3227467103e20c9d139954e20e8011d".into(),
+            "84e2ee9990d6320bd35386a81740bfe96cba15e0bff10013".into(),
+            "1bee79ab32a69690".into()
+        ));
+        assert_eq!(gamma(&result, &SYNCHRO, KeyMeshing::None, &key(SBox::Tc26Z)), data);
+        assert_eq!(gamma(&data[..13], &SYNCHRO, KeyMeshing::None, &key(SBox::Tc26Z)), result[..13]);
+    }
+
+    #[test]
+    fn test_gamma_meshing() {
+        let data = data(2 * MESHING_INTERVAL + 8);
+        let result = gamma(&data, &SYNCHRO, KeyMeshing::CryptoPro, &key(SBox::Tc26Z));
+        assert_eq!(parts(&result), (
+            "c3227467103e20c9d139954e20e8011d".into(),
+            "84e2ee9990d6320bfbcad837458ad4515221bc1709935671".into(),
+            "9dcb9e90ed024711".into()
+        ));
+        assert_eq!(gamma(&result, &SYNCHRO, KeyMeshing::CryptoPro, &key(SBox::Tc26Z)), data);
+    }
+
+    #[test]
+    fn test_gamma_feedback() {
+        let data = data(2 * MESHING_INTERVAL + 8);
+        let result = gamma_feedback_encrypt(&data, &SYNCHRO, KeyMeshing::None, &key(SBox::CryptoProA));
+        assert_eq!(parts(&result), (
+            "26473d98bef072781669db8548336457".into(),
+            "9c8e23cd0eed4e5087ffc8d66fca6c87dd6d099b457d199e".into(),
+            "469e68bbfbc5890f".into()
+        ));
+        assert_eq!(gamma_feedback_decrypt(&result, &SYNCHRO, KeyMeshing::None, &key(SBox::CryptoProA)), data);
+        let short = gamma_feedback_encrypt(&data[..13], &SYNCHRO, KeyMeshing::None, &key(SBox::CryptoProA));
+        assert_eq!(short, result[..13]);
+        assert_eq!(gamma_feedback_decrypt(&short, &SYNCHRO, KeyMeshing::None, &key(SBox::CryptoProA)), data[..13]);
+    }
+
+    #[test]
+    fn test_gamma_feedback_meshing() {
+        let data = data(2 * MESHING_INTERVAL + 8);
+        let result = gamma_feedback_encrypt(&data, &SYNCHRO, KeyMeshing::CryptoPro, &key(SBox::CryptoProA));
+        assert_eq!(parts(&result), (
+            "26473d98bef072781669db8548336457".into(),
+            "9c8e23cd0eed4e50f752bb5477d9e09451f92e7adace49ca".into(),
+            "8e56c97e622b86e8".into()
+        ));
+        assert_eq!(gamma_feedback_decrypt(&result, &SYNCHRO, KeyMeshing::CryptoPro, &key(SBox::CryptoProA)), data);
+    }
+
+    #[test]
+    fn test_imitovstavka() {
+        let key = key(SBox::GostR3411Test);
+        for (len, valid) in [(5, "2a01ceeb"), (13, "53535250"), (16, "b7dfe405"), (100, "2bbac514")] {
+            assert_eq!(hex::encode(imitovstavka(&data(len), 4, KeyMeshing::None, &key).unwrap()), valid);
+        }
+        assert_eq!(hex::encode(imitovstavka(&data(5), 2, KeyMeshing::None, &key).unwrap()), "2a01");
+        assert!(imitovstavka(&data(8), 0, KeyMeshing::None, &key).is_err());
+        assert!(imitovstavka(&data(8), 5, KeyMeshing::None, &key).is_err());
+    }
+
+    #[test]
+    fn test_imitovstavka_meshing() {
+        let key = key(SBox::Tc26Z);
+        for (len, valid) in [(1024, "7be229c7"), (1032, "bcc949ab"), (2056, "a209ba85")] {
+            assert_eq!(hex::encode(imitovstavka(&data(len), 4, KeyMeshing::CryptoPro, &key).unwrap()), valid);
+        }
+        assert_eq!(hex::encode(imitovstavka(&data(1032), 4, KeyMeshing::None, &key).unwrap()), "a18d7c1b");
+    }
+}
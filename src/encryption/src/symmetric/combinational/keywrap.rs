@@ -0,0 +1,231 @@
+//! Export of keys encrypted under a key-encryption key: KExp15 and KImp15
+//! of R 1323565.1.017-2018 for any `BlockCipher` and the AES Key Wrap
+//! of RFC 3394 with its padded variant of RFC 5649 for 128-bit ciphers.
+//!
+//! Unwrapping fails with `InvalidTag` when the integrity check does not match.
+
+use crate::errors::CipherError;
+use super::{mac, modes, BlockCipher};
+
+const KW_IV: [u8; 8] = [0xa6; 8];
+const KWP_IV: [u8; 4] = [0xa6, 0x59, 0x59, 0xa6];
+
+/// `CTR(K_enc, IV, K || OMAC(K_mac, IV || K))`, the vector is half of the block
+pub fn kexp15<C: BlockCipher>(key: &[u8], mac_key: &C, enc_key: &C, iv: &[u8]) -> Result<Vec<u8>, CipherError> {
+    if key.is_empty() { Err(CipherError::EmptyValue { param: "Ключ" })?; }
+    let tag = mac::mac(&[iv, key].concat(), C::BLOCK_SIZE, mac_key)?;
+    modes::ctr(&[key, &tag].concat(), iv, C::BLOCK_SIZE, enc_key)
+}
+
+pub fn kimp15<C: BlockCipher>(data: &[u8], mac_key: &C, enc_key: &C, iv: &[u8]) -> Result<Vec<u8>, CipherError> {
+    if data.len() <= C::BLOCK_SIZE {
+        Err(CipherError::invalid_size(
+            "Данные", &format!("Длина экспортированного ключа должна быть больше {} байт", C::BLOCK_SIZE)
+        ))?;
+    }
+    let data = modes::ctr(data, iv, C::BLOCK_SIZE, enc_key)?;
+    let (key, tag) = data.split_at(data.len() - C::BLOCK_SIZE);
+    if !mac::verify(&[iv, key].concat(), tag, mac_key)? { Err(CipherError::InvalidTag)?; }
+    Ok(key.to_vec())
+}
+
+/// AES-KW of RFC 3394, the key is a multiple of 8 bytes and at least 16 bytes long
+pub fn wrap<C: BlockCipher>(data: &[u8], key: &C) -> Result<Vec<u8>, CipherError> {
+    if C::BLOCK_SIZE != 16 { Err(CipherError::UnsupportedOperation)?; }
+    if data.len() < 16 || !data.len().is_multiple_of(8) {
+        Err(CipherError::invalid_size(
+            "Данные", "Длина ключа должна быть кратна 8 байтам и не меньше 16 байт"
+        ))?;
+    }
+    Ok(wrap_blocks(KW_IV, data, key))
+}
+
+pub fn unwrap<C: BlockCipher>(data: &[u8], key: &C) -> Result<Vec<u8>, CipherError> {
+    if C::BLOCK_SIZE != 16 { Err(CipherError::UnsupportedOperation)?; }
+    check_wrapped(data, 24)?;
+    let (check, result) = unwrap_blocks(data, key);
+    if check != KW_IV { Err(CipherError::InvalidTag)?; }
+    Ok(result)
+}
+
+/// AES-KWP of RFC 5649, the key may be of any length
+pub fn wrap_padded<C: BlockCipher>(data: &[u8], key: &C) -> Result<Vec<u8>, CipherError> {
+    if C::BLOCK_SIZE != 16 { Err(CipherError::UnsupportedOperation)?; }
+    if data.is_empty() { Err(CipherError::EmptyValue { param: "Ключ" })?; }
+    let len = u32::try_from(data.len()).map_err(|_| CipherError::invalid_size(
+        "Данные", "Длина ключа не должна превышать 2^32 - 1 байт"
+    ))?;
+    let mut check = [0; 8];
+    check[..4].copy_from_slice(&KWP_IV);
+    check[4..].copy_from_slice(&len.to_be_bytes());
+    let mut padded = data.to_vec();
+    padded.resize(data.len().div_ceil(8) * 8, 0);
+    if padded.len() == 8 {
+        let mut block = [check.as_slice(), &padded].concat();
+        key.encrypt_block(&mut block);
+        Ok(block)
+    } else {
+        Ok(wrap_blocks(check, &padded, key))
+    }
+}
+
+pub fn unwrap_padded<C: BlockCipher>(data: &[u8], key: &C) -> Result<Vec<u8>, CipherError> {
+    if C::BLOCK_SIZE != 16 { Err(CipherError::UnsupportedOperation)?; }
+    check_wrapped(data, 16)?;
+    let (check, mut result) = if data.len() == 16 {
+        let mut block = data.to_vec();
+        key.decrypt_block(&mut block);
+        (block[..8].try_into().unwrap(), block[8..].to_vec())
+    } else {
+        unwrap_blocks(data, key)
+    };
+    let len = u32::from_be_bytes(check[4..].try_into().unwrap()) as usize;
+    if check[..4] != KWP_IV || len > result.len() || len + 8 <= result.len()
+        || result[len..].iter().any(|byte| *byte != 0)
+    {
+        Err(CipherError::InvalidTag)?;
+    }
+    result.truncate(len);
+    Ok(result)
+}
+
+fn check_wrapped(data: &[u8], min: usize) -> Result<(), CipherError> {
+    if data.len() < min || !data.len().is_multiple_of(8) {
+        Err(CipherError::invalid_size(
+            "Данные", &format!("Длина зашифрованного ключа должна быть кратна 8 байтам и не меньше {min} байт")
+        ))?;
+    }
+    Ok(())
+}
+
+/// Wrapping function `W` with the initial value `check`
+fn wrap_blocks<C: BlockCipher>(check: [u8; 8], data: &[u8], key: &C) -> Vec<u8> {
+    let mut check = check;
+    let mut blocks: Vec<[u8; 8]> = data.chunks(8).map(|block| block.try_into().unwrap()).collect();
+    let n = blocks.len();
+    let mut result = [0; 16];
+    for j in 0..6 {
+        for (i, block) in blocks.iter_mut().enumerate() {
+            result[..8].copy_from_slice(&check);
+            result[8..].copy_from_slice(block);
+            key.encrypt_block(&mut result);
+            let t = (n * j + i + 1) as u64;
+            check = (u64::from_be_bytes(result[..8].try_into().unwrap()) ^ t).to_be_bytes();
+            block.copy_from_slice(&result[8..]);
+        }
+    }
+    std::iter::once(check).chain(blocks).flatten().collect()
+}
+
+/// Unwrapping function `W^-1`, returns the recovered initial value and the key
+fn unwrap_blocks<C: BlockCipher>(data: &[u8], key: &C) -> ([u8; 8], Vec<u8>) {
+    let mut check: [u8; 8] = data[..8].try_into().unwrap();
+    let mut blocks: Vec<[u8; 8]> = data[8..].chunks(8).map(|block| block.try_into().unwrap()).collect();
+    let n = blocks.len();
+    let mut result = [0; 16];
+    for j in (0..6).rev() {
+        for (i, block) in blocks.iter_mut().enumerate().rev() {
+            let t = (n * j + i + 1) as u64;
+            result[..8].copy_from_slice(&(u64::from_be_bytes(check) ^ t).to_be_bytes());
+            result[8..].copy_from_slice(block);
+            key.decrypt_block(&mut result);
+            check = result[..8].try_into().unwrap();
+            block.copy_from_slice(&result[8..]);
+        }
+    }
+    (check, blocks.into_iter().flatten().collect())
+}
+
+#[cfg(test)]
+mod keywrap_tests {
+    use crate::aes::AesKey;
+    use crate::kuznechik::KuznechikKey;
+    use crate::magma::MagmaKey;
+    use super::*;
+
+    fn bytes(value: &str) -> Vec<u8> {
+        hex::decode(value).unwrap()
+    }
+
+    /// Example of R 1323565.1.017-2018
+    fn keys() -> (Vec<u8>, [u8; 32], [u8; 32]) {
+        let key = bytes("8899aabbccddeeff0011223344556677fedcba98765432100123456789abcdef");
+        let mac_key = bytes("08090a0b0c0d0e0f0001020304050607101112131415161718191a1b1c1d1e1f");
+        let enc_key = bytes("202122232425262728292a2b2c2d2e2f38393a3b3c3d3e3f3031323334353637");
+        (key, mac_key.try_into().unwrap(), enc_key.try_into().unwrap())
+    }
+
+    #[test]
+    fn test_kexp15_magma() {
+        let (key, mac_key, enc_key) = keys();
+        let (mac_key, enc_key) = (MagmaKey::new(mac_key), MagmaKey::new(enc_key));
+        let iv = bytes("67bed654");
+        let result = kexp15(&key, &mac_key, &enc_key, &iv).unwrap();
+        assert_eq!(
+            hex::encode(&result),
+            "cfd5a12d5b81b6e1e99c916d07900c6ac12703fb3abded55567bf3742c899c755dafe7b42e3a8bd9"
+        );
+        assert_eq!(kimp15(&result, &mac_key, &enc_key, &iv).unwrap(), key);
+        assert_eq!(kimp15(&result, &enc_key, &enc_key, &iv), Err(CipherError::InvalidTag));
+    }
+
+    #[test]
+    fn test_kexp15_kuznechik() {
+        let (key, mac_key, enc_key) = keys();
+        let (mac_key, enc_key) = (KuznechikKey::new(mac_key), KuznechikKey::new(enc_key));
+        let iv = bytes("0909472dd9f26be8");
+        let mut result = kexp15(&key, &mac_key, &enc_key, &iv).unwrap();
+        assert_eq!(
+            hex::encode(&result),
+            "e36184e84e8d736ff36cc2e5ae065dc656b23c20f549b02fdff88e1f3f30d8c2\
+             9a53f3ca554dbad80de152b9a4625b32"
+        );
+        assert_eq!(kimp15(&result, &mac_key, &enc_key, &iv).unwrap(), key);
+        result[0] ^= 1;
+        assert_eq!(kimp15(&result, &mac_key, &enc_key, &iv), Err(CipherError::InvalidTag));
+        assert!(kimp15(&result[..16], &mac_key, &enc_key, &iv).is_err());
+        assert!(kexp15(&key, &mac_key, &enc_key, &iv[..4]).is_err());
+    }
+
+    #[test]
+    fn test_wrap() {
+        let key = AesKey::from_hex("000102030405060708090a0b0c0d0e0f").unwrap();
+        let data = bytes("00112233445566778899aabbccddeeff");
+        let mut result = wrap(&data, &key).unwrap();
+        assert_eq!(hex::encode(&result), "1fa68b0a8112b447aef34bd8fb5a7b829d3e862371d2cfe5");
+        assert_eq!(unwrap(&result, &key).unwrap(), data);
+        result[23] ^= 1;
+        assert_eq!(unwrap(&result, &key), Err(CipherError::InvalidTag));
+        assert!(wrap(&data[..12], &key).is_err());
+        assert!(unwrap(&result[..16], &key).is_err());
+        assert_eq!(wrap(&data, &MagmaKey::new([0; 32])), Err(CipherError::UnsupportedOperation));
+    }
+
+    #[test]
+    fn test_wrap_256() {
+        let key = AesKey::from_hex("000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f").unwrap();
+        let data = bytes("00112233445566778899aabbccddeeff000102030405060708090a0b0c0d0e0f");
+        let result = wrap(&data, &key).unwrap();
+        assert_eq!(
+            hex::encode(&result),
+            "28c9f404c4b810f4cbccb35cfb87f8263f5786e2d80ed326cbc7f0e71a99f43bfb988b9b7a02dd21"
+        );
+        assert_eq!(unwrap(&result, &key).unwrap(), data);
+    }
+
+    #[test]
+    fn test_wrap_padded() {
+        let key = AesKey::from_hex("5840df6e29b02af1ab493b705bf16ea1ae8338f4dcc176a8").unwrap();
+        let data = bytes("c37b7e6492584340bed12207808941155068f738");
+        let result = wrap_padded(&data, &key).unwrap();
+        assert_eq!(hex::encode(&result), "138bdeaa9b8fa7fc61f97742e72248ee5ae6ae5360d1ae6a5f54f373fa543b6a");
+        assert_eq!(unwrap_padded(&result, &key).unwrap(), data);
+        let data = bytes("466f7250617369");
+        let mut result = wrap_padded(&data, &key).unwrap();
+        assert_eq!(hex::encode(&result), "afbeb0f07dfbf5419200f2ccb50bb24f");
+        assert_eq!(unwrap_padded(&result, &key).unwrap(), data);
+        result[0] ^= 1;
+        assert_eq!(unwrap_padded(&result, &key), Err(CipherError::InvalidTag));
+        assert!(wrap_padded(&[], &key).is_err());
+    }
+}
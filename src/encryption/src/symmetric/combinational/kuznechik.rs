@@ -1,5 +1,12 @@
-use std::error::Error;
+use std::fmt;
+use std::sync::OnceLock;
+use zeroize::Zeroize;
+use crate::errors::CipherError;
+use crate::cipher::Cipher;
 use crate::methods::{bytes_to_hex, hex_to_bytes};
+use crate::secret::Secret;
+use super::{aead, gf_mul, key_from_hex, keywrap, lookup, mac, modes, process_blocks, BlockCipher};
+use super::padding::Padding;
 
 const S: [u8; 256] = [
     0xFC, 0xEE, 0xDD, 0x11, 0xCF, 0x6E, 0x31, 0x16, 0xFB, 0xC4, 0xFA, 0xDA, 0x23, 0xC5, 0x04, 0x4D,
@@ -79,140 +86,335 @@ const C: [[u8; 16]; 32] = [
     [0x5e, 0xa7, 0xd8, 0x58, 0x1e, 0x14, 0x9b, 0x61, 0xf1, 0x6a, 0xc1, 0x45, 0x9c, 0xed, 0xa8, 0x20],
 ];
 
-fn x(left: &[u8], right: &[u8]) -> Vec<u8> {
-    left.iter().zip(right.iter()).map(|(left, right)| *left ^ *right).collect()
+type Block = [u8; 16];
+
+fn x(left: &Block, right: &Block) -> Block {
+    std::array::from_fn(|i| left[i] ^ right[i])
 }
 
-fn s(part: &[u8]) -> Vec<u8> {
-    part.iter().map(|elem| S[*elem as usize]).collect()
+fn s(part: &Block) -> Block {
+    part.map(|elem| lookup(&S, elem as usize))
 }
 
-fn s_reverse(part: &[u8]) -> Vec<u8> {
-    part.iter().map(|elem| S_REVERSE[*elem as usize]).collect()
+fn s_reverse(part: &Block) -> Block {
+    part.map(|elem| lookup(&S_REVERSE, elem as usize))
 }
 
-fn gf_mul(mut left: u8, mut right: u8) -> u8 {
-    let mut result: u8 = 0;
-    let mut hi_bit: u8;
-    for _ in 0..8 {
-        if right & 1 != 0 { result ^= left }
-        hi_bit = left & 0x80;
-        left <<= 1;
-        if hi_bit != 0 { left ^= 0xc3 }
-        right >>= 1;
-    }
-    result
+fn r(data: &Block) -> Block {
+    let a_15 = data.iter().zip(L_VEC.iter()).fold(0, |acc, (elem, coef)| acc ^ gf_mul(*elem, *coef, 0xc3));
+    std::array::from_fn(|i| if i == 0 { a_15 } else { data[i - 1] })
 }
 
-fn r(data: &[u8]) -> Vec<u8> {
-    let mut result = data[..data.len() - 1].to_vec();
-    let mut a_15 = 0_u8;
-    for (elem, coef) in data.iter().zip(L_VEC.iter()) {
-        a_15 ^= gf_mul(*elem, *coef);
-    }
-    result.insert(0, a_15);
-    result
+fn r_reverse(data: &Block) -> Block {
+    let a_0 = data[1..].iter().chain([&data[0]]).zip(L_VEC.iter())
+        .fold(0, |acc, (elem, coef)| acc ^ gf_mul(*elem, *coef, 0xc3));
+    std::array::from_fn(|i| if i == 15 { a_0 } else { data[i + 1] })
 }
 
-fn r_reverse(data: &[u8]) -> Vec<u8> {
-    let mut result = data[1..].to_vec();
-    let mut a_0 = 0;
-    let mut copy: Vec<u8> = data[1..].to_vec();
-    copy.push(*data.first().unwrap());
-    for (elem, coef) in copy.iter().zip(L_VEC.iter()) {
-        a_0 ^= gf_mul(*elem, *coef);
-    }
-    result.push(a_0);
-    result
+fn l(data: &Block, func: fn(&Block) -> Block) -> Block {
+    (0..16).fold(*data, |data, _| func(&data))
 }
 
-fn l<T>(data: &[u8], func: T) -> Vec<u8>
-    where T: Fn(&[u8]) -> Vec<u8>
-{
-    let mut data = data.to_vec();
-    for _ in 0..16 {
-        data = func(&data);
-    }
-    data
+fn f(left: &Block, right: &Block, iter_c: &Block) -> (Block, Block) {
+    (x(&l(&s(&x(left, iter_c)), r), right), *left)
+}
+
+/// Linear transformations of a single byte at every position of the block:
+/// `ls` includes the substitution, `l_reverse` is the inverse of `L` alone
+struct Tables {
+    ls: [[u128; 256]; 16],
+    l_reverse: [[u128; 256]; 16]
 }
 
-fn f(left: &[u8], right: &[u8], iter_c: &[u8]) -> (Vec<u8>, Vec<u8>) {
-    (x(&l(&s(&x(left, iter_c)), r), right), left.to_vec())
+fn tables() -> &'static Tables {
+    static TABLES: OnceLock<Tables> = OnceLock::new();
+    TABLES.get_or_init(|| {
+        let mut tables = Tables { ls: [[0; 256]; 16], l_reverse: [[0; 256]; 16] };
+        for i in 0..16 {
+            for (byte, value) in S.iter().enumerate() {
+                let mut block = [0; 16];
+                block[i] = *value;
+                tables.ls[i][byte] = u128::from_be_bytes(l(&block, r));
+                block[i] = byte as u8;
+                tables.l_reverse[i][byte] = u128::from_be_bytes(l(&block, r_reverse));
+            }
+        }
+        tables
+    })
 }
 
-fn expand_key(key: &[u8]) -> Vec<Vec<u8>> {
-    let mut result: Vec<Vec<u8>> = vec![
-        key[..16].to_vec(),
-        key[16..].to_vec()
-    ];
+fn apply(table: &[[u128; 256]; 16], value: u128) -> u128 {
+    value.to_be_bytes().iter().zip(table).fold(0, |acc, (byte, row)| acc ^ lookup(row, *byte as usize))
+}
+
+fn expand_key(key: &[u8; 32]) -> [Block; 10] {
+    let mut result = [[0; 16]; 10];
+    result[0].copy_from_slice(&key[..16]);
+    result[1].copy_from_slice(&key[16..]);
     for i in 0..4_usize {
-        let (mut p1, mut p2) = (result.get(i * 2).unwrap().clone(), result.get(i * 2 + 1).unwrap().clone());
+        let (mut p1, mut p2) = (result[i * 2], result[i * 2 + 1]);
         for j in 0..8 {
             (p1, p2) = f(&p1, &p2, &C[i * 8 + j]);
         }
-        result.push(p1);
-        result.push(p2);
+        result[i * 2 + 2] = p1;
+        result[i * 2 + 3] = p2;
     }
     result
 }
 
-fn enc(part: &[u8], keys: &[Vec<u8>]) -> Vec<u8> {
-    let mut result = part.to_vec();
-    for key in keys[..keys.len()-1].iter() {
-        result = l(&s(&x(&result, key)), r);
+fn enc(part: &Block, keys: &[u128; 10]) -> Block {
+    let tables = tables();
+    let result = keys[1..].iter()
+        .fold(u128::from_be_bytes(*part) ^ keys[0], |state, key| apply(&tables.ls, state) ^ key);
+    result.to_be_bytes()
+}
+
+fn dec(part: &Block, keys: &[u128; 10]) -> Block {
+    let tables = tables();
+    let result = keys[1..].iter().rev().fold(u128::from_be_bytes(*part), |state, key| {
+        u128::from_be_bytes(s_reverse(&apply(&tables.l_reverse, state ^ key).to_be_bytes()))
+    });
+    (result ^ keys[0]).to_be_bytes()
+}
+
+/// Kuznechik key with the round keys expanded once
+#[derive(Clone)]
+pub struct KuznechikKey {
+    keys: [u128; 10]
+}
+
+impl KuznechikKey {
+    pub fn new(key: [u8; 32]) -> Self {
+        KuznechikKey::expand(&key)
+    }
+
+    fn expand(key: &[u8; 32]) -> Self {
+        let mut blocks = expand_key(key);
+        let result = KuznechikKey { keys: blocks.map(u128::from_be_bytes) };
+        blocks.zeroize();
+        result
+    }
+
+    pub fn from_hex(key: &str) -> Result<Self, CipherError> {
+        Self::try_from(Secret::<Vec<u8>>::from_hex(key)?.expose().as_slice())
     }
-    result = x(&result, keys.last().unwrap());
-    result
 }
 
-fn dec(part: &[u8], keys: &[Vec<u8>]) -> Vec<u8> {
-    let mut result = part.to_vec();
-    for key in keys[1..].iter().rev() {
-        result = s_reverse(&l(&x(&result, key), r_reverse));
+impl fmt::Debug for KuznechikKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("KuznechikKey").finish_non_exhaustive()
     }
-    result = x(&result, keys.first().unwrap());
-    result
 }
 
-fn proto<T>(phrase: &str, key: &str, encryption_func: T) -> Result<String, Box<dyn Error>> 
-    where T: Fn(&[u8], &[Vec<u8>]) -> Vec<u8>
-{
-    let phrase = hex_to_bytes(phrase, 16)?;
-    let key = hex_to_bytes(key, 32)?;
-    let keys = expand_key(&key);
-    let mut result: Vec<u8> = Vec::new();
-    for segment in phrase.windows(16).step_by(16) {
-        result.extend(encryption_func(segment, &keys));
+impl From<[u8; 32]> for KuznechikKey {
+    fn from(value: [u8; 32]) -> Self {
+        KuznechikKey::new(value)
+    }
+}
+
+/// Expands the key without copying it out of the `Secret`
+impl From<&Secret<[u8; 32]>> for KuznechikKey {
+    fn from(value: &Secret<[u8; 32]>) -> Self {
+        KuznechikKey::expand(value.expose())
+    }
+}
+
+impl TryFrom<&[u8]> for KuznechikKey {
+    type Error = CipherError;
+
+    fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
+        Ok(KuznechikKey::from(&Secret::try_from(value)?))
+    }
+}
+
+impl Drop for KuznechikKey {
+    fn drop(&mut self) {
+        self.keys.zeroize();
+    }
+}
+
+impl BlockCipher for KuznechikKey {
+    const BLOCK_SIZE: usize = 16;
+
+    fn encrypt_block(&self, block: &mut [u8]) {
+        let block: &mut [u8; 16] = block.try_into().unwrap();
+        *block = enc(block, &self.keys);
+    }
+
+    fn decrypt_block(&self, block: &mut [u8]) {
+        let block: &mut [u8; 16] = block.try_into().unwrap();
+        *block = dec(block, &self.keys);
+    }
+}
+
+pub fn encrypt_block(block: &[u8; 16], key: &KuznechikKey) -> [u8; 16] {
+    enc(block, &key.keys)
+}
+
+pub fn decrypt_block(block: &[u8; 16], key: &KuznechikKey) -> [u8; 16] {
+    dec(block, &key.keys)
+}
+
+pub fn encrypt_bytes(data: &[u8], key: &KuznechikKey) -> Result<Vec<u8>, CipherError> {
+    process_blocks(data, 16, |block| key.encrypt_block(block))
+}
+
+pub fn decrypt_bytes(data: &[u8], key: &KuznechikKey) -> Result<Vec<u8>, CipherError> {
+    process_blocks(data, 16, |block| key.decrypt_block(block))
+}
+
+/// CTR mode, the counter starts with `init_v` followed by zeros
+pub fn ctr(data: &[u8], init_v: &[u8; 8], key: &KuznechikKey) -> Result<Vec<u8>, CipherError> {
+    modes::ctr(data, init_v, 16, key)
+}
+
+/// CTR mode with the whole initial counter block (NIST SP 800-38A)
+pub fn ctr_from(data: &[u8], counter: &[u8; 16], key: &KuznechikKey) -> Result<Vec<u8>, CipherError> {
+    modes::ctr_from(data, counter, 16, key)
+}
+
+pub fn encrypt_padded(data: &[u8], padding: Padding, key: &KuznechikKey) -> Result<Vec<u8>, CipherError> {
+    modes::ecb_encrypt(data, padding, key)
+}
+
+pub fn decrypt_padded(data: &[u8], padding: Padding, key: &KuznechikKey) -> Result<Vec<u8>, CipherError> {
+    modes::ecb_decrypt(data, padding, key)
+}
+
+/// Imitovstavka of GOST R 34.13-2015 truncated to `len` bytes
+pub fn mac(data: &[u8], len: usize, key: &KuznechikKey) -> Result<Vec<u8>, CipherError> {
+    mac::mac(data, len, key)
+}
+
+/// MGM of RFC 9058, the tag of `tag_len` bytes follows the ciphertext
+pub fn mgm_encrypt(
+    data: &[u8], associated: &[u8], nonce: &[u8; 16], tag_len: usize, key: &KuznechikKey
+) -> Result<Vec<u8>, CipherError> {
+    aead::mgm_encrypt(data, associated, nonce, tag_len, key)
+}
+
+pub fn mgm_decrypt(
+    data: &[u8], associated: &[u8], nonce: &[u8; 16], tag_len: usize, key: &KuznechikKey
+) -> Result<Vec<u8>, CipherError> {
+    aead::mgm_decrypt(data, associated, nonce, tag_len, key)
+}
+
+/// KExp15 of R 1323565.1.017-2018
+pub fn kexp15(key: &[u8], mac_key: &KuznechikKey, enc_key: &KuznechikKey, iv: &[u8; 8]) -> Result<Vec<u8>, CipherError> {
+    keywrap::kexp15(key, mac_key, enc_key, iv)
+}
+
+pub fn kimp15(data: &[u8], mac_key: &KuznechikKey, enc_key: &KuznechikKey, iv: &[u8; 8]) -> Result<Vec<u8>, CipherError> {
+    keywrap::kimp15(data, mac_key, enc_key, iv)
+}
+
+/// The phrase is completed with zeros to the block border
+pub fn encrypt(phrase: &str, key: &str) -> Result<String, CipherError> {
+    encrypt_with_padding(phrase, key, Padding::Zero)
+}
+
+pub fn decrypt(phrase: &str, key: &str) -> Result<String, CipherError> {
+    decrypt_with_padding(phrase, key, Padding::Zero)
+}
+
+pub fn encrypt_with_padding(phrase: &str, key: &str, padding: Padding) -> Result<String, CipherError> {
+    let key = KuznechikKey::from(&key_from_hex(key)?);
+    Ok(bytes_to_hex(&encrypt_padded(&hex_to_bytes(phrase, 1)?, padding, &key)?))
+}
+
+pub fn decrypt_with_padding(phrase: &str, key: &str, padding: Padding) -> Result<String, CipherError> {
+    let key = KuznechikKey::from(&key_from_hex(key)?);
+    Ok(bytes_to_hex(&decrypt_padded(&hex_to_bytes(phrase, 1)?, padding, &key)?))
+}
+
+/// State of the block after every transformation of a round
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Round {
+    pub x: [u8; 16],
+    pub s: [u8; 16],
+    pub l: [u8; 16],
+}
+
+/// Log of the encryption of one block, the last round key is added after the rounds
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Trace {
+    pub round_keys: [[u8; 16]; 10],
+    pub rounds: Vec<Round>,
+}
+
+impl fmt::Display for Trace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Раундовые ключи:")?;
+        for (i, key) in self.round_keys.iter().enumerate() {
+            writeln!(f, "K{}: {}", i + 1, bytes_to_hex(key))?;
+        }
+        for (i, round) in self.rounds.iter().enumerate() {
+            writeln!(f, "Раунд {}\nX: {}\nS: {}\nL: {}", i + 1,
+                bytes_to_hex(&round.x), bytes_to_hex(&round.s), bytes_to_hex(&round.l))?;
+        }
+        Ok(())
     }
-    Ok(bytes_to_hex(&result))
 }
 
-pub fn encrypt(phrase: &str, key: &str) -> Result<String, Box<dyn Error>> {
-    proto(phrase, key, enc)
+/// Encrypts the block transformation by transformation without the tables
+pub fn encrypt_block_traced(block: &[u8; 16], key: &KuznechikKey) -> ([u8; 16], Trace) {
+    let round_keys = key.keys.map(u128::to_be_bytes);
+    let mut state = *block;
+    let rounds = round_keys[..9].iter().map(|round_key| {
+        let x = x(&state, round_key);
+        let s = s(&x);
+        state = l(&s, r);
+        Round { x, s, l: state }
+    }).collect();
+    (x(&state, &round_keys[9]), Trace { round_keys, rounds })
 }
 
-pub fn decrypt(phrase: &str, key: &str) -> Result<String, Box<dyn Error>> {
-    proto(phrase, key, dec)
+/// Same as `encrypt` with the log of every block
+pub fn encrypt_traced(phrase: &str, key: &str) -> Result<(String, Vec<Trace>), CipherError> {
+    let key = KuznechikKey::from(&key_from_hex(key)?);
+    let data = Padding::Zero.pad(&hex_to_bytes(phrase, 1)?, 16)?;
+    let (result, traces): (Vec<Block>, Vec<Trace>) = data.chunks(16)
+        .map(|block| encrypt_block_traced(block.try_into().unwrap(), &key))
+        .unzip();
+    Ok((bytes_to_hex(result.as_flattened()), traces))
+}
+
+pub struct Kuznechik;
+
+impl Cipher for Kuznechik {
+    type Key = KuznechikKey;
+
+    fn encrypt(&self, phrase: &str, key: &KuznechikKey) -> Result<String, CipherError> {
+        Ok(bytes_to_hex(&encrypt_padded(&hex_to_bytes(phrase, 1)?, Padding::Zero, key)?))
+    }
+
+    fn decrypt(&self, phrase: &str, key: &KuznechikKey) -> Result<String, CipherError> {
+        Ok(bytes_to_hex(&decrypt_padded(&hex_to_bytes(phrase, 1)?, Padding::Zero, key)?))
+    }
 }
 
 #[cfg(test)]
 mod kuznechik_tests {
     use crate::methods::hex_to_bytes;
     use super::*;
+
+    fn block(value: &str) -> Block {
+        hex_to_bytes(value, 1).unwrap().try_into().unwrap()
+    }
     
     #[test]
     fn test_s() {
         let data = [
-            hex_to_bytes("ffeeddccbbaa99881122334455667700", 1).unwrap(),
-            hex_to_bytes("b66cd8887d38e8d77765aeea0c9a7efc", 1).unwrap(),
-            hex_to_bytes("559d8dd7bd06cbfe7e7b262523280d39", 1).unwrap(),
-            hex_to_bytes("0c3322fed531e4630d80ef5c5a81c50b", 1).unwrap(),
+            block("ffeeddccbbaa99881122334455667700"),
+            block("b66cd8887d38e8d77765aeea0c9a7efc"),
+            block("559d8dd7bd06cbfe7e7b262523280d39"),
+            block("0c3322fed531e4630d80ef5c5a81c50b"),
         ];
         let valid = [
-            hex_to_bytes("b66cd8887d38e8d77765aeea0c9a7efc", 1).unwrap(),
-            hex_to_bytes("559d8dd7bd06cbfe7e7b262523280d39", 1).unwrap(),
-            hex_to_bytes("0c3322fed531e4630d80ef5c5a81c50b", 1).unwrap(),
-            hex_to_bytes("23ae65633f842d29c5df529c13f5acda", 1).unwrap(),
+            block("b66cd8887d38e8d77765aeea0c9a7efc"),
+            block("559d8dd7bd06cbfe7e7b262523280d39"),
+            block("0c3322fed531e4630d80ef5c5a81c50b"),
+            block("23ae65633f842d29c5df529c13f5acda"),
         ];
         for (data, valid) in data.iter().zip(valid.iter()) {
             assert_eq!(s(data), *valid);
@@ -222,16 +424,16 @@ mod kuznechik_tests {
     #[test]
     fn test_r() {
         let data = [
-            hex_to_bytes("00000000000000000000000000000100", 1).unwrap(),
-            hex_to_bytes("94000000000000000000000000000001", 1).unwrap(),
-            hex_to_bytes("a5940000000000000000000000000000", 1).unwrap(),
-            hex_to_bytes("64a59400000000000000000000000000", 1).unwrap(),
+            block("00000000000000000000000000000100"),
+            block("94000000000000000000000000000001"),
+            block("a5940000000000000000000000000000"),
+            block("64a59400000000000000000000000000"),
         ];
         let valid = [
-            hex_to_bytes("94000000000000000000000000000001", 1).unwrap(),
-            hex_to_bytes("a5940000000000000000000000000000", 1).unwrap(),
-            hex_to_bytes("64a59400000000000000000000000000", 1).unwrap(),
-            hex_to_bytes("0d64a594000000000000000000000000", 1).unwrap(),
+            block("94000000000000000000000000000001"),
+            block("a5940000000000000000000000000000"),
+            block("64a59400000000000000000000000000"),
+            block("0d64a594000000000000000000000000"),
         ];
         for (data, valid) in data.iter().zip(valid.iter()) {
             assert_eq!(r(data), *valid);
@@ -241,16 +443,16 @@ mod kuznechik_tests {
     #[test]
     fn test_r_reverse() {
         let data = [
-            hex_to_bytes("94000000000000000000000000000001", 1).unwrap(),
-            hex_to_bytes("a5940000000000000000000000000000", 1).unwrap(),
-            hex_to_bytes("64a59400000000000000000000000000", 1).unwrap(),
-            hex_to_bytes("0d64a594000000000000000000000000", 1).unwrap(),
+            block("94000000000000000000000000000001"),
+            block("a5940000000000000000000000000000"),
+            block("64a59400000000000000000000000000"),
+            block("0d64a594000000000000000000000000"),
         ];
         let valid = [
-            hex_to_bytes("00000000000000000000000000000100", 1).unwrap(),
-            hex_to_bytes("94000000000000000000000000000001", 1).unwrap(),
-            hex_to_bytes("a5940000000000000000000000000000", 1).unwrap(),
-            hex_to_bytes("64a59400000000000000000000000000", 1).unwrap(),
+            block("00000000000000000000000000000100"),
+            block("94000000000000000000000000000001"),
+            block("a5940000000000000000000000000000"),
+            block("64a59400000000000000000000000000"),
         ];
         for (data, valid) in data.iter().zip(valid.iter()) {
             assert_eq!(r_reverse(data), *valid);
@@ -260,16 +462,16 @@ mod kuznechik_tests {
     #[test]
     fn test_l() {
         let data = [
-            hex_to_bytes("64a59400000000000000000000000000", 1).unwrap(),
-            hex_to_bytes("d456584dd0e3e84cc3166e4b7fa2890d", 1).unwrap(),
-            hex_to_bytes("79d26221b87b584cd42fbc4ffea5de9a", 1).unwrap(),
-            hex_to_bytes("0e93691a0cfc60408b7b68f66b513c13", 1).unwrap(),
+            block("64a59400000000000000000000000000"),
+            block("d456584dd0e3e84cc3166e4b7fa2890d"),
+            block("79d26221b87b584cd42fbc4ffea5de9a"),
+            block("0e93691a0cfc60408b7b68f66b513c13"),
         ];
         let valid = [
-            hex_to_bytes("d456584dd0e3e84cc3166e4b7fa2890d", 1).unwrap(),
-            hex_to_bytes("79d26221b87b584cd42fbc4ffea5de9a", 1).unwrap(),
-            hex_to_bytes("0e93691a0cfc60408b7b68f66b513c13", 1).unwrap(),
-            hex_to_bytes("e6a8094fee0aa204fd97bcb0b44b8580", 1).unwrap(),
+            block("d456584dd0e3e84cc3166e4b7fa2890d"),
+            block("79d26221b87b584cd42fbc4ffea5de9a"),
+            block("0e93691a0cfc60408b7b68f66b513c13"),
+            block("e6a8094fee0aa204fd97bcb0b44b8580"),
         ];
         for (data, valid) in data.iter().zip(valid.iter()) {
             assert_eq!(l(data, r), *valid);
@@ -279,16 +481,16 @@ mod kuznechik_tests {
     #[test]
     fn test_l_reverse() {
         let data = [
-            hex_to_bytes("d456584dd0e3e84cc3166e4b7fa2890d", 1).unwrap(),
-            hex_to_bytes("79d26221b87b584cd42fbc4ffea5de9a", 1).unwrap(),
-            hex_to_bytes("0e93691a0cfc60408b7b68f66b513c13", 1).unwrap(),
-            hex_to_bytes("e6a8094fee0aa204fd97bcb0b44b8580", 1).unwrap(),
+            block("d456584dd0e3e84cc3166e4b7fa2890d"),
+            block("79d26221b87b584cd42fbc4ffea5de9a"),
+            block("0e93691a0cfc60408b7b68f66b513c13"),
+            block("e6a8094fee0aa204fd97bcb0b44b8580"),
         ];
         let valid = [
-            hex_to_bytes("64a59400000000000000000000000000", 1).unwrap(),
-            hex_to_bytes("d456584dd0e3e84cc3166e4b7fa2890d", 1).unwrap(),
-            hex_to_bytes("79d26221b87b584cd42fbc4ffea5de9a", 1).unwrap(),
-            hex_to_bytes("0e93691a0cfc60408b7b68f66b513c13", 1).unwrap(),
+            block("64a59400000000000000000000000000"),
+            block("d456584dd0e3e84cc3166e4b7fa2890d"),
+            block("79d26221b87b584cd42fbc4ffea5de9a"),
+            block("0e93691a0cfc60408b7b68f66b513c13"),
         ];
         for (data, valid) in data.iter().zip(valid.iter()) {
             assert_eq!(l(data, r_reverse), *valid);
@@ -297,18 +499,18 @@ mod kuznechik_tests {
 
     #[test]
     fn test_x() {
-        let k = hex_to_bytes("8899aabbccddeeff0011223344556677", 1).unwrap();
-        let v = hex_to_bytes("e63bdcc9a09594475d369f2399d1f276", 1).unwrap();
+        let k = block("8899aabbccddeeff0011223344556677");
+        let v = block("e63bdcc9a09594475d369f2399d1f276");
         assert_eq!(x(&C[0], &k), v);
     }
 
     #[test]
     fn test_f() {
-        let k1 = hex_to_bytes("8899aabbccddeeff0011223344556677", 1).unwrap();
-        let k2 = hex_to_bytes("fedcba98765432100123456789abcdef", 1).unwrap();
+        let k1 = block("8899aabbccddeeff0011223344556677");
+        let k2 = block("fedcba98765432100123456789abcdef");
         let (r1, r2) = f(&k1, &k2, &C[0]);
-        let v1 = hex_to_bytes("c3d5fa01ebe36f7a9374427ad7ca8949", 1).unwrap();
-        let v2 = hex_to_bytes("8899aabbccddeeff0011223344556677", 1).unwrap();
+        let v1 = block("c3d5fa01ebe36f7a9374427ad7ca8949");
+        let v2 = block("8899aabbccddeeff0011223344556677");
         assert_eq!(r1, v1);
         assert_eq!(r2, v2);
     }
@@ -316,6 +518,7 @@ mod kuznechik_tests {
     #[test]
     fn test_expand_key() {
         let key = hex_to_bytes("8899aabbccddeeff0011223344556677fedcba98765432100123456789abcdef", 1).unwrap();
+        let key: [u8; 32] = key.try_into().unwrap();
         let validate = vec![
             hex_to_bytes("8899aabbccddeeff0011223344556677", 1).unwrap(),
             hex_to_bytes("fedcba98765432100123456789abcdef", 1).unwrap(),
@@ -336,9 +539,9 @@ mod kuznechik_tests {
     #[test]
     fn test_enc() {
         let key = hex_to_bytes("8899aabbccddeeff0011223344556677fedcba98765432100123456789abcdef", 32).unwrap();
-        let phrase = hex_to_bytes("1122334455667700ffeeddccbbaa9988", 16).unwrap();
-        let keys = expand_key(&key);
-        let valid = hex_to_bytes("7f679d90bebc24305a468d42b9d4edcd", 16).unwrap();
+        let phrase = block("1122334455667700ffeeddccbbaa9988");
+        let keys = KuznechikKey::new(key.try_into().unwrap()).keys;
+        let valid = block("7f679d90bebc24305a468d42b9d4edcd");
         let result = enc(&phrase, &keys);
         assert_eq!(result, valid);
     }
@@ -346,9 +549,9 @@ mod kuznechik_tests {
     #[test]
     fn test_dec() {
         let key = hex_to_bytes("8899aabbccddeeff0011223344556677fedcba98765432100123456789abcdef", 32).unwrap();
-        let phrase = hex_to_bytes("7f679d90bebc24305a468d42b9d4edcd", 16).unwrap();
-        let keys = expand_key(&key);
-        let valid = hex_to_bytes("1122334455667700ffeeddccbbaa9988", 16).unwrap();
+        let phrase = block("7f679d90bebc24305a468d42b9d4edcd");
+        let keys = KuznechikKey::new(key.try_into().unwrap()).keys;
+        let valid = block("1122334455667700ffeeddccbbaa9988");
         let result = dec(&phrase, &keys);
         assert_eq!(result, valid);
     }
@@ -368,4 +571,69 @@ mod kuznechik_tests {
         let valid = "1122334455667700ffeeddccbbaa9988";
         assert_eq!(&decrypt(text, key).unwrap(), valid);
     }
+
+    #[test]
+    fn test_bytes() {
+        let key = KuznechikKey::from_hex(
+            "8899aabbccddeeff0011223344556677fedcba98765432100123456789abcdef"
+        ).unwrap();
+        let data = hex_to_bytes("1122334455667700ffeeddccbbaa998800112233445566778899aabbcceeff0a", 1).unwrap();
+        let result = encrypt_bytes(&data, &key).unwrap();
+        assert_eq!(
+            bytes_to_hex(&result),
+            "7f679d90bebc24305a468d42b9d4edcdb429912c6e0032f9285452d76718d08b"
+        );
+        assert_eq!(decrypt_bytes(&result, &key).unwrap(), data);
+        let block: [u8; 16] = data[..16].try_into().unwrap();
+        assert_eq!(decrypt_block(&encrypt_block(&block, &key), &key), block);
+        assert!(encrypt_bytes(&data[1..], &key).is_err());
+        assert!(KuznechikKey::try_from([0u8; 16].as_slice()).is_err());
+    }
+
+    #[test]
+    fn test_secret() {
+        let hex = "8899aabbccddeeff0011223344556677fedcba98765432100123456789abcdef";
+        let key = KuznechikKey::from(&Secret::<[u8; 32]>::from_hex(hex).unwrap());
+        let block = block("1122334455667700ffeeddccbbaa9988");
+        assert_eq!(bytes_to_hex(&encrypt_block(&block, &key)), "7f679d90bebc24305a468d42b9d4edcd");
+        assert!(!format!("{key:?}").contains(&format!("{}", key.keys[0])));
+    }
+
+    #[test]
+    fn test_traced() {
+        let key = KuznechikKey::from_hex(
+            "8899aabbccddeeff0011223344556677fedcba98765432100123456789abcdef"
+        ).unwrap();
+        let data = block("1122334455667700ffeeddccbbaa9988");
+        let (result, trace) = encrypt_block_traced(&data, &key);
+        assert_eq!(result, block("7f679d90bebc24305a468d42b9d4edcd"));
+        assert_eq!(trace.round_keys[1], block("fedcba98765432100123456789abcdef"));
+        assert_eq!(trace.round_keys[2], block("db31485315694343228d6aef8cc78c44"));
+        assert_eq!(trace.rounds.len(), 9);
+        assert_eq!(trace.rounds[0].x, block("99bb99ff99bb99ffffffffffffffffff"));
+        assert_eq!(trace.rounds[0].s, s(&trace.rounds[0].x));
+        assert_eq!(trace.rounds[1].x, x(&trace.rounds[0].l, &trace.round_keys[1]));
+        let phrase = "1122334455667700ffeeddccbbaa998800112233";
+        let key = "8899aabbccddeeff0011223344556677fedcba98765432100123456789abcdef";
+        let (result, traces) = encrypt_traced(phrase, key).unwrap();
+        assert_eq!(result, encrypt(phrase, key).unwrap());
+        assert_eq!(traces.len(), 2);
+        assert!(traces[0].to_string().contains("Раунд 9\n"));
+    }
+
+    #[test]
+    fn test_ctr() {
+        let key = KuznechikKey::from_hex(
+            "8899aabbccddeeff0011223344556677fedcba98765432100123456789abcdef"
+        ).unwrap();
+        let init_v = [0x12, 0x34, 0x56, 0x78, 0x90, 0xab, 0xce, 0xf0];
+        let data = hex_to_bytes("1122334455667700ffeeddccbbaa998800112233445566", 1).unwrap();
+        let result = ctr(&data, &init_v, &key).unwrap();
+        assert_eq!(bytes_to_hex(&result), "f195d8bec10ed1dbd57b5fa240bda1b885eee733f6a13e");
+        assert_eq!(ctr(&result, &init_v, &key).unwrap(), data);
+        let counter = [0x12, 0x34, 0x56, 0x78, 0x90, 0xab, 0xce, 0xf0, 0, 0, 0, 0, 0, 0, 0, 0];
+        assert_eq!(ctr_from(&data, &counter, &key).unwrap(), result);
+        let counter = [0xff; 16];
+        assert_eq!(ctr_from(&data, &counter, &key), Err(CipherError::CounterOverflow));
+    }
 }
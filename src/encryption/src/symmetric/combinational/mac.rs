@@ -0,0 +1,128 @@
+//! Message authentication code of GOST R 34.13-2015 (imitovstavka),
+//! the same algorithm with the full block length is AES-CMAC of RFC 4493.
+
+use crate::errors::CipherError;
+use super::{ct, xor, BlockCipher};
+use super::padding::Padding;
+
+/// Subkeys `K1` and `K2` derived from the encryption of the zero block
+pub fn subkeys<C: BlockCipher>(key: &C) -> (Vec<u8>, Vec<u8>) {
+    let mut zero = vec![0; C::BLOCK_SIZE];
+    key.encrypt_block(&mut zero);
+    let first = double(&zero);
+    let second = double(&first);
+    (first, second)
+}
+
+/// Returns the first `len` bytes of the MAC of `data`
+pub fn mac<C: BlockCipher>(data: &[u8], len: usize, key: &C) -> Result<Vec<u8>, CipherError> {
+    let n = C::BLOCK_SIZE;
+    if len == 0 || len > n {
+        Err(CipherError::invalid_size(
+            "Имитовставка", &format!("Длина имитовставки должна быть от 1 до {n} байт")
+        ))?;
+    }
+    let (first, second) = subkeys(key);
+    // Only an incomplete last block is completed (GOST R 34.13-2015 procedure 3)
+    let (head, last) = data.split_at(data.len().saturating_sub(1) / n * n);
+    let (last, subkey) = if last.len() == n {
+        (last.to_vec(), first)
+    } else {
+        (Padding::Iso7816.pad(last, n)?, second)
+    };
+    let mut state = vec![0; n];
+    for block in head.chunks(n) {
+        xor(&mut state, block);
+        key.encrypt_block(&mut state);
+    }
+    xor(&mut state, &last);
+    xor(&mut state, &subkey);
+    key.encrypt_block(&mut state);
+    state.truncate(len);
+    Ok(state)
+}
+
+/// Checks the MAC truncated to the length of `tag`
+pub fn verify<C: BlockCipher>(data: &[u8], tag: &[u8], key: &C) -> Result<bool, CipherError> {
+    Ok(ct::compare(&mac(data, tag.len(), key)?, tag))
+}
+
+/// Multiplication by `x` in the field of the block size
+fn double(value: &[u8]) -> Vec<u8> {
+    let polynomial = if value.len() == 8 { 0x1b } else { 0x87 };
+    let mut result: Vec<u8> = value.iter()
+        .zip(value.iter().skip(1).chain([&0]))
+        .map(|(byte, next)| byte << 1 | next >> 7)
+        .collect();
+    if value[0] & 0x80 != 0 {
+        let last = result.len() - 1;
+        result[last] ^= polynomial;
+    }
+    result
+}
+
+#[cfg(test)]
+mod mac_tests {
+    use crate::aes::AesKey;
+    use crate::kuznechik::KuznechikKey;
+    use crate::magma::MagmaKey;
+    use crate::modes;
+    use super::*;
+
+    const AES_TEXT: &str = "6bc1bee22e409f96e93d7e117393172aae2d8a571e03ac9c9eb76fac45af8e51\
+                            30c81c46a35ce411e5fbc1191a0a52eff69f2445df4f9b17ad2b417be66c3710";
+
+    fn bytes(value: &str) -> Vec<u8> {
+        hex::decode(value).unwrap()
+    }
+
+    #[test]
+    fn test_gost() {
+        let key = KuznechikKey::from_hex("8899aabbccddeeff0011223344556677fedcba98765432100123456789abcdef").unwrap();
+        let data = bytes("1122334455667700ffeeddccbbaa998800112233445566778899aabbcceeff0a\
+                          112233445566778899aabbcceeff0a002233445566778899aabbcceeff0a0011");
+        assert_eq!(hex::encode(mac(&data, 8, &key).unwrap()), "336f4d296059fbe3");
+        let key = MagmaKey::from_hex("ffeeddccbbaa99887766554433221100f0f1f2f3f4f5f6f7f8f9fafbfcfdfeff").unwrap();
+        let data = bytes("92def06b3c130a59db54c704f8189d204a98fb2e67a8024c8912409b17b57e41");
+        let (first, second) = subkeys(&key);
+        assert_eq!(hex::encode(first), "5f459b3342521424");
+        assert_eq!(hex::encode(second), "be8b366684a42848");
+        assert_eq!(hex::encode(mac(&data, 4, &key).unwrap()), "154e7210");
+    }
+
+    #[test]
+    fn test_aes_cmac() {
+        let key = AesKey::from_hex("2b7e151628aed2a6abf7158809cf4f3c").unwrap();
+        let (first, second) = subkeys(&key);
+        assert_eq!(hex::encode(first), "fbeed618357133667c85e08f7236a8de");
+        assert_eq!(hex::encode(second), "f7ddac306ae266ccf90bc11ee46d513b");
+        let data = bytes(AES_TEXT);
+        assert_eq!(hex::encode(mac(&[], 16, &key).unwrap()), "bb1d6929e95937287fa37d129b756746");
+        assert_eq!(hex::encode(mac(&data[..16], 16, &key).unwrap()), "070a16b46b4d4144f79bdd9dd04a287c");
+        assert_eq!(hex::encode(mac(&data[..40], 16, &key).unwrap()), "dfa66747de9ae63030ca32611497c827");
+        assert_eq!(hex::encode(mac(&data, 16, &key).unwrap()), "51f0bebf7e3b9d92fc49741779363cfe");
+    }
+
+    #[test]
+    fn test_verify() {
+        let key = AesKey::from_hex("2b7e151628aed2a6abf7158809cf4f3c").unwrap();
+        let data = bytes(AES_TEXT);
+        assert!(verify(&data, &bytes("51f0bebf"), &key).unwrap());
+        assert!(!verify(&data[1..], &bytes("51f0bebf"), &key).unwrap());
+        assert!(mac(&data, 17, &key).is_err());
+        assert!(verify(&data, &[], &key).is_err());
+    }
+
+    #[test]
+    fn test_malleability() {
+        let key = MagmaKey::from_hex("ffeeddccbbaa99887766554433221100f0f1f2f3f4f5f6f7f8f9fafbfcfdfeff").unwrap();
+        let iv = [0x12, 0x34, 0x56, 0x78];
+        let data = b"pay 100".to_vec();
+        let tag = mac(&data, 4, &key).unwrap();
+        let mut encrypted = modes::ctr(&data, &iv, 8, &key).unwrap();
+        encrypted[4] ^= b'1' ^ b'9';
+        let forged = modes::ctr(&encrypted, &iv, 8, &key).unwrap();
+        assert_eq!(forged, b"pay 900");
+        assert!(!verify(&forged, &tag, &key).unwrap());
+    }
+}
@@ -1,171 +1,504 @@
-use std::error::Error;
-use crate::methods::{bytes_to_hex, hex_to_bytes, modd};
-
-const S_TABLE: [[u8; 16]; 8] = [
-    [1,7,14,13,0,5,8,3,4,15,10,6,9,12,11,2],
-    [8,14,2,5,6,9,1,12,15,4,11,0,13,10,3,7],
-    [5,13,15,6,9,2,12,10,11,7,8,1,4,3,14,0],
-    [7,15,5,10,8,1,6,13,0,9,3,14,11,4,2,12],
-    [12,8,2,1,13,4,15,6,7,0,10,5,3,14,9,11],
-    [11,3,5,8,2,15,10,13,14,1,7,4,12,9,6,0],
+use std::fmt;
+use zeroize::Zeroize;
+use crate::errors::CipherError;
+use crate::cipher::Cipher;
+use crate::methods::{bytes_to_hex, hex_to_bytes};
+use crate::secret::Secret;
+use super::{aead, key_from_hex, key_from_slice, keywrap, lookup, mac, modes, process_blocks, BlockCipher};
+use super::padding::Padding;
+
+type Table = [[u8; 16]; 8];
+
+const TC26_Z: Table = [
+    [12,4,6,2,10,5,11,9,14,8,13,7,0,3,15,1],
     [6,8,2,3,9,10,5,12,1,14,4,7,11,13,0,15],
-    [12,4,6,2,10,5,11,9,14,8,13,7,0,3,15,1]
+    [11,3,5,8,2,15,10,13,14,1,7,4,12,9,6,0],
+    [12,8,2,1,13,4,15,6,7,0,10,5,3,14,9,11],
+    [7,15,5,10,8,1,6,13,0,9,3,14,11,4,2,12],
+    [5,13,15,6,9,2,12,10,11,7,8,1,4,3,14,0],
+    [8,14,2,5,6,9,1,12,15,4,11,0,13,10,3,7],
+    [1,7,14,13,0,5,8,3,4,15,10,6,9,12,11,2]
 ];
 
-pub fn t (in_data: &[u8]) -> Vec<u8> {
-    in_data[0..4].iter().enumerate().map(|(i, number)| {
-        let first_part_byte = (*number & 0xf0) >> 4;
-        let sec_part_byte = *number & 0x0f;
-        let first_part_byte = S_TABLE[i * 2][first_part_byte as usize];
-        let sec_part_byte = S_TABLE[i * 2 + 1][sec_part_byte as usize];
-        (first_part_byte << 4) | sec_part_byte
-    }).collect()
+const TEST: Table = [
+    [4,2,15,5,9,1,0,8,14,3,11,12,13,7,10,6],
+    [12,9,15,14,8,1,3,10,2,7,4,13,6,0,11,5],
+    [13,8,14,12,7,3,9,10,1,5,2,4,6,15,0,11],
+    [14,9,11,2,5,15,7,1,0,13,12,6,10,4,3,8],
+    [3,14,5,9,6,8,0,13,10,11,7,12,2,1,15,4],
+    [8,15,6,11,1,9,12,5,13,3,7,10,0,14,2,4],
+    [9,11,12,0,3,6,7,5,4,8,14,15,1,10,2,13],
+    [12,6,5,2,11,0,9,13,3,14,7,10,15,4,1,8]
+];
+
+const GOST_R_3411_TEST: Table = [
+    [4,10,9,2,13,8,0,14,6,11,1,12,7,15,5,3],
+    [14,11,4,12,6,13,15,10,2,3,8,1,0,7,5,9],
+    [5,8,1,13,10,3,4,2,14,15,12,7,6,0,9,11],
+    [7,13,10,1,0,8,9,15,14,4,6,12,11,2,5,3],
+    [6,12,7,1,5,15,13,8,4,10,9,14,0,3,11,2],
+    [4,11,10,0,7,2,1,13,3,6,8,5,9,12,15,14],
+    [13,11,4,1,3,15,5,9,0,10,14,7,6,8,2,12],
+    [1,15,13,0,5,7,10,4,9,2,3,14,6,11,8,12]
+];
+
+const CRYPTO_PRO_A: Table = [
+    [9,6,3,2,8,11,1,7,10,4,14,15,12,0,13,5],
+    [3,7,14,9,8,10,15,0,5,2,6,12,11,4,13,1],
+    [14,4,6,2,11,3,13,8,12,15,5,10,0,7,1,9],
+    [14,7,10,12,13,1,3,9,0,2,11,4,15,8,5,6],
+    [11,5,1,9,8,13,15,0,14,4,2,3,12,7,10,6],
+    [3,10,13,12,1,2,0,11,7,5,9,4,8,15,14,6],
+    [1,13,2,9,7,10,6,0,8,12,4,5,15,3,11,14],
+    [11,10,15,5,0,12,14,8,6,2,3,9,1,7,13,4]
+];
+
+const CRYPTO_PRO_B: Table = [
+    [8,4,11,1,3,5,0,9,2,14,10,12,13,6,7,15],
+    [0,1,2,10,4,13,5,12,9,7,3,15,11,8,6,14],
+    [14,12,0,10,9,2,13,11,7,5,8,15,3,6,1,4],
+    [7,5,0,13,11,6,1,2,3,10,12,15,4,14,9,8],
+    [2,7,12,15,9,5,10,11,1,4,0,13,6,8,14,3],
+    [8,3,2,6,4,13,14,11,12,1,7,15,10,0,9,5],
+    [5,2,10,11,9,1,12,3,7,4,13,0,6,15,8,14],
+    [0,4,11,14,8,3,7,1,10,2,9,6,15,13,5,12]
+];
+
+const CRYPTO_PRO_C: Table = [
+    [1,11,12,2,9,13,0,15,4,5,8,14,10,7,6,3],
+    [0,1,7,13,11,4,5,2,8,14,15,12,9,10,6,3],
+    [8,2,5,0,4,9,15,10,3,7,12,13,6,14,1,11],
+    [3,6,0,1,5,13,10,8,11,2,9,7,14,15,12,4],
+    [8,13,11,0,4,5,1,2,9,3,12,14,6,15,10,7],
+    [12,9,11,1,8,14,2,4,7,3,6,5,10,0,15,13],
+    [10,9,6,8,13,14,2,0,15,3,5,11,4,1,12,7],
+    [7,4,0,5,10,2,15,14,12,6,1,11,13,9,3,8]
+];
+
+const CRYPTO_PRO_D: Table = [
+    [15,12,2,10,6,4,5,0,7,9,14,13,1,11,8,3],
+    [11,6,3,4,12,15,14,2,7,13,8,0,5,10,9,1],
+    [1,12,11,0,15,14,6,5,10,13,4,8,9,3,7,2],
+    [1,5,14,12,10,7,0,13,6,2,11,4,9,3,15,8],
+    [0,12,8,9,13,2,10,11,7,3,6,5,4,14,15,1],
+    [8,0,15,3,2,5,14,11,1,10,4,7,12,9,13,6],
+    [3,0,6,15,1,14,9,2,13,8,12,4,11,10,5,7],
+    [1,10,6,8,15,11,0,4,12,3,5,9,7,13,2,14]
+];
+
+/// Substitution of GOST 28147-89, the rows replace the nibbles
+/// starting from the least significant one
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum SBox {
+    /// id-tc26-gost-28147-param-Z fixed by GOST R 34.12-2015
+    #[default]
+    Tc26Z,
+    /// id-Gost28147-89-TestParamSet of RFC 4357
+    Test,
+    /// id-GostR3411-94-TestParamSet of RFC 4357, the test substitution of GOST R 34.11-94
+    GostR3411Test,
+    CryptoProA,
+    CryptoProB,
+    CryptoProC,
+    CryptoProD,
+    /// Table checked by `SBox::custom`
+    Custom(CustomSBox),
 }
 
-pub fn t_reverse(in_data: &[u8]) -> Vec<u8> {
-    in_data[0..4].iter().enumerate().map(|(i, number)| {
-        let first_part_byte = (*number & 0xf0) >> 4;
-        let sec_part_byte = *number & 0x0f;
-        let first_part_byte = S_TABLE[i * 2].iter()
-            .position(|x| *x == first_part_byte).unwrap() as u8;
-        let sec_part_byte = S_TABLE[i * 2 + 1].iter()
-            .position(|x| *x == sec_part_byte).unwrap() as u8;
-        (first_part_byte << 4) | sec_part_byte
-    }).collect()
+/// Eight permutations of 0..15
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct CustomSBox(Table);
+
+impl SBox {
+    pub const NAMED: [SBox; 7] = [
+        SBox::Tc26Z,
+        SBox::Test,
+        SBox::GostR3411Test,
+        SBox::CryptoProA,
+        SBox::CryptoProB,
+        SBox::CryptoProC,
+        SBox::CryptoProD,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            SBox::Tc26Z => "ТК 26 (ГОСТ Р 34.12-2015)",
+            SBox::Test => "Тестовый (ГОСТ 28147-89)",
+            SBox::GostR3411Test => "Тестовый (ГОСТ Р 34.11-94)",
+            SBox::CryptoProA => "CryptoPro A",
+            SBox::CryptoProB => "CryptoPro B",
+            SBox::CryptoProC => "CryptoPro C",
+            SBox::CryptoProD => "CryptoPro D",
+            SBox::Custom(_) => "Пользовательский",
+        }
+    }
+
+    /// Every row of `table` must be a permutation of 0..15
+    pub fn custom(table: [[u8; 16]; 8]) -> Result<Self, CipherError> {
+        for (index, row) in table.iter().enumerate() {
+            let mut seen = [false; 16];
+            for value in row {
+                if *value > 15 || seen[*value as usize] {
+                    Err(CipherError::invalid_key(
+                        "S-блок", &format!("Строка {index} S-блока не является перестановкой чисел от 0 до 15")
+                    ))?;
+                }
+                seen[*value as usize] = true;
+            }
+        }
+        Ok(SBox::Custom(CustomSBox(table)))
+    }
+
+    pub fn table(&self) -> &[[u8; 16]; 8] {
+        match self {
+            SBox::Tc26Z => &TC26_Z,
+            SBox::Test => &TEST,
+            SBox::GostR3411Test => &GOST_R_3411_TEST,
+            SBox::CryptoProA => &CRYPTO_PRO_A,
+            SBox::CryptoProB => &CRYPTO_PRO_B,
+            SBox::CryptoProC => &CRYPTO_PRO_C,
+            SBox::CryptoProD => &CRYPTO_PRO_D,
+            SBox::Custom(table) => &table.0,
+        }
+    }
 }
 
-fn to_32(vec: &[u8]) -> u32 {
-    let mut shift: i32 = 24;
-    vec.iter().map(|num| {
-        let result = (*num as u32) << shift;
-        shift -= 8;
+impl SBox {
+    /// Inverse permutations of the rows used by `t_reverse`
+    fn inverse(&self) -> Table {
+        let mut result = [[0; 16]; 8];
+        for (row, inverse) in self.table().iter().zip(result.iter_mut()) {
+            for (index, value) in row.iter().enumerate() {
+                inverse[*value as usize] = index as u8;
+            }
+        }
         result
-    }).sum()
+    }
 }
 
-fn from_32(num: u32) -> Vec<u8> {
-    vec![
-        ((num >> 24) & 0xff) as u8,
-        ((num >> 16) & 0xff) as u8,
-        ((num >> 8) & 0xff) as u8,
-        (num & 0xff) as u8
-    ]
+fn substitute(in_data: &[u8], table: &Table) -> [u8; 4] {
+    std::array::from_fn(|i| {
+        let first_part_byte = (in_data[i] & 0xf0) >> 4;
+        let sec_part_byte = in_data[i] & 0x0f;
+        let first_part_byte = lookup(&table[7 - i * 2], first_part_byte as usize);
+        let sec_part_byte = lookup(&table[6 - i * 2], sec_part_byte as usize);
+        (first_part_byte << 4) | sec_part_byte
+    })
 }
 
-fn add_32(left: &[u8], right: &[u8]) -> Vec<u8> {
-    let left_32: u32 = to_32(left);
-    let right_32: u32 = to_32(right);
-    let result_32 = ((left_32 as u64 + right_32 as u64) % 0x100000000u64) as u32;
-    from_32(result_32)
+pub fn t(in_data: &[u8], sbox: &SBox) -> [u8; 4] {
+    substitute(in_data, sbox.table())
 }
 
-fn g(key: &[u8], a: &[u8]) -> Vec<u8> {
-    let internal = add_32(a, key);
-    let internal = t(&internal);
-    let mut result_32 = to_32(&internal);
-    result_32 = (result_32 << 11) | (result_32>>21);
-    from_32(result_32)
+pub fn t_reverse(in_data: &[u8], sbox: &SBox) -> [u8; 4] {
+    substitute(in_data, &sbox.inverse())
 }
 
-fn xor_32(left: &[u8], right: &[u8]) -> Vec<u8> {
-    left.iter().zip(right.iter()).map(|(left, right)| *left ^ *right).collect()
-}
+/// Substitution of every byte of a word merged with the rotation of `g`
+pub(super) type GTable = [[u32; 256]; 4];
 
-fn expand_key(key: &[u8]) -> Vec<&[u8]> {
-    let mut result: Vec<&[u8]> = (0..24).map(|i| {
-        let i1 = modd(i*4, 32);
-        let i2 = modd(i*4+4, 32);
-        let i2 = if i2 == 0 { i2 + 32 } else { i2 };
-        &key[i1..i2]
-    }).collect();
-    result.append(&mut (0..=7).rev().map(
-        |i| &key[i*4..i*4+4]
-    ).collect::<Vec<&[u8]>>());
+pub(super) fn g_table(sbox: &SBox) -> Box<GTable> {
+    let mut result = Box::new([[0; 256]; 4]);
+    for (i, row) in result.iter_mut().enumerate() {
+        for (byte, item) in row.iter_mut().enumerate() {
+            let mut word = [0; 4];
+            word[i] = byte as u8;
+            *item = ((t(&word, sbox)[i] as u32) << (24 - 8 * i)).rotate_left(11);
+        }
+    }
     result
 }
 
-fn feistel_net_node(left: &[u8], right: &[u8], key: &[u8]) -> (Vec<u8>, Vec<u8>) {
-    (right.to_vec(), xor_32(left, &g(right, key)))
+pub(super) fn g(key: u32, a: u32, table: &GTable) -> u32 {
+    a.wrapping_add(key).to_be_bytes().iter().zip(table)
+        .fold(0, |acc, (byte, row)| acc ^ lookup(row, *byte as usize))
+}
+
+fn expand_key(key: &[u8; 32]) -> [u32; 32] {
+    std::array::from_fn(|i| {
+        let i = if i < 24 { i % 8 } else { 31 - i };
+        u32::from_be_bytes(key[i*4..i*4+4].try_into().unwrap())
+    })
 }
 
-fn feistel_net_32(val: &[u8], keys: &[&[u8]]) -> Vec<u8> {
-    let mut left: Vec<u8> = val[0..4].to_vec();
-    let mut right: Vec<u8> = val[4..8].to_vec();
-    let mut key = keys[0..32].iter();
-    (right, left) = loop {
-        (left, right) = match key.next() {
-            Some(key) => feistel_net_node(&left, &right, key),
-            None => break (left, right)
-        };
-    };
-    let mut result = left;
-    result.append(&mut right);
+fn feistel_net_node(left: u32, right: u32, key: u32, table: &GTable) -> (u32, u32) {
+    (right, left ^ g(key, right, table))
+}
+
+fn halves(val: &[u8]) -> (u32, u32) {
+    (u32::from_be_bytes(val[0..4].try_into().unwrap()), u32::from_be_bytes(val[4..8].try_into().unwrap()))
+}
+
+fn join(left: u32, right: u32) -> [u8; 8] {
+    let mut result = [0; 8];
+    result[..4].copy_from_slice(&left.to_be_bytes());
+    result[4..].copy_from_slice(&right.to_be_bytes());
     result
 }
 
-fn proto(phrase: &str, keys: &[&[u8]]) ->Result<String, Box<dyn Error>> {
-    let phrase = hex_to_bytes(phrase, 8)?;
-    let mut result = String::new();
-    for fragment in phrase.windows(8).step_by(8) {
-        let fragment = feistel_net_32(fragment, keys);
-        result.push_str(&bytes_to_hex(&fragment));
+/// Thirty two rounds, the last one without the swap of the halves
+fn feistel_net_32<'a, K>(val: &[u8], keys: K, table: &GTable) -> [u8; 8]
+    where K: IntoIterator<Item = &'a u32>
+{
+    let (left, right) = keys.into_iter()
+        .fold(halves(val), |(left, right), key| feistel_net_node(left, right, *key, table));
+    join(right, left)
+}
+
+/// Sixteen rounds keyed with `K1..K8` twice and without the final swap,
+/// the transformation of the imitovstavka of GOST 28147-89
+fn feistel_net_16(val: &[u8], keys: &[u32], table: &GTable) -> [u8; 8] {
+    let (left, right) = keys[..16].iter()
+        .fold(halves(val), |(left, right), key| feistel_net_node(left, right, *key, table));
+    join(left, right)
+}
+
+/// Magma key with the round keys and the substitution tables prepared once
+#[derive(Clone)]
+pub struct MagmaKey {
+    keys: [u32; 32],
+    sbox: SBox,
+    table: Box<GTable>
+}
+
+impl MagmaKey {
+    pub fn new(key: [u8; 32]) -> Self {
+        MagmaKey::with_sbox(key, SBox::default())
+    }
+
+    /// Key of GOST 28147-89 with another substitution
+    pub fn with_sbox(key: [u8; 32], sbox: SBox) -> Self {
+        MagmaKey::expand(&key, sbox)
+    }
+
+    fn expand(key: &[u8; 32], sbox: SBox) -> Self {
+        MagmaKey { keys: expand_key(key), sbox, table: g_table(&sbox) }
+    }
+
+    pub fn sbox(&self) -> &SBox {
+        &self.sbox
+    }
+
+    pub fn from_hex(key: &str) -> Result<Self, CipherError> {
+        Self::try_from(Secret::<Vec<u8>>::from_hex(key)?.expose().as_slice())
+    }
+
+    pub(super) fn encrypt_16(&self, block: &[u8]) -> [u8; 8] {
+        feistel_net_16(block, &self.keys, &self.table)
     }
-    Ok(result)
 }
 
-pub fn encrypt(phrase: &str, key: &str) -> Result<String, Box<dyn Error>> {
-    let key = hex_to_bytes(key, 32)?;
-    let expanded_keys = expand_key(&key);
-    proto(phrase, &expanded_keys)
+impl fmt::Debug for MagmaKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MagmaKey").field("sbox", &self.sbox).finish_non_exhaustive()
+    }
 }
 
-pub fn decrypt(phrase: &str, key: &str) -> Result<String, Box<dyn Error>> {
-    let key = hex_to_bytes(key, 32)?;
-    let mut expanded_keys = expand_key(&key);
-    expanded_keys.reverse();
-    proto(phrase, &expanded_keys)
+impl From<[u8; 32]> for MagmaKey {
+    fn from(value: [u8; 32]) -> Self {
+        MagmaKey::new(value)
+    }
 }
 
-fn add_xor(left: &[u8], right: &[u8]) -> Vec<u8> {
-    left.iter().zip(right.iter()).map(|(left, right)| *left ^ *right).collect()
+/// Expands the key without copying it out of the `Secret`
+impl From<&Secret<[u8; 32]>> for MagmaKey {
+    fn from(value: &Secret<[u8; 32]>) -> Self {
+        MagmaKey::expand(value.expose(), SBox::default())
+    }
 }
 
-fn inc_ctr(counter: &[u8]) -> Vec<u8> {
-    let mut buffer: usize = 0;
-    let bits: Vec<u8> = vec![0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01];
-    let result: Vec<u8> = counter.iter().rev().zip(bits.iter().rev()).map(|(elem, bit)| {
-        buffer = *elem as usize + *bit as usize + (buffer >> 8);
-        (buffer & 0xff) as u8
-    }).collect();
-    result.iter().rev().copied().collect()
+impl TryFrom<&[u8]> for MagmaKey {
+    type Error = CipherError;
+
+    fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
+        Ok(MagmaKey::from(&Secret::try_from(value)?))
+    }
 }
 
-fn null_len(vec: &[u8]) -> usize {
-    let mut count = 0_usize;
-    for elem in vec.iter().rev() {
-        if *elem == 0 { count += 1; }
-        else { break; }
+impl Drop for MagmaKey {
+    fn drop(&mut self) {
+        self.keys.zeroize();
+    }
+}
+
+impl BlockCipher for MagmaKey {
+    const BLOCK_SIZE: usize = 8;
+
+    fn encrypt_block(&self, block: &mut [u8]) {
+        block.copy_from_slice(&feistel_net_32(block, &self.keys, &self.table));
+    }
+
+    fn decrypt_block(&self, block: &mut [u8]) {
+        block.copy_from_slice(&feistel_net_32(block, self.keys.iter().rev(), &self.table));
     }
-    count
 }
 
+pub fn encrypt_block(block: &[u8; 8], key: &MagmaKey) -> [u8; 8] {
+    feistel_net_32(block, &key.keys, &key.table)
+}
+
+pub fn decrypt_block(block: &[u8; 8], key: &MagmaKey) -> [u8; 8] {
+    feistel_net_32(block, key.keys.iter().rev(), &key.table)
+}
+
+pub fn encrypt_bytes(data: &[u8], key: &MagmaKey) -> Result<Vec<u8>, CipherError> {
+    process_blocks(data, 8, |block| key.encrypt_block(block))
+}
+
+pub fn decrypt_bytes(data: &[u8], key: &MagmaKey) -> Result<Vec<u8>, CipherError> {
+    process_blocks(data, 8, |block| key.decrypt_block(block))
+}
+
+pub fn encrypt_padded(data: &[u8], padding: Padding, key: &MagmaKey) -> Result<Vec<u8>, CipherError> {
+    modes::ecb_encrypt(data, padding, key)
+}
+
+pub fn decrypt_padded(data: &[u8], padding: Padding, key: &MagmaKey) -> Result<Vec<u8>, CipherError> {
+    modes::ecb_decrypt(data, padding, key)
+}
+
+/// Imitovstavka of GOST R 34.13-2015 truncated to `len` bytes
+pub fn mac(data: &[u8], len: usize, key: &MagmaKey) -> Result<Vec<u8>, CipherError> {
+    mac::mac(data, len, key)
+}
+
+/// MGM of RFC 9058, the tag of `tag_len` bytes follows the ciphertext
+pub fn mgm_encrypt(
+    data: &[u8], associated: &[u8], nonce: &[u8; 8], tag_len: usize, key: &MagmaKey
+) -> Result<Vec<u8>, CipherError> {
+    aead::mgm_encrypt(data, associated, nonce, tag_len, key)
+}
+
+pub fn mgm_decrypt(
+    data: &[u8], associated: &[u8], nonce: &[u8; 8], tag_len: usize, key: &MagmaKey
+) -> Result<Vec<u8>, CipherError> {
+    aead::mgm_decrypt(data, associated, nonce, tag_len, key)
+}
+
+/// KExp15 of R 1323565.1.017-2018
+pub fn kexp15(key: &[u8], mac_key: &MagmaKey, enc_key: &MagmaKey, iv: &[u8; 4]) -> Result<Vec<u8>, CipherError> {
+    keywrap::kexp15(key, mac_key, enc_key, iv)
+}
+
+pub fn kimp15(data: &[u8], mac_key: &MagmaKey, enc_key: &MagmaKey, iv: &[u8; 4]) -> Result<Vec<u8>, CipherError> {
+    keywrap::kimp15(data, mac_key, enc_key, iv)
+}
+
+/// The phrase is completed with zeros to the block border
+pub fn encrypt(phrase: &str, key: &str) -> Result<String, CipherError> {
+    encrypt_with_padding(phrase, key, Padding::Zero)
+}
+
+pub fn decrypt(phrase: &str, key: &str) -> Result<String, CipherError> {
+    decrypt_with_padding(phrase, key, Padding::Zero)
+}
+
+pub fn encrypt_with_padding(phrase: &str, key: &str, padding: Padding) -> Result<String, CipherError> {
+    let key = MagmaKey::from(&key_from_hex(key)?);
+    Ok(bytes_to_hex(&encrypt_padded(&hex_to_bytes(phrase, 1)?, padding, &key)?))
+}
+
+pub fn decrypt_with_padding(phrase: &str, key: &str, padding: Padding) -> Result<String, CipherError> {
+    let key = MagmaKey::from(&key_from_hex(key)?);
+    Ok(bytes_to_hex(&decrypt_padded(&hex_to_bytes(phrase, 1)?, padding, &key)?))
+}
+
+/// Gamma of any length, the last block is truncated to the length of `data`
+pub fn ctr_magma_bytes(data: &[u8], init_v: &[u8; 4], key: &MagmaKey) -> Result<Vec<u8>, CipherError> {
+    modes::ctr(data, init_v, 8, key)
+}
 
-pub fn ctr_magma(phrase: &str, init_v: &str, key: &str) -> Result<String, Box<dyn Error>> {
+pub fn ctr_magma(phrase: &str, init_v: &str, key: &str) -> Result<String, CipherError> {
     let init_v = hex_to_bytes(init_v, 4)?;
-    let mut ctr: Vec<u8> = init_v.to_vec();
-    ctr.extend(vec![0x00u8; 4]);
-    let key = hex_to_bytes(key, 32)?;
-    let keys = expand_key(&key);
-    let mut gamma: Vec<u8>;
-    let phrase = hex_to_bytes(phrase, 8)?;
-    let null_count = null_len(&phrase);
-    let mut result_v: Vec<u8> = Vec::new();
-    for part in phrase.windows(8).step_by(8) {
-        gamma = feistel_net_32(&ctr, &keys);
-        ctr = inc_ctr(&ctr);
-        result_v.extend(add_xor(part, &gamma));
-    }
-    Ok(bytes_to_hex(&result_v[0..result_v.len() - null_count]))
+    if init_v.is_empty() { Err(CipherError::EmptyValue { param: "Вектор инициализации" })?; }
+    let key = MagmaKey::from(&key_from_hex(key)?);
+    let result = ctr_magma_bytes(&hex_to_bytes(phrase, 1)?, &key_from_slice(&init_v[..4])?, &key)?;
+    Ok(bytes_to_hex(&result))
+}
+
+fn hex_proto<F>(phrase: &str, func: F) -> Result<String, CipherError>
+    where F: Fn(&[u8]) -> Result<Vec<u8>, CipherError>
+{
+    Ok(bytes_to_hex(&func(&hex_to_bytes(phrase, 1)?)?))
+}
+
+/// Halves of the block after a round of the Feistel network
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Round {
+    pub key: u32,
+    pub left: u32,
+    pub right: u32,
+}
+
+/// Log of the encryption of one block, the halves are not swapped after the last round
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Trace {
+    /// `K1..K32` in the order of the rounds
+    pub round_keys: [u32; 32],
+    pub rounds: Vec<Round>,
+}
+
+impl fmt::Display for Trace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, round) in self.rounds.iter().enumerate() {
+            writeln!(f, "Раунд {}: K = {:08x}, a1 = {:08x}, a0 = {:08x}", i + 1, round.key, round.left, round.right)?;
+        }
+        Ok(())
+    }
+}
+
+/// Encrypts the block round by round
+pub fn encrypt_block_traced(block: &[u8; 8], key: &MagmaKey) -> ([u8; 8], Trace) {
+    let (mut left, mut right) = halves(block);
+    let rounds = key.keys.iter().map(|round_key| {
+        (left, right) = feistel_net_node(left, right, *round_key, &key.table);
+        Round { key: *round_key, left, right }
+    }).collect();
+    (join(right, left), Trace { round_keys: key.keys, rounds })
+}
+
+/// Same as `encrypt` with the log of every block
+pub fn encrypt_traced(phrase: &str, key: &str) -> Result<(String, Vec<Trace>), CipherError> {
+    let key = MagmaKey::from(&key_from_hex(key)?);
+    let data = Padding::Zero.pad(&hex_to_bytes(phrase, 1)?, 8)?;
+    let (result, traces): (Vec<[u8; 8]>, Vec<Trace>) = data.chunks(8)
+        .map(|block| encrypt_block_traced(block.try_into().unwrap(), &key))
+        .unzip();
+    Ok((bytes_to_hex(result.as_flattened()), traces))
+}
+
+pub struct Magma;
+
+impl Cipher for Magma {
+    type Key = MagmaKey;
+
+    fn encrypt(&self, phrase: &str, key: &MagmaKey) -> Result<String, CipherError> {
+        hex_proto(phrase, |data| encrypt_padded(data, Padding::Zero, key))
+    }
+
+    fn decrypt(&self, phrase: &str, key: &MagmaKey) -> Result<String, CipherError> {
+        hex_proto(phrase, |data| decrypt_padded(data, Padding::Zero, key))
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct CtrMagmaKey {
+    pub key: MagmaKey,
+    pub init_v: [u8; 4]
+}
+
+pub struct CtrMagma;
+
+impl Cipher for CtrMagma {
+    type Key = CtrMagmaKey;
+
+    fn encrypt(&self, phrase: &str, key: &CtrMagmaKey) -> Result<String, CipherError> {
+        hex_proto(phrase, |data| ctr_magma_bytes(data, &key.init_v, &key.key))
+    }
+
+    fn decrypt(&self, phrase: &str, key: &CtrMagmaKey) -> Result<String, CipherError> {
+        hex_proto(phrase, |data| ctr_magma_bytes(data, &key.init_v, &key.key))
+    }
 }
 
 #[cfg(test)]
@@ -193,7 +526,7 @@ mod magma_tests {
     fn test_t() {
         let (data, validate) = init_data();
         for (values, results) in data.iter().zip(validate.iter()) {
-            let result = t(values);
+            let result = t(values, &SBox::Tc26Z);
             assert_eq!(*results, result);
         }
     }
@@ -202,7 +535,7 @@ mod magma_tests {
     fn test_t_reverse() {
         let (validate, data) = init_data();
         for (values, results) in data.iter().zip(validate.iter()) {
-            let result = t_reverse(values);
+            let result = t_reverse(values, &SBox::Tc26Z);
             assert_eq!(*results, result);
         }
     }
@@ -214,7 +547,7 @@ mod magma_tests {
         ).unwrap();
         let mut result = String::new();
         for i in (0..bytes.len()).step_by(4) {
-            let buffer: Vec<u8> = t(&bytes[i..i+4]).into_iter().collect();
+            let buffer: Vec<u8> = t(&bytes[i..i+4], &SBox::Tc26Z).into_iter().collect();
             result.push_str(&bytes_to_hex(&buffer));
         }
         assert_eq!(result, "c812e316e83756dc663759dc633758dc63f7eb71c812e31ccebdeb75c814eb7fc81aeb7fe83f70dc6e3754dc633757dc68f7eb76c811e314cebb2bdc623756dc6cf7eb79c817eb72c814eb7ec815eb7cc811e316e357cb6c");
@@ -228,45 +561,32 @@ mod magma_tests {
         ).unwrap();
         let mut data = Vec::new();
         for i in (0..bytes.len()).step_by(4) {
-            let mut buffer: Vec<u8> = t_reverse(&bytes[i..i+4]).into_iter().collect();
+            let mut buffer: Vec<u8> = t_reverse(&bytes[i..i+4], &SBox::Tc26Z).into_iter().collect();
             data.append(&mut buffer);
         }
         let result = bytes_to_string(&data).unwrap();
-        assert_eq!(result, "от одного порченого яблока весь воз загнивает.");
+        assert_eq!(result, "от одного порченого яблока весь воз загнивает.\0\0\0");
     }
 
     #[test]
     fn test_g() {
-        let keys: Vec<Vec<u8>> = vec![
-            vec![ 0x87, 0x65, 0x43, 0x21 ],
-            vec![ 0xfd, 0xcb, 0xc2, 0x0c ],
-            vec![ 0x7e, 0x79, 0x1a, 0x4b ],
-            vec![ 0xc7, 0x65, 0x49, 0xec ]
-        ];
-        let validate: Vec<Vec<u8>> = vec![
-            vec![ 0xfd, 0xcb, 0xc2, 0x0c ],
-            vec![ 0x7e, 0x79, 0x1a, 0x4b ],
-            vec![ 0xc7, 0x65, 0x49, 0xec ],
-            vec![ 0x97, 0x91, 0xc8, 0x49 ]
-        ];
-        let data: Vec<Vec<u8>> = vec![
-            vec![ 0xfe, 0xdc, 0xba, 0x98 ],
-            vec![ 0x87, 0x65, 0x43, 0x21 ],
-            vec![ 0xfd, 0xcb, 0xc2, 0x0c ],
-            vec![ 0x7e, 0x79, 0x1a, 0x4b ],
-        ];
-        for (i , datum) in data.iter().enumerate() {
-            assert_eq!(validate.get(i).unwrap(), &g(keys.get(i).unwrap(), datum));
+        let keys = [0x87654321, 0xfdcbc20c, 0x7e791a4b, 0xc76549ec];
+        let validate = [0xfdcbc20c, 0x7e791a4b, 0xc76549ec, 0x9791c849];
+        let data = [0xfedcba98, 0x87654321, 0xfdcbc20c, 0x7e791a4b];
+        let table = g_table(&SBox::Tc26Z);
+        for (i, datum) in data.iter().enumerate() {
+            assert_eq!(validate[i], g(keys[i], *datum, &table));
         }
     }
 
     #[test]
     fn test_feistel_net_node() {
-        let key: Vec<u8> = vec![ 0x87, 0x65, 0x43, 0x21 ];
-        let left: Vec<u8> = vec![ 0xfe, 0xdc, 0xba, 0x98 ];
-        let right: Vec<u8> = vec![ 0xfd, 0xcb, 0xc2, 0x0c ];
-        let (right_r, left_r) = feistel_net_node(&left, &right, &key);
-        let (r, l) = feistel_net_node(&left_r, &right_r, &key);
+        let key = 0x87654321;
+        let left = 0xfedcba98;
+        let right = 0xfdcbc20c;
+        let table = g_table(&SBox::Tc26Z);
+        let (right_r, left_r) = feistel_net_node(left, right, key, &table);
+        let (r, l) = feistel_net_node(left_r, right_r, key, &table);
         assert_eq!((left, right), (l, r));
     }
 
@@ -278,27 +598,46 @@ mod magma_tests {
             "ffeeddcc", "bbaa9988", "77665544", "33221100", "f0f1f2f3", "f4f5f6f7", "f8f9fafb", "fcfdfeff",
             "fcfdfeff", "f8f9fafb", "f4f5f6f7", "f0f1f2f3", "33221100", "77665544", "bbaa9988", "ffeeddcc",
         ];
-        let key = hex_to_bytes(
+        let key: [u8; 32] = hex_to_bytes(
             "ffeeddccbbaa99887766554433221100f0f1f2f3f4f5f6f7f8f9fafbfcfdfeff",
             32
-        ).unwrap();
+        ).unwrap().try_into().unwrap();
         let mut val = validate.iter();
         for key in expand_key(&key) {
-            assert_eq!(*val.next().unwrap(), bytes_to_hex(key));
+            assert_eq!(*val.next().unwrap(), bytes_to_hex(&key.to_be_bytes()));
         }
     }
 
     #[test]
     fn test_feistel_net_32() {
-        let key = hex_to_bytes(
+        let key: [u8; 32] = hex_to_bytes(
             "ffeeddccbbaa99887766554433221100f0f1f2f3f4f5f6f7f8f9fafbfcfdfeff",
             32
-        ).unwrap();
+        ).unwrap().try_into().unwrap();
         let val = hex_to_bytes("fedcba9876543210", 8).unwrap();
-        let result = bytes_to_hex(&feistel_net_32(&val, &expand_key(&key)));
+        let result = bytes_to_hex(&feistel_net_32(&val, &expand_key(&key), &g_table(&SBox::Tc26Z)));
         assert_eq!(result, "4ee901e5c2d8ca3d");
     }
 
+    #[test]
+    fn test_traced() {
+        let key = MagmaKey::from_hex("ffeeddccbbaa99887766554433221100f0f1f2f3f4f5f6f7f8f9fafbfcfdfeff").unwrap();
+        let block = [0xfe, 0xdc, 0xba, 0x98, 0x76, 0x54, 0x32, 0x10];
+        let (result, trace) = encrypt_block_traced(&block, &key);
+        assert_eq!(result, encrypt_block(&block, &key));
+        assert_eq!(trace.round_keys[0], 0xffeeddcc);
+        assert_eq!(trace.round_keys[31], 0xffeeddcc);
+        assert_eq!(trace.rounds.len(), 32);
+        assert_eq!(trace.rounds[0], Round { key: 0xffeeddcc, left: 0x76543210, right: 0x28da3b14 });
+        assert_eq!(trace.rounds[1], Round { key: 0xbbaa9988, left: 0x28da3b14, right: 0xb14337a5 });
+        assert_eq!(trace.to_string().lines().count(), 32);
+        let phrase = "fedcba9876543210fedcba98";
+        let key = "ffeeddccbbaa99887766554433221100f0f1f2f3f4f5f6f7f8f9fafbfcfdfeff";
+        let (result, traces) = encrypt_traced(phrase, key).unwrap();
+        assert_eq!(result, encrypt(phrase, key).unwrap());
+        assert_eq!(traces.len(), 2);
+    }
+
     #[test]
     fn test_encrypt() {
         let key = "ffeeddccbbaa99887766554433221100f0f1f2f3f4f5f6f7f8f9fafbfcfdfeff";
@@ -332,4 +671,72 @@ mod magma_tests {
         let result = ctr_magma(&phrase, &iv, &key).unwrap();
         assert_eq!(result, valid);
     }
+
+    #[test]
+    fn test_bytes() {
+        let key = MagmaKey::from_hex("ffeeddccbbaa99887766554433221100f0f1f2f3f4f5f6f7f8f9fafbfcfdfeff").unwrap();
+        let data = hex_to_bytes("fedcba9876543210fedcba9876543210", 1).unwrap();
+        let result = encrypt_bytes(&data, &key).unwrap();
+        assert_eq!(bytes_to_hex(&result), "4ee901e5c2d8ca3d4ee901e5c2d8ca3d");
+        assert_eq!(decrypt_bytes(&result, &key).unwrap(), data);
+        let block = [0xfe, 0xdc, 0xba, 0x98, 0x76, 0x54, 0x32, 0x10];
+        assert_eq!(decrypt_block(&encrypt_block(&block, &key), &key), block);
+        assert!(encrypt_bytes(&data[1..], &key).is_err());
+    }
+
+    #[test]
+    fn test_key() {
+        assert!(MagmaKey::from_hex("ffee").is_err());
+        assert!(MagmaKey::try_from([0u8; 31].as_slice()).is_err());
+        assert!(matches!(encrypt("fedcba9876543210", ""), Err(CipherError::EmptyValue { param: "Ключ" })));
+    }
+
+    #[test]
+    fn test_ctr_bytes() {
+        let key = MagmaKey::from_hex("ffeeddccbbaa99887766554433221100f0f1f2f3f4f5f6f7f8f9fafbfcfdfeff").unwrap();
+        let data = hex_to_bytes("92def06b3c130a59db54c7", 1).unwrap();
+        let result = ctr_magma_bytes(&data, &[0x12, 0x34, 0x56, 0x78], &key).unwrap();
+        assert_eq!(bytes_to_hex(&result), "4e98110c97b7b93c3e250d");
+        let key = "ffeeddccbbaa99887766554433221100f0f1f2f3f4f5f6f7f8f9fafbfcfdfeff";
+        assert_eq!(ctr_magma("92def06b3c130a5900", "12345678", key).unwrap(), "4e98110c97b7b93ce5");
+    }
+
+    #[test]
+    fn test_mac() {
+        let key = MagmaKey::from_hex("ffeeddccbbaa99887766554433221100f0f1f2f3f4f5f6f7f8f9fafbfcfdfeff").unwrap();
+        let data = hex_to_bytes("92def06b3c130a59db54c704f8189d204a98fb2e67a8024c8912409b17b57e41", 1).unwrap();
+        assert_eq!(bytes_to_hex(&mac(&data, 4, &key).unwrap()), "154e7210");
+        assert_eq!(bytes_to_hex(&mac(&data, 8, &key).unwrap()), "154e72102030c5bb");
+    }
+
+    #[test]
+    fn test_sbox() {
+        let key: [u8; 32] = hex_to_bytes("ffeeddccbbaa99887766554433221100f0f1f2f3f4f5f6f7f8f9fafbfcfdfeff", 1)
+            .unwrap().try_into().unwrap();
+        let block = [0xfe, 0xdc, 0xba, 0x98, 0x76, 0x54, 0x32, 0x10];
+        // Outputs of libgcrypt for the same key and block in the byte order of GOST 28147-89
+        let vectors = [
+            "4ee901e5c2d8ca3d", "c7da9dd6085f3881", "d2c58a3a9b036abd", "cd222ca34cb08341",
+            "d71be8ef528045a1", "ccd2af5d6eaac242", "26c998e5562506d6",
+        ];
+        for (sbox, valid) in SBox::NAMED.into_iter().zip(vectors) {
+            let key = MagmaKey::with_sbox(key, sbox);
+            let result = encrypt_block(&block, &key);
+            assert_eq!(bytes_to_hex(&result), valid, "{}", sbox.name());
+            assert_eq!(decrypt_block(&result, &key), block);
+            assert_eq!(t_reverse(&t(&block, &sbox), &sbox), block[..4]);
+        }
+    }
+
+    #[test]
+    fn test_custom_sbox() {
+        let sbox = SBox::custom(TC26_Z).unwrap();
+        assert_eq!(sbox.table(), SBox::Tc26Z.table());
+        assert_eq!(t(&[0xfd, 0xb9, 0x75, 0x31], &sbox), [0x2a, 0x19, 0x6f, 0x34]);
+        let mut table = TC26_Z;
+        table[3][0] = table[3][1];
+        assert!(matches!(SBox::custom(table), Err(CipherError::InvalidKey { param: "S-блок", .. })));
+        table[3][0] = 16;
+        assert!(SBox::custom(table).is_err());
+    }
 }
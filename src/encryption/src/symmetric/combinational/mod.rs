@@ -1,3 +1,102 @@
 pub mod magma;
 pub mod aes;
+pub mod aead;
+pub mod ct;
+pub mod des;
+pub mod feistel;
+pub mod gost28147;
+pub mod keywrap;
 pub mod kuznechik;
+pub mod mac;
+pub mod modes;
+pub mod padding;
+pub mod stream;
+pub mod triple_des;
+
+use crate::errors::CipherError;
+use crate::methods::hex_to_bytes;
+use crate::secret::Secret;
+
+/// Block transformation of a cipher with an expanded key,
+/// used by the modes of operation
+pub trait BlockCipher {
+    /// Block size in bytes
+    const BLOCK_SIZE: usize;
+
+    /// Transforms `block` in place, it must be exactly `BLOCK_SIZE` bytes long
+    fn encrypt_block(&self, block: &mut [u8]);
+
+    fn decrypt_block(&self, block: &mut [u8]);
+}
+
+/// Applies `func` in place to every block of a copy of `data`,
+/// the length of `data` must be a multiple of `size`
+fn process_blocks<F>(data: &[u8], size: usize, mut func: F) -> Result<Vec<u8>, CipherError>
+    where F: FnMut(&mut [u8])
+{
+    if !data.len().is_multiple_of(size) {
+        Err(CipherError::invalid_size(
+            "Данные", &format!("Длина данных должна быть кратна {size} байтам")
+        ))?;
+    }
+    let mut result = data.to_vec();
+    for block in result.chunks_mut(size) {
+        func(block);
+    }
+    Ok(result)
+}
+
+fn key_from_slice<const N: usize>(key: &[u8]) -> Result<[u8; N], CipherError> {
+    key.try_into().map_err(|_| CipherError::invalid_size(
+        "Ключ", &format!("Ключ должен состоять из {N} байт")
+    ))
+}
+
+/// Parses a hex key the way the string functions always did:
+/// a short key is padded with zeros and a long one is truncated
+fn key_from_hex<const N: usize>(key: &str) -> Result<Secret<[u8; N]>, CipherError> {
+    let key = Secret::new(hex_to_bytes(key, N)?);
+    if key.expose().is_empty() { Err(CipherError::EmptyValue { param: "Ключ" })?; }
+    Secret::try_from(&key.expose()[..N])
+}
+
+/// Substitution through a table indexed by secret data
+#[cfg(not(feature = "constant-time"))]
+fn lookup<T: Copy>(table: &[T], index: usize) -> T {
+    table[index]
+}
+
+#[cfg(feature = "constant-time")]
+use ct::lookup;
+
+/// Multiplication modulo `x^8 + poly`
+#[cfg(not(feature = "constant-time"))]
+fn gf_mul(mut left: u8, mut right: u8, poly: u8) -> u8 {
+    let mut result: u8 = 0;
+    let mut hi_bit: u8;
+    for _ in 0..8 {
+        if right & 1 != 0 { result ^= left }
+        hi_bit = left & 0x80;
+        left <<= 1;
+        if hi_bit != 0 { left ^= poly }
+        right >>= 1;
+    }
+    result
+}
+
+#[cfg(feature = "constant-time")]
+use ct::gf_mul;
+
+/// Xors `value` into the common prefix of `target`
+fn xor(target: &mut [u8], value: &[u8]) {
+    target.iter_mut().zip(value).for_each(|(target, value)| *target ^= value);
+}
+
+/// Increments the big-endian counter, returns `true` when it wraps around to zero
+fn increment(counter: &mut [u8]) -> bool {
+    for byte in counter.iter_mut().rev() {
+        *byte = byte.wrapping_add(1);
+        if *byte != 0 { return false; }
+    }
+    true
+}
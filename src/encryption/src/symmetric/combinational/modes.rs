@@ -0,0 +1,293 @@
+//! Modes of operation of GOST R 34.13-2015 for any `BlockCipher`.
+//!
+//! Sizes are given in bytes: `n` is the block size of the cipher, `iv` is the
+//! initial value of the register and `s` is the width of the gamma.
+
+use crate::errors::CipherError;
+use super::{increment, process_blocks, xor, BlockCipher};
+use super::padding::Padding;
+
+/// Simple replacement, `padding` is added before encryption
+pub fn ecb_encrypt<C: BlockCipher>(data: &[u8], padding: Padding, key: &C) -> Result<Vec<u8>, CipherError> {
+    let data = padding.pad(data, C::BLOCK_SIZE)?;
+    process_blocks(&data, C::BLOCK_SIZE, |block| key.encrypt_block(block))
+}
+
+/// `padding` is removed after decryption
+pub fn ecb_decrypt<C: BlockCipher>(data: &[u8], padding: Padding, key: &C) -> Result<Vec<u8>, CipherError> {
+    let result = process_blocks(data, C::BLOCK_SIZE, |block| key.decrypt_block(block))?;
+    padding.unpad(&result, C::BLOCK_SIZE)
+}
+
+/// Gamming, the counter starts with `iv` of `n / 2` bytes followed by zeros.
+/// Encryption and decryption are the same, the last block may be incomplete
+pub fn ctr<C: BlockCipher>(data: &[u8], iv: &[u8], s: usize, key: &C) -> Result<Vec<u8>, CipherError> {
+    let n = C::BLOCK_SIZE;
+    if iv.len() != n / 2 {
+        Err(CipherError::invalid_size(
+            "Вектор инициализации", &format!("Вектор инициализации должен состоять из {} байт", n / 2)
+        ))?;
+    }
+    let mut counter = iv.to_vec();
+    counter.resize(n, 0);
+    ctr_from(data, &counter, s, key)
+}
+
+/// Gamming starting from the whole block `counter`, the counter is incremented
+/// modulo `2^(8n)` and is never allowed to wrap around
+pub fn ctr_from<C: BlockCipher>(data: &[u8], counter: &[u8], s: usize, key: &C) -> Result<Vec<u8>, CipherError> {
+    let n = C::BLOCK_SIZE;
+    check_width(s, n)?;
+    if counter.len() != n {
+        Err(CipherError::invalid_size("Счетчик", &format!("Счетчик должен состоять из {n} байт")))?;
+    }
+    let mut counter = counter.to_vec();
+    let mut gamma = vec![0; n];
+    let mut result = data.to_vec();
+    for (index, part) in result.chunks_mut(s).enumerate() {
+        if index > 0 && increment(&mut counter) { Err(CipherError::CounterOverflow)?; }
+        gamma.copy_from_slice(&counter);
+        key.encrypt_block(&mut gamma);
+        xor(part, &gamma);
+    }
+    Ok(result)
+}
+
+/// Output feedback, `iv` takes a multiple of `n` bytes.
+/// Encryption and decryption are the same, the last block may be incomplete
+pub fn ofb<C: BlockCipher>(data: &[u8], iv: &[u8], s: usize, key: &C) -> Result<Vec<u8>, CipherError> {
+    let n = C::BLOCK_SIZE;
+    check_width(s, n)?;
+    check_register(iv, n, true)?;
+    let mut register = iv.to_vec();
+    let mut gamma = vec![0; n];
+    let mut result = data.to_vec();
+    for part in result.chunks_mut(s) {
+        gamma.copy_from_slice(&register[..n]);
+        key.encrypt_block(&mut gamma);
+        xor(part, &gamma);
+        shift(&mut register, &gamma);
+    }
+    Ok(result)
+}
+
+/// Cipher block chaining, `iv` takes a multiple of `n` bytes.
+/// `padding` is added before encryption and removed after decryption
+pub fn cbc_encrypt<C: BlockCipher>(data: &[u8], iv: &[u8], padding: Padding, key: &C) -> Result<Vec<u8>, CipherError> {
+    let n = C::BLOCK_SIZE;
+    check_register(iv, n, true)?;
+    let mut register = iv.to_vec();
+    process_blocks(&padding.pad(data, n)?, n, |block| {
+        xor(block, &register);
+        key.encrypt_block(block);
+        shift(&mut register, block);
+    })
+}
+
+pub fn cbc_decrypt<C: BlockCipher>(data: &[u8], iv: &[u8], padding: Padding, key: &C) -> Result<Vec<u8>, CipherError> {
+    let n = C::BLOCK_SIZE;
+    check_register(iv, n, true)?;
+    let mut register = iv.to_vec();
+    let mut cipher = vec![0; n];
+    let result = process_blocks(data, n, |block| {
+        cipher.copy_from_slice(block);
+        key.decrypt_block(block);
+        xor(block, &register);
+        shift(&mut register, &cipher);
+    })?;
+    padding.unpad(&result, n)
+}
+
+/// Cipher feedback, `iv` takes at least `n` bytes, the last block may be incomplete
+pub fn cfb_encrypt<C: BlockCipher>(data: &[u8], iv: &[u8], s: usize, key: &C) -> Result<Vec<u8>, CipherError> {
+    cfb(data, iv, s, key, false)
+}
+
+pub fn cfb_decrypt<C: BlockCipher>(data: &[u8], iv: &[u8], s: usize, key: &C) -> Result<Vec<u8>, CipherError> {
+    cfb(data, iv, s, key, true)
+}
+
+fn cfb<C: BlockCipher>(data: &[u8], iv: &[u8], s: usize, key: &C, decrypt: bool) -> Result<Vec<u8>, CipherError> {
+    let n = C::BLOCK_SIZE;
+    check_width(s, n)?;
+    check_register(iv, n, false)?;
+    let mut register = iv.to_vec();
+    let mut gamma = vec![0; n];
+    let mut result = data.to_vec();
+    for part in result.chunks_mut(s) {
+        gamma.copy_from_slice(&register[..n]);
+        key.encrypt_block(&mut gamma);
+        if decrypt { shift(&mut register, part); }
+        xor(part, &gamma);
+        if !decrypt { shift(&mut register, part); }
+    }
+    Ok(result)
+}
+
+pub(super) fn check_width(s: usize, n: usize) -> Result<(), CipherError> {
+    if s == 0 || s > n {
+        Err(CipherError::invalid_size("Гамма", &format!("Ширина гаммы должна быть от 1 до {n} байт")))?;
+    }
+    Ok(())
+}
+
+pub(super) fn check_register(iv: &[u8], n: usize, multiple: bool) -> Result<(), CipherError> {
+    if iv.len() < n || multiple && !iv.len().is_multiple_of(n) {
+        let reason = if multiple { "кратна" } else { "не меньше" };
+        Err(CipherError::invalid_size(
+            "Вектор инициализации", &format!("Длина вектора инициализации должна быть {reason} {n} байтам")
+        ))?;
+    }
+    Ok(())
+}
+
+/// Drops the first `value.len()` bytes of the register and appends `value`
+pub(super) fn shift(register: &mut Vec<u8>, value: &[u8]) {
+    register.drain(..value.len());
+    register.extend_from_slice(value);
+}
+
+#[cfg(test)]
+mod modes_tests {
+    use crate::aes::AesKey;
+    use crate::kuznechik::KuznechikKey;
+    use crate::magma::MagmaKey;
+    use super::*;
+
+    const KUZNECHIK_KEY: &str = "8899aabbccddeeff0011223344556677fedcba98765432100123456789abcdef";
+    const KUZNECHIK_TEXT: &str = "1122334455667700ffeeddccbbaa998800112233445566778899aabbcceeff0a\
+                                  112233445566778899aabbcceeff0a002233445566778899aabbcceeff0a0011";
+    const KUZNECHIK_IV: &str = "1234567890abcef0a1b2c3d4e5f0011223344556677889901213141516171819";
+    const MAGMA_KEY: &str = "ffeeddccbbaa99887766554433221100f0f1f2f3f4f5f6f7f8f9fafbfcfdfeff";
+    const MAGMA_TEXT: &str = "92def06b3c130a59db54c704f8189d204a98fb2e67a8024c8912409b17b57e41";
+    const MAGMA_IV: &str = "1234567890abcdef234567890abcdef1";
+
+    fn bytes(value: &str) -> Vec<u8> {
+        hex::decode(value).unwrap()
+    }
+
+    #[test]
+    fn test_ecb() {
+        let key = KuznechikKey::from_hex(KUZNECHIK_KEY).unwrap();
+        let result = ecb_encrypt(&bytes(KUZNECHIK_TEXT), Padding::None, &key).unwrap();
+        assert_eq!(hex::encode(&result), "7f679d90bebc24305a468d42b9d4edcdb429912c6e0032f9285452d76718d08b\
+                                          f0ca33549d247ceef3f5a5313bd4b157d0b09ccde830b9eb3a02c4c5aa8ada98");
+        assert_eq!(ecb_decrypt(&result, Padding::None, &key).unwrap(), bytes(KUZNECHIK_TEXT));
+        let key = MagmaKey::from_hex(MAGMA_KEY).unwrap();
+        let result = ecb_encrypt(&bytes(MAGMA_TEXT), Padding::None, &key).unwrap();
+        assert_eq!(hex::encode(&result), "2b073f0494f372a0de70e715d3556e4811d8d9e9eacfbc1e7c68260996c67efb");
+        assert_eq!(ecb_decrypt(&result, Padding::None, &key).unwrap(), bytes(MAGMA_TEXT));
+    }
+
+    #[test]
+    fn test_ctr() {
+        let key = KuznechikKey::from_hex(KUZNECHIK_KEY).unwrap();
+        let result = ctr(&bytes(KUZNECHIK_TEXT), &bytes("1234567890abcef0"), 16, &key).unwrap();
+        assert_eq!(hex::encode(&result), "f195d8bec10ed1dbd57b5fa240bda1b885eee733f6a13e5df33ce4b33c45dee4\
+                                          a5eae88be6356ed3d5e877f13564a3a5cb91fab1f20cbab6d1c6d15820bdba73");
+        let key = MagmaKey::from_hex(MAGMA_KEY).unwrap();
+        let result = ctr(&bytes(MAGMA_TEXT), &bytes("12345678"), 8, &key).unwrap();
+        assert_eq!(hex::encode(&result), "4e98110c97b7b93c3e250d93d6e85d69136d868807b2dbef568eb680ab52a12d");
+        assert_eq!(ctr(&result, &bytes("12345678"), 8, &key).unwrap(), bytes(MAGMA_TEXT));
+        assert_eq!(hex::encode(ctr(&bytes("92def06b3c"), &bytes("12345678"), 8, &key).unwrap()), "4e98110c97");
+    }
+
+    #[test]
+    fn test_ctr_from() {
+        let key = AesKey::from_hex("2b7e151628aed2a6abf7158809cf4f3c").unwrap();
+        let data = bytes("6bc1bee22e409f96e93d7e117393172aae2d8a571e03ac9c9eb76fac45af8e51");
+        let counter = bytes("f0f1f2f3f4f5f6f7f8f9fafbfcfdfeff");
+        let result = ctr_from(&data, &counter, 16, &key).unwrap();
+        assert_eq!(hex::encode(&result), "874d6191b620e3261bef6864990db6ce9806f66b7970fdff8617187bb9fffdff");
+        let counter = [0xff; 16];
+        assert!(ctr_from(&data[..16], &counter, 16, &key).is_ok());
+        assert_eq!(ctr_from(&data, &counter, 16, &key), Err(CipherError::CounterOverflow));
+        assert!(matches!(ctr_from(&data, &counter[1..], 16, &key), Err(CipherError::InvalidSize { param: "Счетчик", .. })));
+    }
+
+    #[test]
+    fn test_ofb() {
+        let key = KuznechikKey::from_hex(KUZNECHIK_KEY).unwrap();
+        let result = ofb(&bytes(KUZNECHIK_TEXT), &bytes(KUZNECHIK_IV), 16, &key).unwrap();
+        assert_eq!(hex::encode(&result), "81800a59b1842b24ff1f795e897abd95ed5b47a7048cfab48fb521369d9326bf\
+                                          66a257ac3ca0b8b1c80fe7fc10288a13203ebbc066138660a0292243f6903150");
+        let key = MagmaKey::from_hex(MAGMA_KEY).unwrap();
+        let result = ofb(&bytes(MAGMA_TEXT), &bytes(MAGMA_IV), 8, &key).unwrap();
+        assert_eq!(hex::encode(&result), "db37e0e266903c830d46644c1f9a089ca0f83062430e327ec824efb8bd4fdb05");
+        assert_eq!(ofb(&result, &bytes(MAGMA_IV), 8, &key).unwrap(), bytes(MAGMA_TEXT));
+    }
+
+    #[test]
+    fn test_cbc() {
+        let key = KuznechikKey::from_hex(KUZNECHIK_KEY).unwrap();
+        let result = cbc_encrypt(&bytes(KUZNECHIK_TEXT), &bytes(KUZNECHIK_IV), Padding::None, &key).unwrap();
+        assert_eq!(hex::encode(&result), "689972d4a085fa4d90e52e3d6d7dcc272826e661b478eca6af1e8e448d5ea5ac\
+                                          fe7babf1e91999e85640e8b0f49d90d0167688065a895c631a2d9a1560b63970");
+        assert_eq!(cbc_decrypt(&result, &bytes(KUZNECHIK_IV), Padding::None, &key).unwrap(), bytes(KUZNECHIK_TEXT));
+        let key = MagmaKey::from_hex(MAGMA_KEY).unwrap();
+        let iv = bytes("1234567890abcdef234567890abcdef134567890abcdef12");
+        let result = cbc_encrypt(&bytes(MAGMA_TEXT), &iv, Padding::None, &key).unwrap();
+        assert_eq!(hex::encode(&result), "96d1b05eea683919aff76129abb937b95058b4a1c4bc001920b78b1a7cd7e667");
+        assert_eq!(cbc_decrypt(&result, &iv, Padding::None, &key).unwrap(), bytes(MAGMA_TEXT));
+    }
+
+    #[test]
+    fn test_cfb() {
+        let key = KuznechikKey::from_hex(KUZNECHIK_KEY).unwrap();
+        let result = cfb_encrypt(&bytes(KUZNECHIK_TEXT), &bytes(KUZNECHIK_IV), 16, &key).unwrap();
+        assert_eq!(hex::encode(&result), "81800a59b1842b24ff1f795e897abd95ed5b47a7048cfab48fb521369d9326bf\
+                                          79f2a8eb5cc68d38842d264e97a238b54ffebecd4e922de6c75bd9dd44fbf4d1");
+        assert_eq!(cfb_decrypt(&result, &bytes(KUZNECHIK_IV), 16, &key).unwrap(), bytes(KUZNECHIK_TEXT));
+        let key = MagmaKey::from_hex(MAGMA_KEY).unwrap();
+        let result = cfb_encrypt(&bytes(MAGMA_TEXT), &bytes(MAGMA_IV), 8, &key).unwrap();
+        assert_eq!(hex::encode(&result), "db37e0e266903c830d46644c1f9a089c24bdd2035315d38bbcc0321421075505");
+        assert_eq!(cfb_decrypt(&result, &bytes(MAGMA_IV), 8, &key).unwrap(), bytes(MAGMA_TEXT));
+    }
+
+    #[test]
+    fn test_padding() {
+        let key = MagmaKey::from_hex(MAGMA_KEY).unwrap();
+        let data = bytes("92def06b3c130a59db54c704f8189d00");
+        let result = ecb_encrypt(&data, Padding::Pkcs7, &key).unwrap();
+        assert_eq!(hex::encode(&result[..8]), "2b073f0494f372a0");
+        assert_eq!(result.len(), 24);
+        assert_eq!(ecb_decrypt(&result, Padding::Pkcs7, &key).unwrap(), data);
+        let iv = bytes(MAGMA_IV);
+        let result = cbc_encrypt(&data[..13], &iv, Padding::Iso7816, &key).unwrap();
+        assert_eq!(result.len(), 16);
+        assert_eq!(cbc_decrypt(&result, &iv, Padding::Iso7816, &key).unwrap(), data[..13]);
+        let result = cbc_encrypt(&data[..13], &iv, Padding::Procedure3, &key).unwrap();
+        assert_eq!(result.len(), 16);
+        assert_eq!(cbc_decrypt(&result, &iv, Padding::Procedure3, &key).unwrap(), [&data[..13], &[0x80, 0, 0]].concat());
+        assert_eq!(cbc_encrypt(&data, &iv, Padding::Procedure3, &key).unwrap().len(), data.len());
+        assert!(ecb_encrypt(&data[..13], Padding::None, &key).is_err());
+        let result = ecb_encrypt(&data[..13], Padding::Zero, &key).unwrap();
+        assert_eq!(ecb_decrypt(&result, Padding::Zero, &key).unwrap(), [&data[..13], &[0; 3]].concat());
+        assert_eq!(ecb_decrypt(&result, Padding::Pkcs7, &key), Err(CipherError::InvalidPadding));
+    }
+
+    #[test]
+    fn test_short_gamma() {
+        let key = AesKey::from_hex("2b7e151628aed2a6abf7158809cf4f3c").unwrap();
+        let data = bytes("6bc1bee22e409f96e93d7e117393172aae2d8a");
+        let iv = bytes("000102030405060708090a0b0c0d0e0f");
+        let result = cfb_encrypt(&data, &iv, 1, &key).unwrap();
+        assert_eq!(hex::encode(&result[..2]), "3b79");
+        assert_eq!(cfb_decrypt(&result, &iv, 1, &key).unwrap(), data);
+        let result = ofb(&data, &iv, 3, &key).unwrap();
+        assert_eq!(ofb(&result, &iv, 3, &key).unwrap(), data);
+        let result = ctr(&data, &iv[..8], 5, &key).unwrap();
+        assert_eq!(ctr(&result, &iv[..8], 5, &key).unwrap(), data);
+    }
+
+    #[test]
+    fn test_errors() {
+        let key = MagmaKey::from_hex(MAGMA_KEY).unwrap();
+        let data = bytes(MAGMA_TEXT);
+        assert!(matches!(ctr(&data, &bytes("1234"), 8, &key), Err(CipherError::InvalidSize { param: "Вектор инициализации", .. })));
+        assert!(matches!(ctr(&data, &bytes("12345678"), 9, &key), Err(CipherError::InvalidSize { param: "Гамма", .. })));
+        assert!(matches!(ofb(&data, &bytes("1234567890abcdef12"), 8, &key), Err(CipherError::InvalidSize { .. })));
+        assert!(cfb_encrypt(&data, &bytes("1234567890abcdef12"), 8, &key).is_ok());
+        assert!(cbc_encrypt(&data[..7], &bytes(MAGMA_IV), Padding::None, &key).is_err());
+    }
+}
@@ -0,0 +1,128 @@
+use crate::errors::CipherError;
+
+/// Completion of the data to a multiple of the block size
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Padding {
+    /// Length must already be a multiple of the block size
+    None,
+    /// Zeros up to the block border (GOST R 34.13-2015 procedure 1),
+    /// can not be removed and is kept by `unpad`
+    Zero,
+    /// Every added byte holds the number of added bytes, a full block is added
+    /// to data of the exact length
+    Pkcs7,
+    /// Byte `0x80` followed by zeros, always added
+    /// (ISO/IEC 7816-4, GOST R 34.13-2015 procedure 2)
+    Iso7816,
+    /// Same as `Iso7816`, but only an incomplete last block is completed
+    /// (GOST R 34.13-2015 procedure 3), can not be removed and is kept by `unpad`
+    Procedure3,
+}
+
+impl Padding {
+    pub fn pad(&self, data: &[u8], size: usize) -> Result<Vec<u8>, CipherError> {
+        check_size(size)?;
+        let rest = size - data.len() % size;
+        let mut result = data.to_vec();
+        match self {
+            Padding::None => check_length(data.len(), size)?,
+            Padding::Zero => if rest != size { result.resize(data.len() + rest, 0); },
+            Padding::Pkcs7 => result.resize(data.len() + rest, rest as u8),
+            Padding::Iso7816 => iso_7816(&mut result, rest),
+            Padding::Procedure3 => if rest != size { iso_7816(&mut result, rest); },
+        }
+        Ok(result)
+    }
+
+    /// Removes the padding from decrypted data
+    pub fn unpad(&self, data: &[u8], size: usize) -> Result<Vec<u8>, CipherError> {
+        check_size(size)?;
+        check_length(data.len(), size)?;
+        let len = match self {
+            Padding::None | Padding::Zero | Padding::Procedure3 => data.len(),
+            Padding::Pkcs7 => {
+                let count = *data.last().ok_or(CipherError::InvalidPadding)? as usize;
+                if count == 0 || count > size || data[data.len() - count..].iter().any(|byte| *byte as usize != count) {
+                    Err(CipherError::InvalidPadding)?;
+                }
+                data.len() - count
+            },
+            Padding::Iso7816 => iso_7816_len(data, size).ok_or(CipherError::InvalidPadding)?,
+        };
+        Ok(data[..len].to_vec())
+    }
+}
+
+fn iso_7816(data: &mut Vec<u8>, rest: usize) {
+    data.push(0x80);
+    data.resize(data.len() + rest - 1, 0);
+}
+
+/// Length of the data without `0x80 0x00..` found in the last block
+fn iso_7816_len(data: &[u8], size: usize) -> Option<usize> {
+    let start = data.len().checked_sub(size)?;
+    let position = data[start..].iter().rposition(|byte| *byte != 0)?;
+    (data[start + position] == 0x80).then_some(start + position)
+}
+
+fn check_size(size: usize) -> Result<(), CipherError> {
+    if size == 0 {
+        Err(CipherError::invalid_size("Размер блока", "Размер блока должен быть больше нуля"))?;
+    }
+    Ok(())
+}
+
+fn check_length(len: usize, size: usize) -> Result<(), CipherError> {
+    if !len.is_multiple_of(size) {
+        Err(CipherError::invalid_size(
+            "Данные", &format!("Длина данных должна быть кратна {size} байтам")
+        ))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod padding_tests {
+    use super::*;
+
+    #[test]
+    fn test_pad() {
+        let data = [0x11, 0x22, 0x33];
+        assert_eq!(Padding::Zero.pad(&data, 4).unwrap(), [0x11, 0x22, 0x33, 0x00]);
+        assert_eq!(Padding::Pkcs7.pad(&data, 4).unwrap(), [0x11, 0x22, 0x33, 0x01]);
+        assert_eq!(Padding::Pkcs7.pad(&data[..2], 2).unwrap(), [0x11, 0x22, 0x02, 0x02]);
+        assert_eq!(Padding::Iso7816.pad(&data[..1], 4).unwrap(), [0x11, 0x80, 0x00, 0x00]);
+        assert_eq!(Padding::Iso7816.pad(&data[..2], 2).unwrap(), [0x11, 0x22, 0x80, 0x00]);
+        assert_eq!(Padding::Procedure3.pad(&data[..2], 2).unwrap(), [0x11, 0x22]);
+        assert_eq!(Padding::Procedure3.pad(&data, 2).unwrap(), [0x11, 0x22, 0x33, 0x80]);
+        assert_eq!(Padding::None.pad(&data[..2], 2).unwrap(), [0x11, 0x22]);
+        assert!(matches!(Padding::None.pad(&data, 2), Err(CipherError::InvalidSize { .. })));
+        assert!(matches!(Padding::Pkcs7.pad(&data, 0), Err(CipherError::InvalidSize { .. })));
+        assert!(matches!(Padding::Iso7816.unpad(&data, 0), Err(CipherError::InvalidSize { .. })));
+    }
+
+    #[test]
+    fn test_unpad() {
+        let data = [0x11, 0x80, 0x00, 0x22, 0x00];
+        for padding in [Padding::Pkcs7, Padding::Iso7816] {
+            for len in 0..=data.len() {
+                let padded = padding.pad(&data[..len], 4).unwrap();
+                assert_eq!(padding.unpad(&padded, 4).unwrap(), data[..len], "{padding:?}");
+            }
+        }
+        assert_eq!(Padding::Zero.unpad(&[0x11, 0x00], 2).unwrap(), [0x11, 0x00]);
+        assert_eq!(Padding::Procedure3.unpad(&[0x11, 0x00], 2).unwrap(), [0x11, 0x00]);
+        assert_eq!(Padding::Procedure3.unpad(&[0x80, 0x00], 2).unwrap(), [0x80, 0x00]);
+    }
+
+    #[test]
+    fn test_unpad_errors() {
+        assert_eq!(Padding::Pkcs7.unpad(&[0x11, 0x03], 2), Err(CipherError::InvalidPadding));
+        assert_eq!(Padding::Pkcs7.unpad(&[0x11, 0x00], 2), Err(CipherError::InvalidPadding));
+        assert_eq!(Padding::Pkcs7.unpad(&[0x01, 0x02], 2), Err(CipherError::InvalidPadding));
+        assert_eq!(Padding::Pkcs7.unpad(&[0x02, 0x02], 2).unwrap(), [0u8; 0]);
+        assert_eq!(Padding::Iso7816.unpad(&[0x11, 0x00], 2), Err(CipherError::InvalidPadding));
+        assert_eq!(Padding::Iso7816.unpad(&[], 2), Err(CipherError::InvalidPadding));
+        assert!(Padding::Pkcs7.unpad(&[0x01], 2).is_err());
+    }
+}
@@ -0,0 +1,403 @@
+//! Encryption of data of any size in constant memory: `StreamReader` and
+//! `StreamWriter` wrap a reader or a writer and pass the data through a
+//! `Transform` chunk by chunk.
+//!
+//! `BlockStream` gives the same result as the functions of `modes` for the
+//! whole message, `Gamma` xors the data with the gamma of a stream cipher
+//! such as `a5_1::keystream`.
+
+use std::io::{self, Read, Write};
+use crate::errors::CipherError;
+use super::padding::Padding;
+use super::{increment, modes, xor, BlockCipher};
+
+const CHUNK_SIZE: usize = 8192;
+
+/// Processing of a stream split into chunks of any length
+pub trait Transform {
+    /// Appends the processed part of `data` to `out`, an incomplete block is kept until the next call
+    fn update(&mut self, data: &[u8], out: &mut Vec<u8>) -> Result<(), CipherError>;
+
+    /// Processes the rest of the stream, the transform can not be used afterwards
+    fn finish(&mut self, out: &mut Vec<u8>) -> Result<(), CipherError>;
+}
+
+/// Mode of operation with its initial value, the parameters are the same as in `modes`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Mode {
+    Ecb,
+    /// `iv` takes a multiple of the block size
+    Cbc { iv: Vec<u8> },
+    /// `iv` is half of the block, `s` bytes of every gamma block are used
+    Ctr { iv: Vec<u8>, s: usize },
+    /// `iv` takes a multiple of the block size
+    Ofb { iv: Vec<u8>, s: usize },
+    /// `iv` takes at least the block size
+    Cfb { iv: Vec<u8>, s: usize },
+}
+
+/// Block cipher in one of the modes, the padding is used by ECB and CBC only
+pub struct BlockStream<C: BlockCipher> {
+    key: C,
+    mode: Mode,
+    padding: Padding,
+    decrypt: bool,
+    register: Vec<u8>,
+    gamma: Vec<u8>,
+    segments: usize,
+    buffer: Vec<u8>
+}
+
+impl<C: BlockCipher> BlockStream<C> {
+    pub fn encryptor(key: C, mode: Mode, padding: Padding) -> Result<Self, CipherError> {
+        BlockStream::new(key, mode, padding, false)
+    }
+
+    pub fn decryptor(key: C, mode: Mode, padding: Padding) -> Result<Self, CipherError> {
+        BlockStream::new(key, mode, padding, true)
+    }
+
+    fn new(key: C, mode: Mode, padding: Padding, decrypt: bool) -> Result<Self, CipherError> {
+        let n = C::BLOCK_SIZE;
+        let register = match &mode {
+            Mode::Ecb => Vec::new(),
+            Mode::Cbc { iv } => {
+                modes::check_register(iv, n, true)?;
+                iv.clone()
+            },
+            Mode::Ctr { iv, s } => {
+                modes::check_width(*s, n)?;
+                if iv.len() != n / 2 {
+                    Err(CipherError::invalid_size(
+                        "Вектор инициализации", &format!("Вектор инициализации должен состоять из {} байт", n / 2)
+                    ))?;
+                }
+                let mut counter = iv.clone();
+                counter.resize(n, 0);
+                counter
+            },
+            Mode::Ofb { iv, s } => {
+                modes::check_width(*s, n)?;
+                modes::check_register(iv, n, true)?;
+                iv.clone()
+            },
+            Mode::Cfb { iv, s } => {
+                modes::check_width(*s, n)?;
+                modes::check_register(iv, n, false)?;
+                iv.clone()
+            },
+        };
+        Ok(BlockStream { key, mode, padding, decrypt, register, gamma: vec![0; n], segments: 0, buffer: Vec::new() })
+    }
+
+    /// Number of bytes processed at once
+    fn segment(&self) -> usize {
+        match self.mode {
+            Mode::Ecb | Mode::Cbc { .. } => C::BLOCK_SIZE,
+            Mode::Ctr { s, .. } | Mode::Ofb { s, .. } | Mode::Cfb { s, .. } => s,
+        }
+    }
+
+    /// Whether the last block must wait for `finish` to remove the padding
+    fn padded(&self) -> bool {
+        matches!(self.mode, Mode::Ecb | Mode::Cbc { .. })
+    }
+
+    /// Processes one segment in place, only the last one may be incomplete
+    fn process(&mut self, part: &mut [u8]) -> Result<(), CipherError> {
+        let n = C::BLOCK_SIZE;
+        let key = &self.key;
+        let gamma = &mut self.gamma;
+        match self.mode {
+            Mode::Ecb if self.decrypt => key.decrypt_block(part),
+            Mode::Ecb => key.encrypt_block(part),
+            Mode::Cbc { .. } if self.decrypt => {
+                gamma.copy_from_slice(part);
+                key.decrypt_block(part);
+                xor(part, &self.register);
+                modes::shift(&mut self.register, gamma);
+            },
+            Mode::Cbc { .. } => {
+                xor(part, &self.register);
+                key.encrypt_block(part);
+                modes::shift(&mut self.register, part);
+            },
+            Mode::Ctr { .. } => {
+                if self.segments > 0 && increment(&mut self.register) { Err(CipherError::CounterOverflow)?; }
+                gamma.copy_from_slice(&self.register);
+                key.encrypt_block(gamma);
+                xor(part, gamma);
+            },
+            Mode::Ofb { .. } => {
+                gamma.copy_from_slice(&self.register[..n]);
+                key.encrypt_block(gamma);
+                modes::shift(&mut self.register, gamma);
+                xor(part, gamma);
+            },
+            Mode::Cfb { .. } => {
+                gamma.copy_from_slice(&self.register[..n]);
+                key.encrypt_block(gamma);
+                if self.decrypt { modes::shift(&mut self.register, part); }
+                xor(part, gamma);
+                if !self.decrypt { modes::shift(&mut self.register, part); }
+            },
+        }
+        self.segments += 1;
+        Ok(())
+    }
+
+    fn process_all(&mut self, data: &[u8], out: &mut Vec<u8>) -> Result<(), CipherError> {
+        let start = out.len();
+        out.extend_from_slice(data);
+        for part in out[start..].chunks_mut(self.segment()) {
+            self.process(part)?;
+        }
+        Ok(())
+    }
+}
+
+impl<C: BlockCipher> Transform for BlockStream<C> {
+    fn update(&mut self, data: &[u8], out: &mut Vec<u8>) -> Result<(), CipherError> {
+        self.buffer.extend_from_slice(data);
+        let size = self.segment();
+        let mut len = self.buffer.len() / size * size;
+        if self.padded() && self.decrypt && len == self.buffer.len() {
+            len = len.saturating_sub(size);
+        }
+        let data: Vec<u8> = self.buffer.drain(..len).collect();
+        self.process_all(&data, out)
+    }
+
+    fn finish(&mut self, out: &mut Vec<u8>) -> Result<(), CipherError> {
+        let n = C::BLOCK_SIZE;
+        let data = std::mem::take(&mut self.buffer);
+        if !self.padded() {
+            return self.process_all(&data, out);
+        }
+        if self.decrypt {
+            if !data.len().is_multiple_of(n) {
+                Err(CipherError::invalid_size(
+                    "Данные", &format!("Длина данных должна быть кратна {n} байтам")
+                ))?;
+            }
+            let mut result = Vec::with_capacity(n);
+            self.process_all(&data, &mut result)?;
+            out.extend(self.padding.unpad(&result, n)?);
+            Ok(())
+        } else {
+            let data = self.padding.pad(&data, n)?;
+            self.process_all(&data, out)
+        }
+    }
+}
+
+/// Xors the stream with the gamma, encryption and decryption are the same
+pub struct Gamma<I: Iterator<Item = u8>> {
+    keystream: I
+}
+
+impl<I: Iterator<Item = u8>> Gamma<I> {
+    pub fn new(keystream: I) -> Self {
+        Gamma { keystream }
+    }
+}
+
+impl<I: Iterator<Item = u8>> Transform for Gamma<I> {
+    fn update(&mut self, data: &[u8], out: &mut Vec<u8>) -> Result<(), CipherError> {
+        out.extend(data.iter().zip(&mut self.keystream).map(|(byte, gamma)| byte ^ gamma));
+        Ok(())
+    }
+
+    fn finish(&mut self, _out: &mut Vec<u8>) -> Result<(), CipherError> {
+        Ok(())
+    }
+}
+
+fn to_io(error: CipherError) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, error)
+}
+
+/// Writes the processed data to `inner`, the stream is complete only after `finish`
+pub struct StreamWriter<W: Write, T: Transform> {
+    inner: W,
+    transform: T,
+    output: Vec<u8>
+}
+
+impl<W: Write, T: Transform> StreamWriter<W, T> {
+    pub fn new(inner: W, transform: T) -> Self {
+        StreamWriter { inner, transform, output: Vec::new() }
+    }
+
+    /// Writes the last block and returns the inner writer
+    pub fn finish(mut self) -> io::Result<W> {
+        self.output.clear();
+        self.transform.finish(&mut self.output).map_err(to_io)?;
+        self.inner.write_all(&self.output)?;
+        self.inner.flush()?;
+        Ok(self.inner)
+    }
+}
+
+impl<W: Write, T: Transform> Write for StreamWriter<W, T> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.output.clear();
+        self.transform.update(buf, &mut self.output).map_err(to_io)?;
+        self.inner.write_all(&self.output)?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Reads the data from `inner` and returns it processed
+pub struct StreamReader<R: Read, T: Transform> {
+    inner: R,
+    transform: T,
+    output: Vec<u8>,
+    position: usize,
+    finished: bool
+}
+
+impl<R: Read, T: Transform> StreamReader<R, T> {
+    pub fn new(inner: R, transform: T) -> Self {
+        StreamReader { inner, transform, output: Vec::new(), position: 0, finished: false }
+    }
+
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R: Read, T: Transform> Read for StreamReader<R, T> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        while self.position == self.output.len() && !self.finished {
+            self.output.clear();
+            self.position = 0;
+            let mut chunk = [0; CHUNK_SIZE];
+            let count = self.inner.read(&mut chunk)?;
+            if count == 0 {
+                self.finished = true;
+                self.transform.finish(&mut self.output).map_err(to_io)?;
+            } else {
+                self.transform.update(&chunk[..count], &mut self.output).map_err(to_io)?;
+            }
+        }
+        let count = buf.len().min(self.output.len() - self.position);
+        buf[..count].copy_from_slice(&self.output[self.position..self.position + count]);
+        self.position += count;
+        Ok(count)
+    }
+}
+
+#[cfg(test)]
+mod stream_tests {
+    use crate::a5_1;
+    use crate::a5_2;
+    use crate::aes::AesKey;
+    use crate::cipher::Cipher;
+    use crate::kuznechik::KuznechikKey;
+    use crate::magma::MagmaKey;
+    use super::*;
+
+    const MAGMA_KEY: &str = "ffeeddccbbaa99887766554433221100f0f1f2f3f4f5f6f7f8f9fafbfcfdfeff";
+    const KUZNECHIK_KEY: &str = "8899aabbccddeeff0011223344556677fedcba98765432100123456789abcdef";
+
+    fn data(len: usize) -> Vec<u8> {
+        (0..len).map(|i| (i * 31 + 7) as u8).collect()
+    }
+
+    /// Writes `data` in pieces of growing length
+    fn write_all<T: Transform>(data: &[u8], transform: T) -> Result<Vec<u8>, io::Error> {
+        let mut writer = StreamWriter::new(Vec::new(), transform);
+        let mut rest = data;
+        let mut size = 1;
+        while !rest.is_empty() {
+            let (part, tail) = rest.split_at(size.min(rest.len()));
+            writer.write_all(part)?;
+            rest = tail;
+            size += 3;
+        }
+        writer.finish()
+    }
+
+    fn read_all<T: Transform>(data: &[u8], transform: T) -> Result<Vec<u8>, io::Error> {
+        let mut result = Vec::new();
+        StreamReader::new(data, transform).read_to_end(&mut result)?;
+        Ok(result)
+    }
+
+    #[test]
+    fn test_modes() {
+        let key = KuznechikKey::from_hex(KUZNECHIK_KEY).unwrap();
+        let iv = data(32);
+        let data = data(1000);
+        let cases = [
+            (Mode::Ecb, Padding::Pkcs7, modes::ecb_encrypt(&data, Padding::Pkcs7, &key).unwrap()),
+            (Mode::Cbc { iv: iv.clone() }, Padding::Iso7816, modes::cbc_encrypt(&data, &iv, Padding::Iso7816, &key).unwrap()),
+            (Mode::Ctr { iv: iv[..8].to_vec(), s: 16 }, Padding::None, modes::ctr(&data, &iv[..8], 16, &key).unwrap()),
+            (Mode::Ofb { iv: iv.clone(), s: 5 }, Padding::None, modes::ofb(&data, &iv, 5, &key).unwrap()),
+            (Mode::Cfb { iv: iv[..24].to_vec(), s: 16 }, Padding::None, modes::cfb_encrypt(&data, &iv[..24], 16, &key).unwrap()),
+        ];
+        for (mode, padding, valid) in cases {
+            let encryptor = || BlockStream::encryptor(key.clone(), mode.clone(), padding).unwrap();
+            let decryptor = || BlockStream::decryptor(key.clone(), mode.clone(), padding).unwrap();
+            assert_eq!(write_all(&data, encryptor()).unwrap(), valid, "{mode:?}");
+            assert_eq!(read_all(&data, encryptor()).unwrap(), valid, "{mode:?}");
+            assert_eq!(write_all(&valid, decryptor()).unwrap(), data, "{mode:?}");
+            assert_eq!(read_all(&valid, decryptor()).unwrap(), data, "{mode:?}");
+        }
+    }
+
+    #[test]
+    fn test_padding() {
+        let key = MagmaKey::from_hex(MAGMA_KEY).unwrap();
+        for len in [0, 7, 8, 9, 16] {
+            let data = data(len);
+            let valid = modes::ecb_encrypt(&data, Padding::Pkcs7, &key).unwrap();
+            let encryptor = BlockStream::encryptor(key.clone(), Mode::Ecb, Padding::Pkcs7).unwrap();
+            assert_eq!(write_all(&data, encryptor).unwrap(), valid);
+            let decryptor = BlockStream::decryptor(key.clone(), Mode::Ecb, Padding::Pkcs7).unwrap();
+            assert_eq!(read_all(&valid, decryptor).unwrap(), data);
+        }
+        let encryptor = BlockStream::encryptor(key.clone(), Mode::Ecb, Padding::None).unwrap();
+        assert_eq!(write_all(&data(9), encryptor).unwrap_err().kind(), io::ErrorKind::InvalidData);
+        let decryptor = BlockStream::decryptor(key.clone(), Mode::Ecb, Padding::None).unwrap();
+        assert!(read_all(&data(9), decryptor).is_err());
+    }
+
+    #[test]
+    fn test_aes() {
+        let key = AesKey::from_hex("000102030405060708090a0b0c0d0e0f").unwrap();
+        let data = data(100);
+        let valid = modes::cbc_encrypt(&data, &[0; 16], Padding::Pkcs7, &key).unwrap();
+        let encryptor = BlockStream::encryptor(key.clone(), Mode::Cbc { iv: vec![0; 16] }, Padding::Pkcs7).unwrap();
+        assert_eq!(read_all(&data, encryptor).unwrap(), valid);
+    }
+
+    #[test]
+    fn test_parameters() {
+        let key = MagmaKey::from_hex(MAGMA_KEY).unwrap();
+        assert!(BlockStream::encryptor(key.clone(), Mode::Cbc { iv: vec![0; 7] }, Padding::None).is_err());
+        assert!(BlockStream::encryptor(key.clone(), Mode::Ctr { iv: vec![0; 8], s: 8 }, Padding::None).is_err());
+        assert!(BlockStream::encryptor(key.clone(), Mode::Ofb { iv: vec![0; 8], s: 9 }, Padding::None).is_err());
+        assert!(BlockStream::encryptor(key, Mode::Cfb { iv: vec![0; 4], s: 8 }, Padding::None).is_err());
+    }
+
+    #[test]
+    fn test_gamma() {
+        let phrase = "ото";
+        let key = "ffeeddcc77665544";
+        let data = phrase.as_bytes();
+        let result = write_all(data, Gamma::new(a5_1::keystream(0xffeeddcc77665544))).unwrap();
+        assert_eq!(hex::encode(&result), a5_1::encrypt(phrase, key).unwrap());
+        let result = read_all(data, Gamma::new(a5_2::keystream(0xffeeddcc77665544))).unwrap();
+        assert_eq!(hex::encode(&result), "9b84cdab0f44");
+        let long = data.repeat(50);
+        let result = read_all(&long, Gamma::new(a5_1::keystream(1))).unwrap();
+        assert_eq!(read_all(&result, Gamma::new(a5_1::keystream(1))).unwrap(), long);
+        assert_eq!(hex::encode(&result), a5_1::A51.encrypt(&String::from_utf8(long).unwrap(), &1).unwrap());
+    }
+}
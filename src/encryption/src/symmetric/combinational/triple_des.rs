@@ -0,0 +1,280 @@
+use std::fmt;
+use crate::errors::CipherError;
+use crate::cipher::Cipher;
+use crate::methods::{bytes_to_hex, hex_to_bytes};
+use crate::secret::Secret;
+use super::des::{self, DesKey};
+use super::{mac, modes, process_blocks, BlockCipher};
+use super::padding::Padding;
+
+/// Keying option of Triple DES
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Keying {
+    /// Two independent keys, the third one equals the first
+    Ede2,
+    #[default]
+    Ede3,
+}
+
+impl Keying {
+    pub const ALL: [Keying; 2] = [Keying::Ede2, Keying::Ede3];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Keying::Ede2 => "3DES EDE2",
+            Keying::Ede3 => "3DES EDE3",
+        }
+    }
+
+    /// Length of the key in bytes
+    pub fn bytes(&self) -> usize {
+        match self {
+            Keying::Ede2 => 16,
+            Keying::Ede3 => 24,
+        }
+    }
+
+    pub fn from_bytes(len: usize) -> Option<Self> {
+        Keying::ALL.into_iter().find(|keying| keying.bytes() == len)
+    }
+}
+
+/// `E(K3, D(K2, E(K1, block)))`
+#[derive(Clone)]
+pub struct TripleDesKey {
+    keys: [DesKey; 3],
+    keying: Keying
+}
+
+impl TripleDesKey {
+    pub fn ede2(key: [u8; 16]) -> Self {
+        TripleDesKey::expand(&key)
+    }
+
+    pub fn ede3(key: [u8; 24]) -> Self {
+        TripleDesKey::expand(&key)
+    }
+
+    /// `key` is 16 or 24 bytes long
+    fn expand(key: &[u8]) -> Self {
+        let part = |i: usize| DesKey::try_from(&key[8 * i % key.len()..][..8]).unwrap();
+        TripleDesKey {
+            keys: [part(0), part(1), part(2)],
+            keying: Keying::from_bytes(key.len()).unwrap()
+        }
+    }
+
+    pub fn from_hex(key: &str) -> Result<Self, CipherError> {
+        Self::try_from(Secret::<Vec<u8>>::from_hex(key)?.expose().as_slice())
+    }
+
+    pub fn keying(&self) -> Keying {
+        self.keying
+    }
+}
+
+impl fmt::Debug for TripleDesKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TripleDesKey").field("keying", &self.keying).finish_non_exhaustive()
+    }
+}
+
+impl From<[u8; 16]> for TripleDesKey {
+    fn from(value: [u8; 16]) -> Self {
+        TripleDesKey::ede2(value)
+    }
+}
+
+impl From<[u8; 24]> for TripleDesKey {
+    fn from(value: [u8; 24]) -> Self {
+        TripleDesKey::ede3(value)
+    }
+}
+
+/// Accepts keys of 16 and 24 bytes
+impl TryFrom<&[u8]> for TripleDesKey {
+    type Error = CipherError;
+
+    fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
+        match Keying::from_bytes(value.len()) {
+            Some(_) => Ok(TripleDesKey::expand(value)),
+            None => Err(CipherError::invalid_size("Ключ", "Ключ должен состоять из 16 или 24 байт"))
+        }
+    }
+}
+
+impl BlockCipher for TripleDesKey {
+    const BLOCK_SIZE: usize = 8;
+
+    fn encrypt_block(&self, block: &mut [u8]) {
+        let block: &mut [u8; 8] = block.try_into().unwrap();
+        *block = encrypt_block(block, self);
+    }
+
+    fn decrypt_block(&self, block: &mut [u8]) {
+        let block: &mut [u8; 8] = block.try_into().unwrap();
+        *block = decrypt_block(block, self);
+    }
+}
+
+/// Rejects weak parts and neighbouring parts that are equal,
+/// such keys reduce Triple DES to a single DES
+pub fn check_key(key: &[u8]) -> Result<(), CipherError> {
+    if Keying::from_bytes(key.len()).is_none() {
+        Err(CipherError::invalid_size("Ключ", "Ключ должен состоять из 16 или 24 байт"))?;
+    }
+    let parts: Vec<[u8; 8]> = key.chunks(8).map(|part| part.try_into().unwrap()).collect();
+    for part in &parts {
+        des::check_key(part)?;
+    }
+    let same = |left: &[u8; 8], right: &[u8; 8]| left.iter().zip(right).all(|(left, right)| left & 0xfe == right & 0xfe);
+    if parts.windows(2).any(|pair| same(&pair[0], &pair[1])) {
+        Err(CipherError::invalid_key("Ключ", "Соседние части ключа 3DES не должны совпадать"))?;
+    }
+    Ok(())
+}
+
+pub fn encrypt_block(block: &[u8; 8], key: &TripleDesKey) -> [u8; 8] {
+    let [first, second, third] = &key.keys;
+    des::encrypt_block(&des::decrypt_block(&des::encrypt_block(block, first), second), third)
+}
+
+pub fn decrypt_block(block: &[u8; 8], key: &TripleDesKey) -> [u8; 8] {
+    let [first, second, third] = &key.keys;
+    des::decrypt_block(&des::encrypt_block(&des::decrypt_block(block, third), second), first)
+}
+
+pub fn encrypt_bytes(data: &[u8], key: &TripleDesKey) -> Result<Vec<u8>, CipherError> {
+    process_blocks(data, 8, |block| key.encrypt_block(block))
+}
+
+pub fn decrypt_bytes(data: &[u8], key: &TripleDesKey) -> Result<Vec<u8>, CipherError> {
+    process_blocks(data, 8, |block| key.decrypt_block(block))
+}
+
+pub fn encrypt_padded(data: &[u8], padding: Padding, key: &TripleDesKey) -> Result<Vec<u8>, CipherError> {
+    modes::ecb_encrypt(data, padding, key)
+}
+
+pub fn decrypt_padded(data: &[u8], padding: Padding, key: &TripleDesKey) -> Result<Vec<u8>, CipherError> {
+    modes::ecb_decrypt(data, padding, key)
+}
+
+/// CMAC with the 64-bit block, `len` is the length of the tag in bytes
+pub fn mac(data: &[u8], len: usize, key: &TripleDesKey) -> Result<Vec<u8>, CipherError> {
+    mac::mac(data, len, key)
+}
+
+fn key_from_str(key: &str) -> Result<TripleDesKey, CipherError> {
+    let key = Secret::<Vec<u8>>::from_hex(key)?;
+    if key.expose().is_empty() { Err(CipherError::EmptyValue { param: "Ключ" })?; }
+    check_key(key.expose())?;
+    TripleDesKey::try_from(key.expose().as_slice())
+}
+
+/// The phrase is completed with zeros to the block border, the key is 16 or 24 bytes long
+pub fn encrypt(phrase: &str, key: &str) -> Result<String, CipherError> {
+    encrypt_with_padding(phrase, key, Padding::Zero)
+}
+
+pub fn decrypt(phrase: &str, key: &str) -> Result<String, CipherError> {
+    decrypt_with_padding(phrase, key, Padding::Zero)
+}
+
+pub fn encrypt_with_padding(phrase: &str, key: &str, padding: Padding) -> Result<String, CipherError> {
+    let key = key_from_str(key)?;
+    Ok(bytes_to_hex(&encrypt_padded(&hex_to_bytes(phrase, 1)?, padding, &key)?))
+}
+
+pub fn decrypt_with_padding(phrase: &str, key: &str, padding: Padding) -> Result<String, CipherError> {
+    let key = key_from_str(key)?;
+    Ok(bytes_to_hex(&decrypt_padded(&hex_to_bytes(phrase, 1)?, padding, &key)?))
+}
+
+pub struct TripleDes;
+
+impl Cipher for TripleDes {
+    type Key = TripleDesKey;
+
+    fn encrypt(&self, phrase: &str, key: &TripleDesKey) -> Result<String, CipherError> {
+        Ok(bytes_to_hex(&encrypt_padded(&hex_to_bytes(phrase, 1)?, Padding::Zero, key)?))
+    }
+
+    fn decrypt(&self, phrase: &str, key: &TripleDesKey) -> Result<String, CipherError> {
+        Ok(bytes_to_hex(&decrypt_padded(&hex_to_bytes(phrase, 1)?, Padding::Zero, key)?))
+    }
+}
+
+#[cfg(test)]
+mod triple_des_tests {
+    use super::*;
+
+    const KEY: &str = "0123456789abcdef23456789abcdef01456789abcdef0123";
+
+    fn block(value: &str) -> [u8; 8] {
+        hex_to_bytes(value, 1).unwrap().try_into().unwrap()
+    }
+
+    #[test]
+    fn test_single() {
+        let data = block("0123456789abcdef");
+        let key = TripleDesKey::from_hex("133457799bbcdff1133457799bbcdff1133457799bbcdff1").unwrap();
+        assert_eq!(bytes_to_hex(&encrypt_block(&data, &key)), "85e813540f0ab405");
+        let key = TripleDesKey::from_hex("133457799bbcdff1133457799bbcdff1").unwrap();
+        assert_eq!(key.keying(), Keying::Ede2);
+        assert_eq!(bytes_to_hex(&encrypt_block(&data, &key)), "85e813540f0ab405");
+    }
+
+    #[test]
+    fn test_keying() {
+        let data = block("5468652071756663");
+        let ede3 = TripleDesKey::from_hex(KEY).unwrap();
+        let result = encrypt_block(&data, &ede3);
+        let parts: Vec<DesKey> = (0..3).map(|i| DesKey::from_hex(&KEY[16 * i..16 * i + 16]).unwrap()).collect();
+        let valid = des::encrypt_block(&des::decrypt_block(&des::encrypt_block(&data, &parts[0]), &parts[1]), &parts[2]);
+        assert_eq!(result, valid);
+        assert_eq!(bytes_to_hex(&result), "a826fd8ce53b855f");
+        assert_eq!(decrypt_block(&result, &ede3), data);
+        let ede2 = TripleDesKey::from_hex(&KEY[..32]).unwrap();
+        let same = TripleDesKey::from_hex(&[&KEY[..32], &KEY[..16]].concat()).unwrap();
+        assert_eq!(encrypt_block(&data, &ede2), encrypt_block(&data, &same));
+        assert!(TripleDesKey::from_hex(&KEY[..16]).is_err());
+    }
+
+    #[test]
+    fn test_nist() {
+        // Example of NIST SP 800-67 appendix B, "The qufck brown fox jump"
+        let data = "54686520717566636b2062726f776e20666f78206a756d70";
+        let valid = "a826fd8ce53b855fcce21c8112256fe668d5c05dd9b6b900";
+        let key = TripleDesKey::from_hex(KEY).unwrap();
+        assert_eq!(bytes_to_hex(&encrypt_bytes(&hex_to_bytes(data, 1).unwrap(), &key).unwrap()), valid);
+        assert_eq!(bytes_to_hex(&decrypt_bytes(&hex_to_bytes(valid, 1).unwrap(), &key).unwrap()), data);
+        assert_eq!(encrypt(data, KEY).unwrap(), valid);
+        assert_eq!(decrypt(valid, KEY).unwrap(), data);
+    }
+
+    #[test]
+    fn test_check_key() {
+        assert_eq!(check_key(&hex_to_bytes(KEY, 1).unwrap()), Ok(()));
+        let weak = hex_to_bytes("0123456789abcdef0101010101010101", 1).unwrap();
+        assert!(matches!(check_key(&weak), Err(CipherError::InvalidKey { .. })));
+        let same = hex_to_bytes("0123456789abcdef0023456789abcdef", 1).unwrap();
+        assert!(matches!(check_key(&same), Err(CipherError::InvalidKey { .. })));
+        assert!(matches!(encrypt("00", "0123456789abcdef"), Err(CipherError::InvalidSize { .. })));
+    }
+
+    #[test]
+    fn test_encrypt() {
+        let phrase = "5468652071756663";
+        let result = encrypt(phrase, KEY).unwrap();
+        assert_eq!(decrypt(&result, KEY).unwrap(), phrase);
+        let key = TripleDesKey::from_hex(KEY).unwrap();
+        assert_eq!(TripleDes.encrypt(phrase, &key).unwrap(), result);
+        assert_eq!(TripleDes.decrypt(&result, &key).unwrap(), phrase);
+        assert_eq!(decrypt_bytes(&encrypt_bytes(&[1; 16], &key).unwrap(), &key).unwrap(), [1; 16]);
+        assert_eq!(mac(b"data", 8, &key).unwrap().len(), 8);
+        assert_eq!(TripleDes.encrypt("0123", &key).unwrap(), encrypt("0123", KEY).unwrap());
+        let result = encrypt_with_padding(phrase, KEY, Padding::Iso7816).unwrap();
+        assert_eq!(decrypt_with_padding(&result, KEY, Padding::Iso7816).unwrap(), phrase);
+    }
+}
@@ -1,7 +1,8 @@
-use std::error::Error;
+use crate::errors::CipherError;
 use crate::alphabet::Alphabet;
-use crate::errors::InvalidSize;
-use crate::methods::{modd, validate_single};
+use crate::options::TextOptions;
+use crate::cipher::Cipher;
+use crate::methods::{modd, parse_number, validate_single};
 
 struct Generator {
     state: usize,
@@ -21,41 +22,87 @@ impl Generator {
     }
 }
 
-fn validate(alphabet: &Alphabet, phrase: &str, t0: &str, a: &str, c: &str)
-    -> Result<(usize, usize, usize), Box<dyn Error>>
-{
-    let t0: usize = t0.parse()?;
-    let a: usize = a.parse()?;
-    let c: usize = c.parse()?;
-    if 0 == t0 || alphabet.len() < t0 { Err(InvalidSize::new("T0 должен быть в пределах от 1 до 32"))?; }
-    if 0 == a || a % 4 != 1 { Err(InvalidSize::new("a должно быть отлично от 0 и остаток от деления на 4 равен 1"))?; }
-    if 0 == c || c % 2 == 0 { Err(InvalidSize::new("c должно быть отлично от 0 и нечетным"))?; }
-    validate_single(alphabet, phrase)?;
-    Ok((t0, a, c))
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ShenonKey {
+    pub t0: usize,
+    pub a: usize,
+    pub c: usize
 }
 
-pub fn encrypt(phrase: &str, t0: &str, a: &str, c: &str) -> Result<String, Box<dyn Error>> {
-    let alphabet = Alphabet::new();
-    let (t0, a, c) = validate(&alphabet, phrase, t0, a, c)?;
-    let mut gen = Generator::new(t0, a, c, alphabet.len());
-    Ok(phrase.chars().map(move |letter| {
-        let pos = (gen.step() + alphabet.index_of(letter)) % alphabet.len();
-        alphabet.get(pos)
-    }).collect())
+pub struct Shenon;
+
+impl Cipher for Shenon {
+    type Key = ShenonKey;
+
+    fn encrypt(&self, phrase: &str, key: &ShenonKey) -> Result<String, CipherError> {
+        proto(&Alphabet::new(), phrase, key, false)
+    }
+
+    fn decrypt(&self, phrase: &str, key: &ShenonKey) -> Result<String, CipherError> {
+        proto(&Alphabet::new(), phrase, key, true)
+    }
 }
 
-pub fn decrypt(phrase: &str, t0: &str, a: &str, c: &str) -> Result<String, Box<dyn Error>> {
-    let alphabet = Alphabet::new();
-    let (t0, a, c) = validate(&alphabet, phrase, t0, a, c)?;
-    let mut gen = Generator::new(t0, a, c, alphabet.len());
+fn parse_key(t0: &str, a: &str, c: &str) -> Result<ShenonKey, CipherError> {
+    Ok(ShenonKey {
+        t0: parse_number(t0, "T0")?,
+        a: parse_number(a, "a")?,
+        c: parse_number(c, "c")?
+    })
+}
+
+fn validate(alphabet: &Alphabet, phrase: &str, key: &ShenonKey) -> Result<(), CipherError> {
+    let ShenonKey { t0, a, c } = *key;
+    if 0 == t0 || alphabet.len() < t0 { Err(CipherError::invalid_key("T0", &format!(
+        "T0 должен быть в пределах от 1 до {}", alphabet.len()
+    )))?; }
+    if 0 == a || a % 4 != 1 { Err(CipherError::invalid_key("a", "a должно быть отлично от 0 и остаток от деления на 4 равен 1"))?; }
+    if 0 == c || c.is_multiple_of(2) { Err(CipherError::invalid_key("c", "c должно быть отлично от 0 и нечетным"))?; }
+    validate_single(alphabet, phrase)
+}
+
+fn proto(alphabet: &Alphabet, phrase: &str, key: &ShenonKey, reverse: bool) -> Result<String, CipherError> {
+    validate(alphabet, phrase, key)?;
+    let mut gen = Generator::new(key.t0, key.a, key.c, alphabet.len());
     Ok(phrase.chars().map(move |letter| {
-        let pos = modd(
-            alphabet.index_of(letter) as isize - gen.step() as isize,
-            alphabet.len());
-        alphabet.get(pos)
+        let shift = gen.step() as isize * if reverse { -1 } else { 1 };
+        alphabet.get(modd(alphabet.index_of(letter) as isize + shift, alphabet.len()))
     }).collect())
 }
 
+pub fn encrypt(phrase: &str, t0: &str, a: &str, c: &str) -> Result<String, CipherError> {
+    proto(&Alphabet::new(), phrase, &parse_key(t0, a, c)?, false)
+}
+
+pub fn decrypt(phrase: &str, t0: &str, a: &str, c: &str) -> Result<String, CipherError> {
+    proto(&Alphabet::new(), phrase, &parse_key(t0, a, c)?, true)
+}
+
+pub fn encrypt_with_alphabet(alphabet: &Alphabet, phrase: &str, t0: &str, a: &str, c: &str)
+    -> Result<String, CipherError>
+{
+    proto(alphabet, phrase, &parse_key(t0, a, c)?, false)
+}
+
+pub fn decrypt_with_alphabet(alphabet: &Alphabet, phrase: &str, t0: &str, a: &str, c: &str)
+    -> Result<String, CipherError>
+{
+    proto(alphabet, phrase, &parse_key(t0, a, c)?, true)
+}
+
+pub fn encrypt_with_options(
+    alphabet: &Alphabet, phrase: &str, t0: &str, a: &str, c: &str, options: &TextOptions
+) -> Result<String, CipherError> {
+    options.apply(alphabet, phrase, |letters| encrypt_with_alphabet(alphabet, letters, t0, a, c))
+}
+
+pub fn decrypt_with_options(
+    alphabet: &Alphabet, phrase: &str, t0: &str, a: &str, c: &str, options: &TextOptions
+) -> Result<String, CipherError> {
+    options.apply(alphabet, phrase, |letters| decrypt_with_alphabet(alphabet, letters, t0, a, c))
+}
+
 #[cfg(test)]
 mod shenon_tests {
     use super::*;
@@ -88,4 +135,21 @@ mod shenon_tests {
         let result = gen.step();
         assert_eq!(result, 0);
     }
+
+    #[test]
+    fn test_with_alphabet() {
+        let alphabet = Alphabet::latin();
+        let result = encrypt_with_alphabet(&alphabet, "attackatdawn", "3", "9", "5").unwrap();
+        assert_eq!(decrypt_with_alphabet(&alphabet, &result, "3", "9", "5").unwrap(), "attackatdawn");
+        assert!(encrypt_with_alphabet(&alphabet, "attack", "27", "9", "5").is_err());
+    }
+
+    #[test]
+    fn test_with_options() {
+        let options = TextOptions { pass_through: true, preserve_case: true };
+        let alphabet = Alphabet::new();
+        let result = encrypt_with_options(&alphabet, "Привет, Мир!", "3", "9", "5", &options).unwrap();
+        assert_eq!(result.chars().filter(|letter| !letter.is_alphabetic()).collect::<String>(), ", !");
+        assert_eq!(decrypt_with_options(&alphabet, &result, "3", "9", "5", &options).unwrap(), "Привет, Мир!");
+    }
 }
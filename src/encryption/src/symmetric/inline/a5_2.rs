@@ -1,6 +1,7 @@
-use std::error::Error;
+use crate::errors::CipherError;
+use crate::cipher::Cipher;
 use crate::methods::{bytes_to_hex, bytes_to_string, hex_to_bytes, str_to_bytes};
-use super::{process, Register, RegIter, System};
+use super::{process, Keystream, Register, RegIter, System};
 use super::{to_64};
 
 const MASK_R4_10: u32 = 0x400; 
@@ -109,24 +110,47 @@ impl System for Sys {
     }
 }
 
-fn proto(phrase: &[u8], key: &str) -> Result<Vec<u8>, Box<dyn Error>> {
-    let key = to_64(&hex_to_bytes(key, 8)?);
+fn proto(phrase: &[u8], key: u64) -> Vec<u8> {
     let mut sys = Sys::new(key);
     let buffer = sys.process(phrase.len() * 8);
-    let proc = process(phrase, &buffer);
-    Ok(proc)
+    process(phrase, &buffer)
 }
 
-pub fn encrypt(phrase: &str, key: &str) -> Result<String, Box<dyn Error>> {
+/// Endless gamma for `stream::Gamma`, the same as used by `encrypt`
+pub fn keystream(key: u64) -> impl Iterator<Item = u8> {
+    Keystream::new(Sys::new(key))
+}
+
+fn proto_encrypt(phrase: &str, key: u64) -> Result<String, CipherError> {
     let phrase = str_to_bytes(phrase, 1)?;
-    let r = proto(&phrase, key)?;
-    Ok(bytes_to_hex(&r))
+    Ok(bytes_to_hex(&proto(&phrase, key)))
 }
 
-pub fn decrypt(phrase: &str, key: &str) -> Result<String, Box<dyn Error>> {
+fn proto_decrypt(phrase: &str, key: u64) -> Result<String, CipherError> {
     let phrase = hex_to_bytes(phrase, 1)?;
-    let r = proto(&phrase, key)?;
-    bytes_to_string(&r)
+    bytes_to_string(&proto(&phrase, key))
+}
+
+pub fn encrypt(phrase: &str, key: &str) -> Result<String, CipherError> {
+    proto_encrypt(phrase, to_64(&hex_to_bytes(key, 8)?))
+}
+
+pub fn decrypt(phrase: &str, key: &str) -> Result<String, CipherError> {
+    proto_decrypt(phrase, to_64(&hex_to_bytes(key, 8)?))
+}
+
+pub struct A52;
+
+impl Cipher for A52 {
+    type Key = u64;
+
+    fn encrypt(&self, phrase: &str, key: &u64) -> Result<String, CipherError> {
+        proto_encrypt(phrase, *key)
+    }
+
+    fn decrypt(&self, phrase: &str, key: &u64) -> Result<String, CipherError> {
+        proto_decrypt(phrase, *key)
+    }
 }
 
 #[cfg(test)]
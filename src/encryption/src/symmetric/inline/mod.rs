@@ -83,6 +83,36 @@ trait System {
     }
 }
 
+/// Gamma of the system as bytes, the frames of 114 bits follow each other without gaps
+struct Keystream<S: System> {
+    sys: S,
+    frame: u128,
+    left: u8
+}
+
+impl<S: System> Keystream<S> {
+    fn new(sys: S) -> Self {
+        Self { sys, frame: 0, left: 0 }
+    }
+}
+
+impl<S: System> Iterator for Keystream<S> {
+    type Item = u8;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut byte = 0;
+        for _ in 0..8 {
+            if self.left == 0 {
+                self.frame = self.sys.takt();
+                self.left = 114;
+            }
+            self.left -= 1;
+            byte = byte << 1 | (self.frame >> self.left & 1) as u8;
+        }
+        Some(byte)
+    }
+}
+
 impl Register {
     fn new(size: u8, scrambler: u32, control: u32) -> Self {
         Self { size, scrambler, value: 0, control }
@@ -1,22 +1,57 @@
-use std::error::Error;
+use crate::errors::CipherError;
 use crate::alphabet::Alphabet;
+use crate::options::TextOptions;
+use crate::cipher::Cipher;
 use crate::methods::validate_single;
 
-fn proto(phrase: &str) -> Result<String, Box<dyn Error>> {
-    let alphabet: Alphabet = Alphabet::new();
-    validate_single(&alphabet, phrase)?;
+fn proto(alphabet: &Alphabet, phrase: &str) -> Result<String, CipherError> {
+    validate_single(alphabet, phrase)?;
     let result: String = phrase.chars().map(
         |letter| alphabet.get(alphabet.len() - 1 - alphabet.index_of(letter))
     ).collect();
     Ok(result)
 }
 
-pub fn encrypt(phrase: &str) -> Result<String, Box<dyn Error>> {
-    proto(phrase)
+pub fn encrypt(phrase: &str) -> Result<String, CipherError> {
+    proto(&Alphabet::new(), phrase)
 }
 
-pub fn decrypt(phrase: &str) -> Result<String, Box<dyn Error>> {
-    proto(phrase)
+pub fn decrypt(phrase: &str) -> Result<String, CipherError> {
+    proto(&Alphabet::new(), phrase)
+}
+
+pub fn encrypt_with_alphabet(alphabet: &Alphabet, phrase: &str) -> Result<String, CipherError> {
+    proto(alphabet, phrase)
+}
+
+pub fn decrypt_with_alphabet(alphabet: &Alphabet, phrase: &str) -> Result<String, CipherError> {
+    proto(alphabet, phrase)
+}
+
+pub struct Atbash;
+
+impl Cipher for Atbash {
+    type Key = ();
+
+    fn encrypt(&self, phrase: &str, _key: &()) -> Result<String, CipherError> {
+        encrypt(phrase)
+    }
+
+    fn decrypt(&self, phrase: &str, _key: &()) -> Result<String, CipherError> {
+        decrypt(phrase)
+    }
+}
+
+pub fn encrypt_with_options(
+    alphabet: &Alphabet, phrase: &str, options: &TextOptions
+) -> Result<String, CipherError> {
+    options.apply(alphabet, phrase, |letters| encrypt_with_alphabet(alphabet, letters))
+}
+
+pub fn decrypt_with_options(
+    alphabet: &Alphabet, phrase: &str, options: &TextOptions
+) -> Result<String, CipherError> {
+    options.apply(alphabet, phrase, |letters| decrypt_with_alphabet(alphabet, letters))
 }
 
 #[cfg(test)]
@@ -46,4 +81,19 @@ mod atbash_test {
     fn test_decrypt_panic() {
         decrypt("z").unwrap();
     }
+
+    #[test]
+    fn test_with_alphabet() {
+        let alphabet = Alphabet::latin();
+        assert_eq!(encrypt_with_alphabet(&alphabet, "abcxyz").unwrap(), "zyxcba");
+        assert_eq!(decrypt_with_alphabet(&alphabet, "zyxcba").unwrap(), "abcxyz");
+    }
+
+    #[test]
+    fn test_with_options() {
+        let options = TextOptions { pass_through: true, preserve_case: true };
+        let alphabet = Alphabet::new();
+        assert_eq!(encrypt_with_options(&alphabet, "Привет, Мир!", &options).unwrap(), "Рпчэън, Учп!");
+        assert_eq!(decrypt_with_options(&alphabet, "Рпчэън, Учп!", &options).unwrap(), "Привет, Мир!");
+    }
 }
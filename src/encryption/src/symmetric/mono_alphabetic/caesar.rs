@@ -1,32 +1,68 @@
-use std::error::Error;
+use crate::errors::CipherError;
 use crate::methods::{modd};
 use crate::alphabet::Alphabet;
-use crate::errors::{NullSizedValue, InvalidKeyError};
+use crate::options::TextOptions;
+use crate::cipher::Cipher;
 
-fn proto(phrase: &str, shift: isize) -> Result<String, Box<dyn Error>> {
-    let alphabet: Alphabet = Alphabet::new();
-    validate(&alphabet, phrase, shift.abs())?;
+fn proto(alphabet: &Alphabet, phrase: &str, shift: isize) -> Result<String, CipherError> {
+    validate(alphabet, phrase, shift.abs())?;
     let result: String = phrase.chars().map(|letter|
         alphabet.get(modd(alphabet.index_of(letter) as isize + shift, alphabet.len()))
     ).collect();
     Ok(result)
 }
 
-pub fn encrypt(phrase: &str, shift: isize) -> Result<String, Box<dyn Error>> {
-    proto(phrase, shift)
+pub fn encrypt(phrase: &str, shift: isize) -> Result<String, CipherError> {
+    proto(&Alphabet::new(), phrase, shift)
 }
-pub fn decrypt(phrase: &str, shift: isize) -> Result<String, Box<dyn Error>> {
-    proto(phrase, -shift)
+pub fn decrypt(phrase: &str, shift: isize) -> Result<String, CipherError> {
+    proto(&Alphabet::new(), phrase, -shift)
 }
 
-fn validate(alphabet: &Alphabet, text: &str, shift: isize) -> Result<(), Box<dyn Error>> {
-    if text.is_empty() { return Err(Box::new(NullSizedValue::new("Фраза"))); }
+pub fn encrypt_with_alphabet(alphabet: &Alphabet, phrase: &str, shift: isize) -> Result<String, CipherError> {
+    proto(alphabet, phrase, shift)
+}
+
+pub fn decrypt_with_alphabet(alphabet: &Alphabet, phrase: &str, shift: isize) -> Result<String, CipherError> {
+    proto(alphabet, phrase, -shift)
+}
+
+pub struct Caesar;
+
+impl Cipher for Caesar {
+    type Key = isize;
+
+    fn encrypt(&self, phrase: &str, key: &isize) -> Result<String, CipherError> {
+        encrypt(phrase, *key)
+    }
+
+    fn decrypt(&self, phrase: &str, key: &isize) -> Result<String, CipherError> {
+        decrypt(phrase, *key)
+    }
+}
+
+fn validate(alphabet: &Alphabet, text: &str, shift: isize) -> Result<(), CipherError> {
+    if text.is_empty() { return Err(CipherError::EmptyValue { param: "Фраза" }); }
     if shift >= alphabet.len() as isize || shift < 1 {
-        return Err(Box::new(InvalidKeyError::new("Сдвиг не принадлежит заданному диапазону от 1 до 32")));
+        return Err(CipherError::invalid_key("Сдвиг", &format!(
+            "Сдвиг не принадлежит заданному диапазону от 1 до {}", alphabet.len()
+        )));
     }
     alphabet.validate(text)
 }
 
+pub fn encrypt_with_options(
+    alphabet: &Alphabet, phrase: &str, shift: isize, options: &TextOptions
+) -> Result<String, CipherError> {
+    options.apply(alphabet, phrase, |letters| encrypt_with_alphabet(alphabet, letters, shift))
+}
+
+pub fn decrypt_with_options(
+    alphabet: &Alphabet, phrase: &str, shift: isize, options: &TextOptions
+) -> Result<String, CipherError> {
+    options.apply(alphabet, phrase, |letters| decrypt_with_alphabet(alphabet, letters, shift))
+}
+
 #[cfg(test)]
 mod caesar_tests {
     use super::*;
@@ -68,4 +104,22 @@ mod caesar_tests {
     fn test_decrypt_shift_panic() {
         decrypt("отодногопорченогояблокавесьвоззагниваеттчк", 32).unwrap();
     }
+
+    #[test]
+    fn test_with_alphabet() {
+        let alphabet = Alphabet::latin();
+        assert_eq!(encrypt_with_alphabet(&alphabet, "hellozebra", 3).unwrap(), "khoorcheud");
+        assert_eq!(decrypt_with_alphabet(&alphabet, "khoorcheud", 3).unwrap(), "hellozebra");
+        assert!(encrypt_with_alphabet(&alphabet, "hello", 26).is_err());
+    }
+
+    #[test]
+    fn test_with_options() {
+        let options = TextOptions { pass_through: true, preserve_case: true };
+        let alphabet = Alphabet::new();
+        assert_eq!(encrypt_with_options(&alphabet, "Привет, Мир!", 3, &options).unwrap(), "Тулеих, Плу!");
+        assert_eq!(decrypt_with_options(&alphabet, "Тулеих, Плу!", 3, &options).unwrap(), "Привет, Мир!");
+        let options = TextOptions { pass_through: false, preserve_case: true };
+        assert!(encrypt_with_options(&alphabet, "Привет, Мир!", 3, &options).is_err());
+    }
 }
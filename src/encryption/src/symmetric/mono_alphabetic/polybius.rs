@@ -1,7 +1,8 @@
-use std::error::Error;
+use crate::errors::CipherError;
 use itertools::Itertools;
 use crate::alphabet::Alphabet;
-use crate::errors::{InvalidIndex, InvalidSize, NullSizedValue, OutOfBounds};
+use crate::cipher::Cipher;
+use crate::methods::parse_number;
 
 struct Table {
     columns: usize,
@@ -11,14 +12,19 @@ struct Table {
 }
 
 impl Table {
-    fn build(rows: usize, columns: usize) -> Result<Table, Box<dyn Error>> {
-        let alphabet = Alphabet::new();
+    fn build(alphabet: &Alphabet, rows: usize, columns: usize) -> Result<Table, CipherError> {
+        let alphabet = alphabet.clone();
         if rows * columns < alphabet.len() {
-            return Err(Box::new(InvalidSize::new(
-                "Произведение количества строк на столбцов должно превышать длину алфавита"
-            )))
+            return Err(CipherError::invalid_size(
+                "Размер таблицы", "Произведение количества строк на столбцов должно превышать длину алфавита"
+            ))
         }
-        let last_row = if alphabet.len() % columns == 0 { alphabet.len() / columns }
+        if rows > 9 || columns > 9 {
+            return Err(CipherError::invalid_size(
+                "Размер таблицы", "Количество строк и столбцов не должно превышать 9"
+            ))
+        }
+        let last_row = if alphabet.len().is_multiple_of(columns) { alphabet.len() / columns }
         else { alphabet.len() / columns + 1 };
         let last_column = columns - (last_row * columns - alphabet.len());
         Ok(Table {
@@ -36,16 +42,37 @@ impl Table {
     }
 }
 
-pub fn encrypt(phrase: &str, rows: &str, columns: &str) -> Result<String, Box<dyn Error>> {
-    let table = validate(phrase, rows, columns)?;
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PolybiusKey {
+    pub rows: usize,
+    pub columns: usize
+}
+
+pub struct Polybius;
+
+impl Cipher for Polybius {
+    type Key = PolybiusKey;
+
+    fn encrypt(&self, phrase: &str, key: &PolybiusKey) -> Result<String, CipherError> {
+        proto_encrypt(&Alphabet::new(), phrase, key)
+    }
+
+    fn decrypt(&self, phrase: &str, key: &PolybiusKey) -> Result<String, CipherError> {
+        proto_decrypt(&Alphabet::new(), phrase, key)
+    }
+}
+
+fn proto_encrypt(alphabet: &Alphabet, phrase: &str, key: &PolybiusKey) -> Result<String, CipherError> {
+    let table = validate(alphabet, phrase, key)?;
     Ok(phrase.chars().map(move |letter| {
         let (row, column) = table.index_of(letter);
         format!("{row}{column}")
     }).collect())
 }
 
-pub fn decrypt(phrase: &str, rows: &str, cols: &str) -> Result<String, Box<dyn Error>> {
-    let table = validate_dec(phrase, rows, cols)?;
+fn proto_decrypt(alphabet: &Alphabet, phrase: &str, key: &PolybiusKey) -> Result<String, CipherError> {
+    let table = validate_dec(alphabet, phrase, key)?;
     Ok(phrase.chars().tuples().map(|(row, col)| {
         let row = row.to_digit(10).unwrap() as usize;
         let col = col.to_digit(10).unwrap() as usize;
@@ -53,38 +80,59 @@ pub fn decrypt(phrase: &str, rows: &str, cols: &str) -> Result<String, Box<dyn E
     }).collect())
 }
 
-fn validate(
-    phrase: &str, rows: &str, columns: &str
-) -> Result<Table, Box<dyn Error>> {
-    if phrase.is_empty() { return Err(Box::new(NullSizedValue::new("Фраза"))); }
-    if rows.is_empty() { return Err(Box::new(NullSizedValue::new("Количество рядов"))); }
-    if columns.is_empty() { return Err(Box::new(NullSizedValue::new("Количество столбцов"))); }
-    let table = Table::build(rows.parse()?, columns.parse()?)?;
+pub fn encrypt(phrase: &str, rows: &str, columns: &str) -> Result<String, CipherError> {
+    proto_encrypt(&Alphabet::new(), phrase, &parse_key(rows, columns)?)
+}
+
+pub fn decrypt(phrase: &str, rows: &str, cols: &str) -> Result<String, CipherError> {
+    proto_decrypt(&Alphabet::new(), phrase, &parse_key(rows, cols)?)
+}
+
+pub fn encrypt_with_alphabet(alphabet: &Alphabet, phrase: &str, rows: &str, columns: &str)
+    -> Result<String, CipherError>
+{
+    proto_encrypt(alphabet, phrase, &parse_key(rows, columns)?)
+}
+
+pub fn decrypt_with_alphabet(alphabet: &Alphabet, phrase: &str, rows: &str, cols: &str)
+    -> Result<String, CipherError>
+{
+    proto_decrypt(alphabet, phrase, &parse_key(rows, cols)?)
+}
+
+fn parse_key(rows: &str, columns: &str) -> Result<PolybiusKey, CipherError> {
+    if rows.is_empty() { return Err(CipherError::EmptyValue { param: "Количество рядов" }); }
+    if columns.is_empty() { return Err(CipherError::EmptyValue { param: "Количество столбцов" }); }
+    Ok(PolybiusKey {
+        rows: parse_number(rows, "Количество рядов")?,
+        columns: parse_number(columns, "Количество столбцов")?
+    })
+}
+
+fn validate(alphabet: &Alphabet, phrase: &str, key: &PolybiusKey) -> Result<Table, CipherError> {
+    if phrase.is_empty() { return Err(CipherError::EmptyValue { param: "Фраза" }); }
+    let table = Table::build(alphabet, key.rows, key.columns)?;
     table.alphabet.validate(phrase)?;
     Ok(table)
 }
 
-fn validate_dec(
-    phrase: &str, rows: &str, columns: &str
-) -> Result<Table, Box<dyn Error>> {
-    if phrase.is_empty() { return Err(Box::new(NullSizedValue::new("Фраза"))); }
-    if phrase.chars().count() % 2 != 0 {return Err(Box::new(InvalidSize::new(
-        "Количество цифр должно быть кратным 2"
-    )));}
-    if rows.is_empty() { return Err(Box::new(NullSizedValue::new("Количество рядов"))); }
-    if columns.is_empty() { return Err(Box::new(NullSizedValue::new("Количество столбцов"))); }
-    let table = Table::build(rows.parse()?, columns.parse()?)?;
+fn validate_dec(alphabet: &Alphabet, phrase: &str, key: &PolybiusKey) -> Result<Table, CipherError> {
+    if phrase.is_empty() { return Err(CipherError::EmptyValue { param: "Фраза" }); }
+    if !phrase.chars().count().is_multiple_of(2) {return Err(CipherError::invalid_size(
+        "Фраза", "Количество цифр должно быть кратным 2"
+    ));}
+    let table = Table::build(alphabet, key.rows, key.columns)?;
     for (row, col) in phrase.chars().tuples() {
-        let row = row.to_digit(10).ok_or(InvalidIndex)? as usize;
-        let col = col.to_digit(10).ok_or(InvalidIndex)? as usize;
+        let row = row.to_digit(10).ok_or(CipherError::InvalidIndex)? as usize;
+        let col = col.to_digit(10).ok_or(CipherError::InvalidIndex)? as usize;
         if row > table.last_row || row == 0{
-            return Err(OutOfBounds::new("ряд"))?;
+            Err(CipherError::OutOfBounds { param: "ряд", value: row })?;
         }
         if col > table.columns || col == 0 {
-            return Err(OutOfBounds::new("столбец"))?;
+            Err(CipherError::OutOfBounds { param: "столбец", value: col })?;
         }
         if col > table.last_column && row == table.last_row {
-            return Err(OutOfBounds::new("столбец"))?;
+            Err(CipherError::OutOfBounds { param: "столбец", value: col })?;
         }
     }
     Ok(table)
@@ -96,7 +144,7 @@ mod polybius_tests {
 
     #[test]
     fn test_table_build() {
-        let table = Table::build(6, 6).unwrap();
+        let table = Table::build(&Alphabet::new(), 6, 6).unwrap();
         assert_eq!(table.columns, 6);
         assert_eq!(table.last_row, 6);
         assert_eq!(table.last_column, 2);
@@ -104,7 +152,7 @@ mod polybius_tests {
 
     #[test]
     fn fn_table_index_of() {
-        let table = Table::build(6, 6).unwrap();
+        let table = Table::build(&Alphabet::new(), 6, 6).unwrap();
         let (row, column) = table.index_of('\u{0430}');
         assert_eq!(row, 1);
         assert_eq!(column, 1);
@@ -112,7 +160,7 @@ mod polybius_tests {
 
     #[test]
     fn fn_table_get() {
-        let table = Table::build(6, 6).unwrap();
+        let table = Table::build(&Alphabet::new(), 6, 6).unwrap();
         let letter = table.get(1, 1);
         assert_eq!(letter, '\u{0430}');
     }
@@ -138,20 +186,20 @@ mod polybius_tests {
     #[test]
     #[should_panic]
     fn fn_table_build_panic() {
-        Table::build(4, 4).unwrap();
+        Table::build(&Alphabet::new(), 4, 4).unwrap();
     }
 
     #[test]
     #[should_panic]
     fn fn_table_index_of_panic() {
-        let table = Table::build(6, 6).unwrap();
+        let table = Table::build(&Alphabet::new(), 6, 6).unwrap();
         table.index_of('o');
     }
 
     #[test]
     #[should_panic]
     fn fn_table_get_panic() {
-        let table = Table::build(6, 6).unwrap();
+        let table = Table::build(&Alphabet::new(), 6, 6).unwrap();
         table.get(6, 3);
     }
 
@@ -166,4 +214,25 @@ mod polybius_tests {
     fn test_decrypt_panic() {
         decrypt("77", "6", "6").unwrap();
     }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(
+            decrypt("77", "6", "6"),
+            Err(CipherError::OutOfBounds { param: "ряд", value: 7 })
+        );
+        assert!(matches!(
+            encrypt("окно", "шесть", "6"),
+            Err(CipherError::InvalidNumber { param: "Количество рядов", .. })
+        ));
+        assert_eq!(encrypt("", "6", "6"), Err(CipherError::EmptyValue { param: "Фраза" }));
+    }
+
+    #[test]
+    fn test_with_alphabet() {
+        let alphabet = Alphabet::latin_25();
+        assert_eq!(encrypt_with_alphabet(&alphabet, "polybius", "5", "5").unwrap(), "3534315412244543");
+        assert_eq!(decrypt_with_alphabet(&alphabet, "3534315412244543", "5", "5").unwrap(), "polybius");
+        assert!(encrypt_with_alphabet(&Alphabet::printable_ascii(), "a", "10", "10").is_err());
+    }
 }
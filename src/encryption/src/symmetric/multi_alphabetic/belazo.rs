@@ -1,17 +1,53 @@
-use std::error::Error;
+use crate::errors::CipherError;
 use crate::alphabet::Alphabet;
+use crate::options::TextOptions;
+use crate::cipher::Cipher;
 use super::{encrypt as m_encrypt, decrypt as m_decrypt};
 
-pub fn encrypt(phrase: &str, key: &str) -> Result<String, Box<dyn Error>> {
+pub fn encrypt(phrase: &str, key: &str) -> Result<String, CipherError> {
     let alphabet: Alphabet = Alphabet::new();
     m_encrypt(&alphabet, phrase, key)
 }
 
-pub fn decrypt(phrase: &str, key: &str) -> Result<String, Box<dyn Error>> {
+pub fn decrypt(phrase: &str, key: &str) -> Result<String, CipherError> {
     let alphabet: Alphabet = Alphabet::new();
     m_decrypt(&alphabet, phrase, key)
 }
 
+pub fn encrypt_with_alphabet(alphabet: &Alphabet, phrase: &str, key: &str) -> Result<String, CipherError> {
+    m_encrypt(alphabet, phrase, key)
+}
+
+pub fn decrypt_with_alphabet(alphabet: &Alphabet, phrase: &str, key: &str) -> Result<String, CipherError> {
+    m_decrypt(alphabet, phrase, key)
+}
+
+pub struct Belazo;
+
+impl Cipher for Belazo {
+    type Key = String;
+
+    fn encrypt(&self, phrase: &str, key: &String) -> Result<String, CipherError> {
+        encrypt(phrase, key)
+    }
+
+    fn decrypt(&self, phrase: &str, key: &String) -> Result<String, CipherError> {
+        decrypt(phrase, key)
+    }
+}
+
+pub fn encrypt_with_options(
+    alphabet: &Alphabet, phrase: &str, key: &str, options: &TextOptions
+) -> Result<String, CipherError> {
+    options.apply(alphabet, phrase, |letters| encrypt_with_alphabet(alphabet, letters, key))
+}
+
+pub fn decrypt_with_options(
+    alphabet: &Alphabet, phrase: &str, key: &str, options: &TextOptions
+) -> Result<String, CipherError> {
+    options.apply(alphabet, phrase, |letters| decrypt_with_alphabet(alphabet, letters, key))
+}
+
 #[cfg(test)]
 mod belazo_tests {
     use super::*;
@@ -57,4 +93,19 @@ mod belazo_tests {
         decrypt(
             "я", "z").unwrap();
     }
+
+    #[test]
+    fn test_with_alphabet() {
+        let alphabet = Alphabet::latin();
+        assert_eq!(encrypt_with_alphabet(&alphabet, "attackatdawn", "lemon").unwrap(), "lxfopvefrnhr");
+        assert_eq!(decrypt_with_alphabet(&alphabet, "lxfopvefrnhr", "lemon").unwrap(), "attackatdawn");
+    }
+
+    #[test]
+    fn test_with_options() {
+        let options = TextOptions { pass_through: true, preserve_case: true };
+        let alphabet = Alphabet::new();
+        assert_eq!(encrypt_with_options(&alphabet, "Привет, Мир!", "ключ", &options).unwrap(), "Щыжщпэ, Кяъ!");
+        assert_eq!(decrypt_with_options(&alphabet, "Щыжщпэ, Кяъ!", "ключ", &options).unwrap(), "Привет, Мир!");
+    }
 }
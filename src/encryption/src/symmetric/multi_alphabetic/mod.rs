@@ -2,11 +2,11 @@ pub mod belazo;
 pub mod trithemium;
 pub mod vigenere;
 
-use std::error::Error;
+use crate::errors::CipherError;
 use crate::alphabet::Alphabet;
 use crate::methods::{modd, validate_two};
 
-fn proto(alphabet: &Alphabet, phrase: &str, key: &str, reverse: bool) -> Result<String, Box<dyn Error>> {
+fn proto(alphabet: &Alphabet, phrase: &str, key: &str, reverse: bool) -> Result<String, CipherError> {
     validate_two(alphabet, phrase, key)?;
     let mut cur_key: usize = 0;
     let key_length = key.chars().count();
@@ -24,10 +24,10 @@ fn proto(alphabet: &Alphabet, phrase: &str, key: &str, reverse: bool) -> Result<
     Ok(result)
 }
 
-fn encrypt (alphabet: &Alphabet, phrase: &str, key: &str) -> Result<String, Box<dyn Error>> {
+fn encrypt (alphabet: &Alphabet, phrase: &str, key: &str) -> Result<String, CipherError> {
     proto(alphabet, phrase, key, false)
 }
 
-fn decrypt (alphabet: &Alphabet, phrase: &str, key: &str) -> Result<String, Box<dyn Error>> {
+fn decrypt (alphabet: &Alphabet, phrase: &str, key: &str) -> Result<String, CipherError> {
     proto(alphabet, phrase, key, true)
 }
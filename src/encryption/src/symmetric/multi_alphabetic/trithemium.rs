@@ -1,17 +1,53 @@
-use std::error::Error;
+use crate::errors::CipherError;
 use crate::alphabet::Alphabet;
+use crate::options::TextOptions;
+use crate::cipher::Cipher;
 use super::{encrypt as m_encrypt, decrypt as m_decrypt};
 
-pub fn encrypt(phrase: &str) -> Result<String, Box<dyn Error>> {
+pub fn encrypt(phrase: &str) -> Result<String, CipherError> {
     let alphabet: Alphabet = Alphabet::new();
     m_encrypt(&alphabet, phrase, alphabet.alphabet())
 }
 
-pub fn decrypt(phrase: &str) -> Result<String, Box<dyn Error>> {
+pub fn decrypt(phrase: &str) -> Result<String, CipherError> {
     let alphabet: Alphabet = Alphabet::new();
     m_decrypt(&alphabet, phrase, alphabet.alphabet())
 }
 
+pub fn encrypt_with_alphabet(alphabet: &Alphabet, phrase: &str) -> Result<String, CipherError> {
+    m_encrypt(alphabet, phrase, alphabet.alphabet())
+}
+
+pub fn decrypt_with_alphabet(alphabet: &Alphabet, phrase: &str) -> Result<String, CipherError> {
+    m_decrypt(alphabet, phrase, alphabet.alphabet())
+}
+
+pub struct Trithemium;
+
+impl Cipher for Trithemium {
+    type Key = ();
+
+    fn encrypt(&self, phrase: &str, _key: &()) -> Result<String, CipherError> {
+        encrypt(phrase)
+    }
+
+    fn decrypt(&self, phrase: &str, _key: &()) -> Result<String, CipherError> {
+        decrypt(phrase)
+    }
+}
+
+pub fn encrypt_with_options(
+    alphabet: &Alphabet, phrase: &str, options: &TextOptions
+) -> Result<String, CipherError> {
+    options.apply(alphabet, phrase, |letters| encrypt_with_alphabet(alphabet, letters))
+}
+
+pub fn decrypt_with_options(
+    alphabet: &Alphabet, phrase: &str, options: &TextOptions
+) -> Result<String, CipherError> {
+    options.apply(alphabet, phrase, |letters| decrypt_with_alphabet(alphabet, letters))
+}
+
 #[cfg(test)]
 mod trithemium_tests {
     use super::*;
@@ -39,4 +75,20 @@ mod trithemium_tests {
     fn test_decrypt_panic() {
         decrypt("z").unwrap();
     }
+
+    #[test]
+    fn test_with_alphabet() {
+        let alphabet = Alphabet::latin();
+        assert_eq!(encrypt_with_alphabet(&alphabet, "aaaa").unwrap(), "abcd");
+        assert_eq!(decrypt_with_alphabet(&alphabet, "abcd").unwrap(), "aaaa");
+    }
+
+    #[test]
+    fn test_with_options() {
+        let options = TextOptions { pass_through: true, preserve_case: true };
+        let alphabet = Alphabet::new();
+        let result = encrypt_with_options(&alphabet, "Привет, Мир!", &options).unwrap();
+        assert_eq!(result.chars().filter(|letter| !letter.is_alphabetic()).collect::<String>(), ", !");
+        assert_eq!(decrypt_with_options(&alphabet, &result, &options).unwrap(), "Привет, Мир!");
+    }
 }
@@ -1,20 +1,27 @@
-use std::error::Error;
+use crate::errors::CipherError;
 use crate::alphabet::Alphabet;
-use crate::errors::{InvalidSize, NullSizedValue};
+use crate::options::TextOptions;
+use crate::cipher::Cipher;
 use crate::methods::modd;
 use super::encrypt as m_encrypt;
 
-pub fn encrypt(phrase: &str, key: &str) -> Result<String, Box<dyn Error>> {
-    let alphabet = Alphabet::new();
-    validate(&alphabet, phrase, key)?;
+pub fn encrypt(phrase: &str, key: &str) -> Result<String, CipherError> {
+    encrypt_with_alphabet(&Alphabet::new(), phrase, key)
+}
+
+pub fn decrypt(phrase: &str, key: &str) -> Result<String, CipherError> {
+    decrypt_with_alphabet(&Alphabet::new(), phrase, key)
+}
+
+pub fn encrypt_with_alphabet(alphabet: &Alphabet, phrase: &str, key: &str) -> Result<String, CipherError> {
+    validate(alphabet, phrase, key)?;
     let mut key = key.to_owned();
     key.push_str(phrase);
-    m_encrypt(&alphabet, phrase, &key)
+    m_encrypt(alphabet, phrase, &key)
 }
 
-pub fn decrypt(phrase: &str, key: &str) -> Result<String, Box<dyn Error>> {
-    let alphabet = Alphabet::new();
-    validate(&alphabet, phrase, key)?;
+pub fn decrypt_with_alphabet(alphabet: &Alphabet, phrase: &str, key: &str) -> Result<String, CipherError> {
+    validate(alphabet, phrase, key)?;
     let mut buffer = key.chars().next().unwrap();
     let result = phrase.chars().map(move |letter| {
             buffer = alphabet.get(modd(
@@ -26,12 +33,38 @@ pub fn decrypt(phrase: &str, key: &str) -> Result<String, Box<dyn Error>> {
     Ok(result)
 }
 
-fn validate(alphabet: &Alphabet, phrase: &str, key: &str) -> Result<(), Box<dyn Error>> {
-    if phrase.is_empty() { return Err(Box::new(NullSizedValue::new("Фраза"))); }
-    if key.is_empty() { return Err(Box::new(NullSizedValue::new("Ключ"))); }
-    if key.chars().count() > 1 { return Err(Box::new(InvalidSize::new("Ключ должен быть одной буквой"))); }
+pub struct Vigenere;
+
+impl Cipher for Vigenere {
+    type Key = char;
+
+    fn encrypt(&self, phrase: &str, key: &char) -> Result<String, CipherError> {
+        encrypt(phrase, &key.to_string())
+    }
+
+    fn decrypt(&self, phrase: &str, key: &char) -> Result<String, CipherError> {
+        decrypt(phrase, &key.to_string())
+    }
+}
+
+fn validate(alphabet: &Alphabet, phrase: &str, key: &str) -> Result<(), CipherError> {
+    if phrase.is_empty() { return Err(CipherError::EmptyValue { param: "Фраза" }); }
+    if key.is_empty() { return Err(CipherError::EmptyValue { param: "Ключ" }); }
+    if key.chars().count() > 1 { return Err(CipherError::invalid_size("Ключ", "Ключ должен быть одной буквой")); }
     alphabet.validate(phrase)?;
-    alphabet.validate(key)
+    alphabet.validate_param(key, "Ключ")
+}
+
+pub fn encrypt_with_options(
+    alphabet: &Alphabet, phrase: &str, key: &str, options: &TextOptions
+) -> Result<String, CipherError> {
+    options.apply(alphabet, phrase, |letters| encrypt_with_alphabet(alphabet, letters, key))
+}
+
+pub fn decrypt_with_options(
+    alphabet: &Alphabet, phrase: &str, key: &str, options: &TextOptions
+) -> Result<String, CipherError> {
+    options.apply(alphabet, phrase, |letters| decrypt_with_alphabet(alphabet, letters, key))
 }
 
 #[cfg(test)]
@@ -87,4 +120,21 @@ mod vigenere_tests {
     fn test_decrypt_key_length_panic() {
         decrypt("я", "юю").unwrap();
     }
+
+    #[test]
+    fn test_with_alphabet() {
+        let alphabet = Alphabet::latin();
+        let result = encrypt_with_alphabet(&alphabet, "attack", "q").unwrap();
+        assert_eq!(result, "qtmtcm");
+        assert_eq!(decrypt_with_alphabet(&alphabet, &result, "q").unwrap(), "attack");
+    }
+
+    #[test]
+    fn test_with_options() {
+        let options = TextOptions { pass_through: true, preserve_case: true };
+        let alphabet = Alphabet::new();
+        let result = encrypt_with_options(&alphabet, "Привет, Мир!", "к", &options).unwrap();
+        assert_eq!(result.chars().filter(|letter| !letter.is_alphabetic()).collect::<String>(), ", !");
+        assert_eq!(decrypt_with_options(&alphabet, &result, "к", &options).unwrap(), "Привет, Мир!");
+    }
 }
@@ -1,8 +1,8 @@
-use std::error::Error;
+use crate::errors::CipherError;
 use std::str::Chars;
 use rand::Rng;
 use crate::alphabet::Alphabet;
-use crate::errors::{InvalidSize, NullSizedValue};
+use crate::cipher::Cipher;
 
 struct CardanoTable {
     rows: usize,
@@ -16,7 +16,7 @@ impl CardanoTable {
         CardanoTable { rows, cols, grid, data: vec!['\u{0}'; rows*cols] }
     }
 
-    fn validate(&self) -> Result<(), Box<dyn Error>> {
+    fn validate(&self) -> Result<(), CipherError> {
         let t1 = Self::new(self.rows, self.cols, self.grid.clone());
         let mut t2 = Self::new(t1.rows, t1.cols, t1.grid.clone());
         t2.reflect_vertical();
@@ -35,7 +35,7 @@ impl CardanoTable {
             let buffer = [*elem1, *elem2, *elem3, *elem4];
             let count = buffer.iter().filter(|elem| **elem).count();
             if count != 1 {
-                Err("Ячейки решетки накладываются друг на друга")?;
+                Err(CipherError::invalid_key("Решетка", "Ячейки решетки накладываются друг на друга"))?;
             }
         }
         Ok(())
@@ -70,8 +70,7 @@ impl CardanoTable {
         self.grid = result;
     }
 
-    fn fill_partial(&mut self, letter_iter: &mut Chars) {
-        let alphabet = Alphabet::new();
+    fn fill_partial(&mut self, alphabet: &Alphabet, letter_iter: &mut Chars) {
         for (i, flag) in self.grid.iter().enumerate() {
             if *flag {
                 *self.data.get_mut(i).unwrap() = if let Some(letter) = letter_iter.next() { letter }
@@ -80,13 +79,13 @@ impl CardanoTable {
         }
     }
 
-    fn fill(&mut self, phrase: &str, directions: &[bool]) -> String {
+    fn fill(&mut self, alphabet: &Alphabet, phrase: &str, directions: &[bool]) -> String {
         let mut letter_iter = phrase.chars();
         for direction in directions {
-            self.fill_partial(&mut letter_iter);
+            self.fill_partial(alphabet, &mut letter_iter);
             self.reflect(*direction);
         }
-        self.fill_partial(&mut letter_iter);
+        self.fill_partial(alphabet, &mut letter_iter);
         self.data.iter().collect()
     }
 
@@ -110,18 +109,30 @@ impl CardanoTable {
 }
 
 pub fn encrypt(phrase: &str, grid: Vec<bool>, rows: usize, cols: usize, dirs: Vec<bool>)
-    -> Result<String, Box<dyn Error>>
+    -> Result<String, CipherError>
 {
-    validate(phrase, &grid, rows, cols, &dirs)?;
-    let mut table = CardanoTable::new(rows, cols, grid);
-    table.validate()?;
-    Ok(table.fill(phrase, &dirs))
+    encrypt_with_alphabet(&Alphabet::new(), phrase, grid, rows, cols, dirs)
 }
 
 pub fn decrypt(phrase: &str, grid: Vec<bool>, rows: usize, cols: usize, dirs: Vec<bool>)
-    -> Result<String, Box<dyn Error>>
+    -> Result<String, CipherError>
 {
-    validate(phrase, &grid, rows, cols, &dirs)?;
+    decrypt_with_alphabet(&Alphabet::new(), phrase, grid, rows, cols, dirs)
+}
+
+pub fn encrypt_with_alphabet(
+    alphabet: &Alphabet, phrase: &str, grid: Vec<bool>, rows: usize, cols: usize, dirs: Vec<bool>
+) -> Result<String, CipherError> {
+    validate(alphabet, phrase, &grid, rows, cols, &dirs)?;
+    let mut table = CardanoTable::new(rows, cols, grid);
+    table.validate()?;
+    Ok(table.fill(alphabet, phrase, &dirs))
+}
+
+pub fn decrypt_with_alphabet(
+    alphabet: &Alphabet, phrase: &str, grid: Vec<bool>, rows: usize, cols: usize, dirs: Vec<bool>
+) -> Result<String, CipherError> {
+    validate(alphabet, phrase, &grid, rows, cols, &dirs)?;
     let mut table = CardanoTable::new_data(
         rows, cols, grid, validate_data(phrase, rows, cols)?
     );
@@ -129,30 +140,52 @@ pub fn decrypt(phrase: &str, grid: Vec<bool>, rows: usize, cols: usize, dirs: Ve
     Ok(table.extract(&dirs))
 }
 
-fn validate(phrase: &str, grid: &[bool], rows: usize, cols: usize, dirs: &[bool])
-    -> Result<(), Box<dyn Error>>
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CardanoKey {
+    pub grid: Vec<bool>,
+    pub rows: usize,
+    pub cols: usize,
+    pub dirs: Vec<bool>
+}
+
+pub struct Cardano;
+
+impl Cipher for Cardano {
+    type Key = CardanoKey;
+
+    fn encrypt(&self, phrase: &str, key: &CardanoKey) -> Result<String, CipherError> {
+        encrypt(phrase, key.grid.clone(), key.rows, key.cols, key.dirs.clone())
+    }
+
+    fn decrypt(&self, phrase: &str, key: &CardanoKey) -> Result<String, CipherError> {
+        decrypt(phrase, key.grid.clone(), key.rows, key.cols, key.dirs.clone())
+    }
+}
+
+fn validate(alphabet: &Alphabet, phrase: &str, grid: &[bool], rows: usize, cols: usize, dirs: &[bool])
+    -> Result<(), CipherError>
 {
-    let alphabet = Alphabet::new();
-    if phrase.is_empty() { Err(NullSizedValue::new("Фраза"))?; }
+    if phrase.is_empty() { Err(CipherError::EmptyValue { param: "Фраза" })?; }
     if rows*cols < phrase.chars().count() {
-        Err(InvalidSize::new("Фраза должна быть меньше размера решетки"))?;
+        Err(CipherError::invalid_size("Фраза", "Фраза должна быть меньше размера решетки"))?;
     }
-    if rows % 2 != 0 || cols % 2 != 0 {
-        Err(InvalidSize::new("Размеры решетки должны быть кратны 2"))?;
+    if !rows.is_multiple_of(2) || !cols.is_multiple_of(2) {
+        Err(CipherError::invalid_size("Решетка", "Размеры решетки должны быть кратны 2"))?;
     }
     if grid.iter().filter(|x| **x).count() != rows * cols / 4 {
-        Err(InvalidSize::new("Количество выколотых ячеек в решетке должно быть равно произведению размеров решетки деленому на 4"))?;
+        Err(CipherError::invalid_size("Решетка", "Количество выколотых ячеек в решетке должно быть равно произведению размеров решетки деленому на 4"))?;
     }
     if dirs.len() != 3 {
-        Err(InvalidSize::new("Количество отражений должно быть равно 3"))?;
+        Err(CipherError::invalid_size("Отражения", "Количество отражений должно быть равно 3"))?;
     }
     alphabet.validate(phrase)?;
     Ok(())
 }
 
-fn validate_data(data: &str, rows: usize, cols: usize) -> Result<Vec<char>, Box<dyn Error>> {
+fn validate_data(data: &str, rows: usize, cols: usize) -> Result<Vec<char>, CipherError> {
     if data.chars().count() != rows*cols {
-        Err(InvalidSize::new("Длина фразы должна совпадать с размером решетки"))?;
+        Err(CipherError::invalid_size("Фраза", "Длина фразы должна совпадать с размером решетки"))?;
     }
     Ok(data.chars().collect())
 }
@@ -165,7 +198,7 @@ mod cardano_tests {
     fn test_fill() {
         let mut table = CardanoTable::new(2, 2, vec![false, true, false, false]);
         let directions = vec![true, false, true];
-        let result = table.fill("окно", &directions);
+        let result = table.fill(&Alphabet::new(), "окно", &directions);
         assert_eq!(result, "коно");
     }
 
@@ -207,4 +240,18 @@ mod cardano_tests {
         ).unwrap();
         assert_eq!(result, "окноокноокноокно");
     }
+
+    #[test]
+    fn test_with_alphabet() {
+        let alphabet = Alphabet::latin();
+        let grid = vec![false, true, false, true,
+                        true, false, true, false,
+                        false, false, false, false,
+                        false, false, false, false];
+        let dirs = vec![true, false, true];
+        let result = encrypt_with_alphabet(&alphabet, "attackatdawn", grid.clone(), 4, 4, dirs.clone()).unwrap();
+        assert!(result.chars().all(|letter| alphabet.contains(letter)));
+        let result = decrypt_with_alphabet(&alphabet, &result, grid, 4, 4, dirs).unwrap();
+        assert!(result.starts_with("attackatdawn"));
+    }
 }
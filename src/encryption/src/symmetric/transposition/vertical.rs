@@ -1,9 +1,11 @@
 use std::collections::HashSet;
-use std::error::Error;
+use crate::errors::CipherError;
 use crate::alphabet::Alphabet;
-use crate::errors::{InvalidIndex, InvalidKeyError};
+use crate::options::TextOptions;
+use crate::cipher::Cipher;
+use crate::methods::validate_two;
 
-fn get_order (alphabet: &Alphabet, key: &str) -> Result<Vec<usize>, Box<dyn Error>> {
+fn get_order (alphabet: &Alphabet, key: &str) -> Result<Vec<usize>, CipherError> {
     let mut result: Vec<usize> = vec![0; key.chars().count()];
     let mut letter_indexes: Vec<usize> = key.chars().map(|letter| alphabet.index_of(letter)).collect();
     let positions: HashSet<usize> = letter_indexes.iter().cloned().collect();
@@ -12,7 +14,7 @@ fn get_order (alphabet: &Alphabet, key: &str) -> Result<Vec<usize>, Box<dyn Erro
     let mut i: usize = 0;
     for position in positions {
         while letter_indexes.contains(&position) {
-            let pos = letter_indexes.iter().position(|x| x == &position).ok_or(InvalidIndex)?;
+            let pos = letter_indexes.iter().position(|x| x == &position).ok_or(CipherError::InvalidIndex)?;
             if let Some(elem) = result.get_mut(pos) { *elem = i + 1; }
             if let Some(elem) = letter_indexes.get_mut(pos) { *elem = alphabet.len(); }
             i += 1;
@@ -25,11 +27,11 @@ fn get_rows_and_keys(
     alphabet: &Alphabet,
     phrase: &str,
     key: &str
-) -> Result<(Vec<usize>, usize), Box<dyn Error>> {
+) -> Result<(Vec<usize>, usize), CipherError> {
     let phrase_len = phrase.chars().count();
     let keys = get_order(alphabet, key)?;
     let row_o = phrase_len / keys.len();
-    let row = if phrase_len % keys.len() != 0 {row_o + 1} else {row_o};
+    let row = if !phrase_len.is_multiple_of(keys.len()) {row_o + 1} else {row_o};
     Ok((keys, row))
 }
 
@@ -55,13 +57,13 @@ fn get_empty_slots(keys: &[usize], last_row: usize) -> Option<Vec<usize>> {
 }
 
 fn get_result(alphabet: &Alphabet, buffer: &[Vec<isize>], row: usize, col: usize)
-    -> Result<String, Box<dyn Error>>
+    -> Result<String, CipherError>
 {
     let mut result = String::new();
     for i in 0..row {
         for j in 0..col {
             let index = *buffer.get(j)
-                .ok_or(InvalidIndex)?.get(i).ok_or(InvalidIndex)?;
+                .ok_or(CipherError::InvalidIndex)?.get(i).ok_or(CipherError::InvalidIndex)?;
             if index != -1 {
                 result.push(alphabet.get(index as usize));
             }
@@ -70,9 +72,17 @@ fn get_result(alphabet: &Alphabet, buffer: &[Vec<isize>], row: usize, col: usize
     Ok(result)
 }
 
-pub fn encrypt(phrase: &str, key: &str) -> Result<String, Box<dyn Error>> {
-    let alphabet = Alphabet::new();
-    let (keys, row) = get_rows_and_keys(&alphabet, phrase, key)?;
+pub fn encrypt(phrase: &str, key: &str) -> Result<String, CipherError> {
+    encrypt_with_alphabet(&Alphabet::new(), phrase, key)
+}
+
+pub fn decrypt(phrase: &str, key: &str) -> Result<String, CipherError> {
+    decrypt_with_alphabet(&Alphabet::new(), phrase, key)
+}
+
+pub fn encrypt_with_alphabet(alphabet: &Alphabet, phrase: &str, key: &str) -> Result<String, CipherError> {
+    validate_two(alphabet, phrase, key)?;
+    let (keys, row) = get_rows_and_keys(alphabet, phrase, key)?;
     let mut buffer: Vec<Vec<isize>> = vec![Vec::new(); keys.len()];
     let mut letter = phrase.chars();
     for _ in 0..row {
@@ -81,27 +91,27 @@ pub fn encrypt(phrase: &str, key: &str) -> Result<String, Box<dyn Error>> {
                 Some(letter) => alphabet.index_of(letter) as isize,
                 None => -1
             };
-            buffer.get_mut(j).ok_or(InvalidIndex)?.push(val);
+            buffer.get_mut(j).ok_or(CipherError::InvalidIndex)?.push(val);
         }
     }
     sort(&mut keys.clone(), &mut buffer);
-    get_result(&alphabet, &buffer, row, keys.len())
+    get_result(alphabet, &buffer, row, keys.len())
 }
 
-fn prepare_keys(keys: &[usize]) -> Result<Vec<usize>, Box<dyn Error>> {
+fn prepare_keys(keys: &[usize]) -> Result<Vec<usize>, CipherError> {
     let mut result: Vec<usize> = vec![0; keys.len()];
     for (i, elem) in keys.iter().enumerate() {
         match result.get_mut(*elem - 1) {
             Some(elem) => *elem = i + 1,
-            None => Err(InvalidKeyError::new("Ключ содержит невалидные значения"))?
+            None => Err(CipherError::invalid_key("Ключ", "Ключ содержит невалидные значения"))?
         };
     }
     Ok(result)
 }
 
-pub fn decrypt(phrase: &str, key: &str) -> Result<String, Box<dyn Error>> {
-    let alphabet = Alphabet::new();
-    let (keys, row) = get_rows_and_keys(&alphabet, phrase, key)?;
+pub fn decrypt_with_alphabet(alphabet: &Alphabet, phrase: &str, key: &str) -> Result<String, CipherError> {
+    validate_two(alphabet, phrase, key)?;
+    let (keys, row) = get_rows_and_keys(alphabet, phrase, key)?;
     let mut buffer: Vec<Vec<isize>> = vec![Vec::new(); keys.len()];
     let mut letter = phrase.chars();
     let empty_slots = get_empty_slots(
@@ -112,17 +122,43 @@ pub fn decrypt(phrase: &str, key: &str) -> Result<String, Box<dyn Error>> {
     for i in 0..row {
         for j in 0..keys.len() {
             let elem = empty_slots.as_ref().and_then(|x| x.get(k).copied());
-            let val = if i + 1 == row && elem.is_some() && elem.ok_or(InvalidIndex)? - 1 == j {
+            let val = if i + 1 == row && elem.is_some() && elem.ok_or(CipherError::InvalidIndex)? - 1 == j {
                 k += 1;
                 -1
             } else {
                 letter.next().map_or(-1, |letter| alphabet.index_of(letter) as isize)
             };
-            buffer.get_mut(j).ok_or(InvalidIndex)?.push(val);
+            buffer.get_mut(j).ok_or(CipherError::InvalidIndex)?.push(val);
         }
     }
     sort(&mut prepare_keys(&keys)?, &mut buffer);
-    get_result(&alphabet, &buffer, row, keys.len())
+    get_result(alphabet, &buffer, row, keys.len())
+}
+
+pub struct Vertical;
+
+impl Cipher for Vertical {
+    type Key = String;
+
+    fn encrypt(&self, phrase: &str, key: &String) -> Result<String, CipherError> {
+        encrypt(phrase, key)
+    }
+
+    fn decrypt(&self, phrase: &str, key: &String) -> Result<String, CipherError> {
+        decrypt(phrase, key)
+    }
+}
+
+pub fn encrypt_with_options(
+    alphabet: &Alphabet, phrase: &str, key: &str, options: &TextOptions
+) -> Result<String, CipherError> {
+    options.apply(alphabet, phrase, |letters| encrypt_with_alphabet(alphabet, letters, key))
+}
+
+pub fn decrypt_with_options(
+    alphabet: &Alphabet, phrase: &str, key: &str, options: &TextOptions
+) -> Result<String, CipherError> {
+    options.apply(alphabet, phrase, |letters| decrypt_with_alphabet(alphabet, letters, key))
 }
 
 #[cfg(test)]
@@ -233,4 +269,21 @@ mod vetrical_tests {
         let result = decrypt(&phrase, &key).unwrap();
         assert_eq!(result, "отодногопорченогоябло");
     }
+
+    #[test]
+    fn test_with_alphabet() {
+        let alphabet = Alphabet::latin();
+        let result = encrypt_with_alphabet(&alphabet, "wearediscovered", "zebras").unwrap();
+        assert_eq!(result, "eaerdwvcsoeider");
+        assert_eq!(decrypt_with_alphabet(&alphabet, &result, "zebras").unwrap(), "wearediscovered");
+    }
+
+    #[test]
+    fn test_with_options() {
+        let options = TextOptions { pass_through: true, preserve_case: true };
+        let alphabet = Alphabet::new();
+        let result = encrypt_with_options(&alphabet, "Привет, Мир!", "ключ", &options).unwrap();
+        assert_eq!(result.chars().filter(|letter| !letter.is_alphabetic()).collect::<String>(), ", !");
+        assert_eq!(decrypt_with_options(&alphabet, &result, "ключ", &options).unwrap(), "Привет, Мир!");
+    }
 }
@@ -23,7 +23,6 @@ use gtk::glib;
 use gtk::prelude::*;
 
 mod imp {
-    use std::error::Error;
     use gtk::{Button, template_callbacks};
     use gtk::prelude::WidgetExt;
     use crate::ui::entry::UIEntry;
@@ -31,6 +30,7 @@ mod imp {
     use crate::ui::text_view::UITextView;
     use crate::window::GCiphersRsWindow;
 
+    use encryption::CipherError;
     use encryption::a5_1::*;
 
     use super::*;
@@ -67,7 +67,7 @@ mod imp {
     #[template_callbacks]
     impl GCiphersRsA51 {
         fn call_p<T>(&self, action: T)
-            where T: Fn(&str, &str) -> Result<String, Box<dyn Error>>
+            where T: Fn(&GCiphersRsWindow, &str, &str) -> Result<String, CipherError>
         {
             let root = self.obj().root().expect("Не удалось получить окно");
             let window = root
@@ -77,7 +77,7 @@ mod imp {
                 .expect("Приведение не удалось");
             let text = self.text_view.get().get_text();
             let key = self.key.get().text().to_string();
-            let result = match action(&text, &key) {
+            let result = match action(window, &text, &key) {
                 Ok(res) => Some(res),
                 Err(e) => {
                     window.show_message(&e.to_string());
@@ -91,12 +91,12 @@ mod imp {
 
         #[template_callback]
         fn on_encrypt_click(&self, _button: &Button) {
-            self.call_p(encrypt)
+            self.call_p(|window, text, key| window.encode_output(&encrypt(text, key)?))
         }
 
         #[template_callback]
         fn on_decrypt_click(&self, _button: &Button) {
-            self.call_p(decrypt)
+            self.call_p(|window, text, key| decrypt(&window.decode_input(text)?, key))
         }
     }
 }
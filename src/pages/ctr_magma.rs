@@ -32,6 +32,7 @@ mod imp {
 
     use encryption::magma::ctr_magma;
     use encryption::methods::{bytes_to_hex, bytes_to_string, hex_to_bytes, str_to_bytes};
+    use encryption::registry::Operation;
 
     use super::*;
 
@@ -89,8 +90,7 @@ mod imp {
         #[template_callback]
         fn on_encrypt_click(&self, _button: &Button) {
             self.call_p(|window, text, key, iv| {
-                if key.chars().count() != 64 {
-                    window.show_message("Ключ должен состоять из 32 байт");
+                if !window.check_params("ctr_magma", Operation::Encrypt, &[key, iv]) {
                     return None;
                 }
                 let text = if window.get_prettify_state() {
@@ -102,7 +102,7 @@ mod imp {
                         }
                     })
                 } else { text.to_owned() };
-                Some(match ctr_magma(&text, iv, key) {
+                Some(match ctr_magma(&text, iv, key).and_then(|result| window.encode_output(&result)) {
                     Ok(result) => result,
                     Err(e) => {
                         window.show_message(&e.to_string());
@@ -115,11 +115,10 @@ mod imp {
         #[template_callback]
         fn on_decrypt_click(&self, _button: &Button) {
             self.call_p(|window, text, key, iv| {
-                if key.chars().count() != 64 {
-                    window.show_message("Ключ должен состоять из 32 байт");
+                if !window.check_params("ctr_magma", Operation::Decrypt, &[key, iv]) {
                     return None;
                 }
-                let result = match ctr_magma(text, iv, key) {
+                let result = match window.decode_input(text).and_then(|text| ctr_magma(&text, iv, key)) {
                     Ok(result) => result,
                     Err(e) => {
                         window.show_message(&e.to_string());
@@ -127,7 +126,7 @@ mod imp {
                     }
                 };
                 let result = if window.get_prettify_state() {
-                    match bytes_to_string(&hex_to_bytes(&result, 4).unwrap()) {
+                    match bytes_to_string(&hex_to_bytes(&result, 1).unwrap()) {
                         Ok(result) => result,
                         Err(e) => {
                             window.show_message(&e.to_string());
@@ -29,6 +29,8 @@ mod imp {
     use crate::window::GCiphersRsWindow;
 
     use encryption::diffie_hellman::*;
+    use encryption::registry;
+    use encryption::schema::Param;
     use crate::ui::entry::UIEntry;
 
     use super::*;
@@ -81,13 +83,43 @@ mod imp {
                 .downcast_ref::<GCiphersRsWindow>()
                 .expect("Приведение не удалось").clone()
         }
+
+        /// Schema of the parameter in the registry
+        fn param(name: &str) -> Param {
+            *registry::find("diffie_hellman")
+                .and_then(|info| info.param(name))
+                .expect("Параметр отсутствует в реестре")
+        }
+
+        /// Public keys take the constraints of the secret ones
+        fn public_param(name: &'static str, secret: &str) -> Param {
+            Param { name, ..Self::param(secret) }
+        }
+
+        fn read_numbers(&self, window: &GCiphersRsWindow, entries: &[(Param, &UIEntry)]) -> Option<Vec<usize>> {
+            let values: Vec<String> = entries.iter().map(|(_, entry)| entry.text().to_string()).collect();
+            let errors: Vec<String> = entries.iter().zip(values.iter())
+                .flat_map(|((param, _), value)| param.check(value))
+                .map(|e| e.to_string())
+                .collect();
+            if !errors.is_empty() {
+                window.show_message(&errors.join("\n"));
+                return None;
+            }
+            Some(values.iter().map(|value| value.trim().parse().expect("Значение проверено схемой")).collect())
+        }
         #[template_callback]
         fn on_key_count_click(&self, _button: &Button) {
             let window = self.get_window();
-            let n = self.n.get().text().to_string().parse::<usize>().expect("Нежданчик");
-            let a = self.a.get().text().to_string().parse::<usize>().expect("Нежданчик");
-            let ka = self.ka.get().text().to_string().parse::<usize>().expect("Нежданчик");
-            let (kb, yb) = match gen_keys(a, n) {
+            let Some(values) = self.read_numbers(
+                &window, &[
+                    (Self::param("n"), &self.n.get()),
+                    (Self::param("a"), &self.a.get()),
+                    (Self::param("Ka"), &self.ka.get()),
+                ]
+            ) else { return; };
+            let [n, a, ka] = values[..] else { return; };
+            let DiffieHellmanKeys { k: kb, y: yb } = match gen_keys(a, n) {
                 Ok(val) => val,
                 Err(e) => {
                     window.show_message(&e.to_string());
@@ -103,11 +135,14 @@ mod imp {
         #[template_callback]
         fn on_key_exchange_click(&self, _button: &Button) {
             let window = self.get_window();
-            let ka = self.ka.get().text().to_string().parse::<usize>().expect("Нежданчик");
-            let kb = self.kb.get().text().to_string().parse::<usize>().expect("Нежданчик");
-            let ya = self.ya.get().text().to_string().parse::<usize>().expect("Нежданчик");
-            let yb = self.yb.get().text().to_string().parse::<usize>().expect("Нежданчик");
-            let n = self.n.get().text().to_string().parse::<usize>().expect("Нежданчик");
+            let Some(values) = self.read_numbers(&window, &[
+                (Self::param("Ka"), &self.ka.get()),
+                (Self::param("Kb"), &self.kb.get()),
+                (Self::public_param("Ya", "Ka"), &self.ya.get()),
+                (Self::public_param("Yb", "Kb"), &self.yb.get()),
+                (Self::param("n"), &self.n.get()),
+            ]) else { return; };
+            let [ka, kb, ya, yb, n] = values[..] else { return; };
             let k1 = get_k(n, ka, yb);
             let k2 = get_k(n, kb, ya);
             if k1 == 1 || k2 == 1 {
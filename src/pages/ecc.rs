@@ -126,7 +126,7 @@ mod imp {
 
         #[template_callback]
         fn on_gen_click(&self, _button: &Button) {
-            let (g, q, cb, db) = get_keys();
+            let EccKeyPair { g, q, secret: cb, open: db } = get_keys();
             self.a.get().set_text(&g.a.to_string());
             self.b.get().set_text(&g.b.to_string());
             self.p.get().set_text(&g.modula.to_string());
@@ -152,9 +152,20 @@ mod imp {
                             return None;
                         }
                     };
-                    Some(enc(m as isize, &db, &g, 5, q).to_string())
+                    let value = enc(m as isize, &db, &g, 5, q);
+                    match window.get_encoding() {
+                        Some(encoding) => {
+                            let (x, y) = value.point.get_x_y();
+                            Some(encoding.encode_numbers(&[x, y, value.value], g.modula))
+                        }
+                        None => Some(value.to_string())
+                    }
                 } else {
-                    match encrypt(&window.mask_text(text), db, g, q) {
+                    let result = match window.get_encoding() {
+                        Some(encoding) => encrypt_with_encoding(&window.mask_text(text), db, g, q, encoding),
+                        None => encrypt(&window.mask_text(text), db, g, q)
+                    };
+                    match result {
                         Ok(res) => Some(res),
                         Err(e) => {
                             window.show_message(&e.to_string());
@@ -169,10 +180,35 @@ mod imp {
         fn on_decrypt_click(&self, _button: &Button) {
             self.call_p(|window, text, _, _, _, a, b, p, cb| {
                 if !window.get_prettify_state() {
-                    let val = CipherValue::new(text, a, b, p);
+                    let val = match window.get_encoding() {
+                        Some(encoding) => match encoding.decode_numbers(text, p) {
+                            Ok(numbers) => match numbers[..] {
+                                [x, y, value] => CipherValue { point: Point::new(a, b, x, y, p), value },
+                                _ => {
+                                    window.show_message("Шифртекст должен содержать три числа");
+                                    return None;
+                                }
+                            },
+                            Err(e) => {
+                                window.show_message(&e.to_string());
+                                return None;
+                            }
+                        },
+                        None => match CipherValue::new(text, a, b, p) {
+                            Ok(val) => val,
+                            Err(e) => {
+                                window.show_message(&e.to_string());
+                                return None;
+                            }
+                        }
+                    };
                     Some(dec(cb, val, p).to_string())
                 } else {
-                    match decrypt(text, cb, a, b, p) {
+                    let result = match window.get_encoding() {
+                        Some(encoding) => decrypt_with_encoding(text, cb, a, b, p, encoding),
+                        None => decrypt(text, cb, a, b, p)
+                    };
+                    match result {
                         Ok(res) => Some(window.demask_text(&res)),
                         Err(e) => {
                             window.show_message(&e.to_string());
@@ -31,6 +31,7 @@ mod imp {
     use crate::window::GCiphersRsWindow;
 
     use encryption::egsa::*;
+    use encryption::elgamal::ElgamalKeyPair;
 
     use super::*;
 
@@ -85,7 +86,7 @@ mod imp {
                 usize,
                 usize,
                 usize,
-                (usize, usize),
+                &str,
             ) -> Option<String>,
         {
             let root = self.obj().root().expect("Не удалось получить окно");
@@ -100,25 +101,9 @@ mod imp {
             let g = self.g.get().text().to_string().parse::<usize>();
             let y = self.y.get().text().to_string().parse::<usize>();
             let modula = self.modula.get().text().to_string().parse::<usize>();
-            let s = if self.sign_val.get().text().is_empty() {
-                (Ok(0), Ok(0))
-            } else {
-                let [ref a, ref b] = self
-                    .sign_val
-                    .get()
-                    .text()
-                    .to_string()
-                    .split(',')
-                    .map(|x| x.parse::<usize>())
-                    .collect::<Vec<Result<usize, _>>>()[..]
-                else {
-                    window.show_message("ЭЦП введена неверно");
-                    return;
-                };
-                (a.clone(), b.clone())
-            };
+            let s = self.sign_val.get().text().to_string();
             let mut args = Vec::new();
-            for elem in [p, x, g, y, modula, s.0, s.1].iter() {
+            for elem in [p, x, g, y, modula].iter() {
                 match elem {
                     Ok(elem) => args.push(*elem),
                     Err(e) => {
@@ -127,16 +112,7 @@ mod imp {
                     }
                 }
             }
-            let result = action(
-                window,
-                &text,
-                args[0],
-                args[1],
-                args[2],
-                args[3],
-                args[4],
-                (args[5], args[6]),
-            );
+            let result = action(window, &text, args[0], args[1], args[2], args[3], args[4], &s);
             if let Some(result) = result {
                 self.sign_val.get().set_text(&result);
             }
@@ -144,7 +120,7 @@ mod imp {
 
         #[template_callback]
         fn on_gen_click(&self, _button: &Button) {
-            let (p, x, g, y) = gen_keys();
+            let ElgamalKeyPair { p, x, g, y } = gen_keys();
             self.p.get().set_text(&p.to_string());
             self.x.get().set_text(&x.to_string());
             self.g.get().set_text(&g.to_string());
@@ -166,7 +142,7 @@ mod imp {
                         Some(5)
                     },
                 ) {
-                    Ok(res) => Some(format!("{},{}", res.0, res.1)),
+                    Ok(res) => Some(window.encode_signature(&res, p)),
                     Err(e) => {
                         window.show_message(&e.to_string());
                         None
@@ -178,7 +154,14 @@ mod imp {
         #[template_callback]
         fn on_check_sign_click(&self, _button: &Button) {
             self.call_p(|window, text, p, _, g, y, modula, s| {
-                match check_sign(&window.mask_text(text), p, g, y, modula, s) {
+                let s = match window.decode_signature(s, p) {
+                    Ok(s) => s,
+                    Err(e) => {
+                        window.show_message(&e.to_string());
+                        return None;
+                    }
+                };
+                match check_sign(&window.mask_text(text), p, g, y, modula, &s) {
                     Ok(true) => {
                         window.show_message("Подпись верна");
                         None
@@ -30,6 +30,7 @@ mod imp {
     use crate::ui::text_view::UITextView;
     use crate::window::GCiphersRsWindow;
 
+    use encryption::Encoding;
     use encryption::elgamal::*;
 
     use super::*;
@@ -80,7 +81,7 @@ mod imp {
                 .expect("Приведение не удалось")
                 .downcast_ref::<GCiphersRsWindow>()
                 .expect("Приведение не удалось");
-            let text = self.text_view.get().get_text().to_lowercase();
+            let text = self.text_view.get().get_text();
             let p = self.p.get().text().to_string().parse::<usize>();
             let x = self.x.get().text().to_string().parse::<usize>();
             let g = self.g.get().text().to_string().parse::<usize>();
@@ -103,7 +104,7 @@ mod imp {
 
         #[template_callback]
         fn on_gen_click(&self, _button: &Button) {
-            let (p, x, g, y) = gen_keys();
+            let ElgamalKeyPair { p, x, g, y } = gen_keys();
             self.p.get().set_text(&p.to_string());
             self.x.get().set_text(&x.to_string());
             self.g.get().set_text(&g.to_string());
@@ -116,7 +117,8 @@ mod imp {
                 let r = if !window.get_prettify_state() {
                     Some(vec![3, 11, 7])
                 } else { None };
-                match encrypt(&window.mask_text(text), p, g, y, r) {
+                let encoding = window.get_encoding().unwrap_or(Encoding::Decimal);
+                match encrypt_with_encoding(&window.mask_text(&text.to_lowercase()), p, g, y, r, encoding) {
                     Ok(res) => Some(res),
                     Err(e) => {
                         window.show_message(&e.to_string());
@@ -129,7 +131,8 @@ mod imp {
         #[template_callback]
         fn on_decrypt_click(&self, _button: &Button) {
             self.call_p(|window, text, p, x, _, _| {
-                match decrypt(text, p, x) {
+                let encoding = window.get_encoding().unwrap_or(Encoding::Decimal);
+                match decrypt_with_encoding(text, p, x, encoding) {
                     Ok(res) => Some(window.demask_text(&res)),
                     Err(e) => {
                         window.show_message(&e.to_string());
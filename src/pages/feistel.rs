@@ -23,7 +23,6 @@ use gtk::glib;
 use gtk::prelude::*;
 
 mod imp {
-    use std::error::Error;
     use gtk::{Button, template_callbacks};
     use gtk::prelude::WidgetExt;
     use crate::ui::entry::UIEntry;
@@ -31,8 +30,10 @@ mod imp {
     use crate::ui::text_view::UITextView;
     use crate::window::GCiphersRsWindow;
 
-    use encryption::magma::{encrypt, decrypt};
-    use encryption::methods::{hex_to_str, str_to_hex};
+    use encryption::{CipherError, Padding};
+    use encryption::feistel::{self, encrypt_traced, decrypt_traced, Network, BLOCK_SIZES, FUNCTIONS, SCHEDULES};
+    use encryption::methods::{padded_hex_to_str, str_to_padded_hex};
+    use encryption::registry::Operation;
 
     use super::*;
 
@@ -42,7 +43,19 @@ mod imp {
         #[template_child]
         pub text_view: TemplateChild<UITextView>,
         #[template_child]
-        pub key: TemplateChild<UIEntry>
+        pub key: TemplateChild<UIEntry>,
+        #[template_child]
+        pub block_size: TemplateChild<gtk::DropDown>,
+        #[template_child]
+        pub function: TemplateChild<gtk::DropDown>,
+        #[template_child]
+        pub schedule: TemplateChild<gtk::DropDown>,
+        #[template_child]
+        pub rounds: TemplateChild<UIEntry>,
+        #[template_child]
+        pub shift: TemplateChild<UIEntry>,
+        #[template_child]
+        pub trace: TemplateChild<gtk::Label>
     }
 
     #[glib::object_subclass]
@@ -61,7 +74,17 @@ mod imp {
         }
     }
 
-    impl ObjectImpl for GCiphersRsFeistel {}
+    impl ObjectImpl for GCiphersRsFeistel {
+        fn constructed(&self) {
+            self.parent_constructed();
+            let sizes: Vec<String> = BLOCK_SIZES.iter().map(|size| format!("{} бит", size * 8)).collect();
+            let sizes: Vec<&str> = sizes.iter().map(String::as_str).collect();
+            self.block_size.set_model(Some(&gtk::StringList::new(&sizes)));
+            self.block_size.set_selected(2);
+            self.function.set_model(Some(&gtk::StringList::new(&FUNCTIONS)));
+            self.schedule.set_model(Some(&gtk::StringList::new(&SCHEDULES)));
+        }
+    }
     impl WidgetImpl for GCiphersRsFeistel {}
     impl BinImpl for GCiphersRsFeistel {}
 
@@ -84,7 +107,7 @@ mod imp {
             }
         }
 
-        fn get_string(&self, res: Result<String, Box<dyn Error>>, window: &GCiphersRsWindow) -> Option<String> {
+        fn get_string(&self, res: Result<String, CipherError>, window: &GCiphersRsWindow) -> Option<String> {
             match res {
                 Ok(result) => Some(result),
                 Err(e) => {
@@ -94,42 +117,58 @@ mod imp {
             }
         }
 
+        /// Builds the network chosen by the user
+        fn get_network(&self, window: &GCiphersRsWindow, operation: Operation, key: &str) -> Option<Network> {
+            let block_size = BLOCK_SIZES.get(self.block_size.selected() as usize).copied().unwrap_or(8).to_string();
+            let rounds = self.rounds.get().text().to_string();
+            let function = self.function.selected().to_string();
+            let schedule = self.schedule.selected().to_string();
+            let shift = self.shift.get().text().to_string();
+            let shift = if shift.is_empty() { "0".to_string() } else { shift };
+            let args = [
+                key, block_size.as_str(), rounds.as_str(), function.as_str(), schedule.as_str(), shift.as_str()
+            ];
+            if !window.check_params("feistel", operation, &args) {
+                return None;
+            }
+            let network = feistel::network(
+                block_size.parse().ok()?, rounds.parse().ok()?, self.function.selected() as usize,
+                self.schedule.selected() as usize, shift.parse().ok()?
+            );
+            match network {
+                Ok(network) => Some(network),
+                Err(e) => {
+                    window.show_message(&e.to_string());
+                    None
+                }
+            }
+        }
+
         #[template_callback]
         fn on_encrypt_click(&self, _button: &Button) {
             self.call_p(|window, text, key| {
-                if key.chars().count() != 64 {
-                    window.show_message("Ключ должен состоять из 32 байт");
-                    return None;
-                }
+                let network = self.get_network(window, Operation::Encrypt, key)?;
                 let result = if window.get_prettify_state() {
-                    self.get_string(encrypt(&str_to_hex(text, 8), key), window)?
+                    let text = self.get_string(str_to_padded_hex(text, Padding::Pkcs7, network.block_size()), window)?;
+                    window.get_traced(encrypt_traced(&text, key, &network), &self.trace.get())?
                 } else {
-                    self.get_string(encrypt(text, key), window)?
+                    window.get_traced(encrypt_traced(text, key, &network), &self.trace.get())?
                 };
-                Some(result)
+                self.get_string(window.encode_output(&result), window)
             })
         }
 
         #[template_callback]
         fn on_decrypt_click(&self, _button: &Button) {
             self.call_p(|window, text, key| {
-                if key.chars().count() != 64 {
-                    window.show_message("Ключ должен состоять из 32 байт");
-                    return None;
-                }
-                let result = if window.get_prettify_state() {
-                    let result = match decrypt(text, key) {
-                        Ok(result) => result,
-                        Err(e) => {
-                            window.show_message(&e.to_string());
-                            return None;
-                        }
-                    };
-                    self.get_string(hex_to_str(&result), window)?
+                let network = self.get_network(window, Operation::Decrypt, key)?;
+                let text = &self.get_string(window.decode_input(text), window)?;
+                let result = window.get_traced(decrypt_traced(text, key, &network), &self.trace.get())?;
+                if window.get_prettify_state() {
+                    self.get_string(padded_hex_to_str(&result, Padding::Pkcs7, network.block_size()), window)
                 } else {
-                    self.get_string(decrypt(text, key), window)?
-                };
-                Some(result)
+                    Some(result)
+                }
             })
         }
     }
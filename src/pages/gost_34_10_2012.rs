@@ -30,6 +30,7 @@ mod imp {
     use crate::ui::text_view::UITextView;
     use crate::window::GCiphersRsWindow;
 
+    use encryption::ecc::EccKeyPair;
     use encryption::gost_r_34_10_2012::*;
 
     use super::*;
@@ -85,7 +86,7 @@ mod imp {
     impl GCiphersRsGOST2012 {
         #[template_callback]
         fn on_gen_click(&self, _button: &Button) {
-            let (g, q, cb, db) = get_keys();
+            let EccKeyPair { g, q, secret: cb, open: db } = get_keys();
             self.a.get().set_text(&g.a.to_string());
             self.b.get().set_text(&g.b.to_string());
             self.p.get().set_text(&g.modula.to_string());
@@ -147,7 +148,7 @@ mod imp {
         fn on_sing_click(&self, _button: &Button) {
             self.call_p(|window, text, _, g, q, cb, _| {
                 match sign(&window.mask_text(text), cb, g, q, 29) {
-                    Ok(res) => Some(res),
+                    Ok(res) => Some(window.encode_signature(&res, q)),
                     Err(e) => {
                         window.show_message(&e.to_string());
                         None
@@ -159,7 +160,9 @@ mod imp {
         #[template_callback]
         fn on_check_sign_click(&self, _button: &Button) {
             self.call_p(|window, text, db, g, q, _, sign| {
-                match check_sign(&window.mask_text(text), db, g, q, sign,29) {
+                let result = window.decode_signature(sign, q)
+                    .and_then(|sign| check_sign(&window.mask_text(text), db, g, q, &sign, 29));
+                match result {
                     Ok(true) => {
                         window.show_message("Подпись верна");
                         None
@@ -79,7 +79,7 @@ mod imp {
     impl GCiphersRsGOST94 {
         fn call_p<T>(&self, action: T)
             where
-                T: Fn(&GCiphersRsWindow, &str, usize, usize, usize, usize, usize, usize, (u128, u128)) -> Option<String>,
+                T: Fn(&GCiphersRsWindow, &str, usize, usize, usize, usize, usize, usize, &str) -> Option<String>,
         {
             let root = self.obj().root().expect("Не удалось получить окно");
             let window = root
@@ -94,14 +94,9 @@ mod imp {
             let x = self.x.get().text().to_string().parse::<usize>();
             let y = self.y.get().text().to_string().parse::<usize>();
             let modula = self.modula.get().text().to_string().parse::<usize>();
-            let (r, s) = if self.sign_val.get().text().is_empty() {
-                (Ok(0), Ok(0))
-            } else {
-                let s = self.sign_val.get().text().to_string().split(',').map(|x| String::from(x)).collect::<Vec<String>>();
-                (s[0].parse::<usize>(), s[1].parse::<usize>())
-            };
+            let s = self.sign_val.get().text().to_string();
             let mut args = Vec::new();
-            for elem in [p, q, a, x, y, modula, r, s].iter() {
+            for elem in [p, q, a, x, y, modula].iter() {
                 match elem {
                     Ok(elem) => args.push(*elem),
                     Err(e) => {
@@ -110,7 +105,7 @@ mod imp {
                     }
                 }
             }
-            let result = action(window, &text, args[2], args[0], args[1], args[3], args[4], args[5], (args[6] as u128, args[7] as u128));
+            let result = action(window, &text, args[2], args[0], args[1], args[3], args[4], args[5], &s);
             if let Some(result) = result {
                 self.sign_val.get().set_text(&result);
             }
@@ -121,7 +116,7 @@ mod imp {
             self.call_p(|window, text, a, p, q, x, _y, m, _| {
                 //let result = sign(&window.mask_text(text), a as u128, p as u128, x as u128, q as u128, m as u128);
                 match sign(&window.mask_text(text), a as u128, p as u128, x as u128, q as u128, m as u128) {
-                    Ok(result) => Some(format!("{},{}", result.0, result.1)),
+                    Ok(result) => Some(window.encode_signature(&result, q)),
                     Err(e) => {
                         window.show_message(&e.to_string());
                         None
@@ -133,7 +128,14 @@ mod imp {
         #[template_callback]
         fn on_check_sign_click(&self, _button: &Button) {
             self.call_p(|window, text, a, p, q, _x, y, m, rss| {
-                match check_sign(&window.mask_text(text), p as u128, q as u128, a as u128, y as u128, m as u128, rss) {
+                let rss = match window.decode_signature(rss, q) {
+                    Ok(rss) => rss,
+                    Err(e) => {
+                        window.show_message(&e.to_string());
+                        return None;
+                    }
+                };
+                match check_sign(&window.mask_text(text), p as u128, q as u128, a as u128, y as u128, m as u128, &rss) {
                     true => {
                         window.show_message("Подпись верна");
                         None
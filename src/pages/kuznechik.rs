@@ -23,7 +23,6 @@ use gtk::glib;
 use gtk::prelude::*;
 
 mod imp {
-    use std::error::Error;
     use gtk::{Button, template_callbacks};
     use gtk::prelude::WidgetExt;
     use crate::ui::entry::UIEntry;
@@ -31,8 +30,10 @@ mod imp {
     use crate::ui::text_view::UITextView;
     use crate::window::GCiphersRsWindow;
 
-    use encryption::kuznechik::{encrypt, decrypt};
-    use encryption::methods::{hex_to_str, str_to_hex};
+    use encryption::{CipherError, Padding};
+    use encryption::kuznechik::{encrypt_traced, decrypt_with_padding};
+    use encryption::methods::{hex_to_str, str_to_padded_hex};
+    use encryption::registry::Operation;
 
     use super::*;
 
@@ -42,7 +43,9 @@ mod imp {
         #[template_child]
         pub text_view: TemplateChild<UITextView>,
         #[template_child]
-        pub key: TemplateChild<UIEntry>
+        pub key: TemplateChild<UIEntry>,
+        #[template_child]
+        pub trace: TemplateChild<gtk::Label>
     }
 
     #[glib::object_subclass]
@@ -84,7 +87,7 @@ mod imp {
             }
         }
 
-        fn get_string(&self, res: Result<String, Box<dyn Error>>, window: &GCiphersRsWindow) -> Option<String> {
+        fn get_string(&self, res: Result<String, CipherError>, window: &GCiphersRsWindow) -> Option<String> {
             match res {
                 Ok(result) => Some(result),
                 Err(e) => {
@@ -97,37 +100,32 @@ mod imp {
         #[template_callback]
         fn on_encrypt_click(&self, _button: &Button) {
             self.call_p(|window, text, key| {
-                if key.chars().count() != 64 {
-                    window.show_message("Ключ должен состоять из 32 байт");
+                if !window.check_params("kuznechik", Operation::Encrypt, &[key]) {
                     return None;
                 }
                 let result = if window.get_prettify_state() {
-                    self.get_string(encrypt(&str_to_hex(text, 8), key), window)?
+                    let text = self.get_string(str_to_padded_hex(text, Padding::Pkcs7, 16), window)?;
+                    window.get_traced(encrypt_traced(&text, key), &self.trace.get())?
                 } else {
-                    self.get_string(encrypt(text, key), window)?
+                    window.get_traced(encrypt_traced(text, key), &self.trace.get())?
                 };
-                Some(result)
+                self.get_string(window.encode_output(&result), window)
             })
         }
 
         #[template_callback]
         fn on_decrypt_click(&self, _button: &Button) {
             self.call_p(|window, text, key| {
-                if key.chars().count() != 64 {
-                    window.show_message("Ключ должен состоять из 32 байт");
+                window.clear_trace(&self.trace.get());
+                if !window.check_params("kuznechik", Operation::Decrypt, &[key]) {
                     return None;
                 }
+                let text = &self.get_string(window.decode_input(text), window)?;
                 let result = if window.get_prettify_state() {
-                    let result = match decrypt(text, key) {
-                        Ok(result) => result,
-                        Err(e) => {
-                            window.show_message(&e.to_string());
-                            return None;
-                        }
-                    };
-                    self.get_string(hex_to_str(&result), window)?
+                    let result = decrypt_with_padding(text, key, Padding::Pkcs7).and_then(|result| hex_to_str(&result));
+                    self.get_string(result, window)?
                 } else {
-                    self.get_string(decrypt(text, key), window)?
+                    self.get_string(decrypt_with_padding(text, key, Padding::Zero), window)?
                 };
                 Some(result)
             })
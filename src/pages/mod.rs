@@ -15,6 +15,8 @@ pub mod ctr_magma;
 pub mod a5_1;
 pub mod a5_2;
 pub mod magma;
+pub mod des;
+pub mod triple_des;
 pub mod kuznechik;
 pub mod aes;
 pub mod rsa;
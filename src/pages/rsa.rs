@@ -30,6 +30,7 @@ mod imp {
     use crate::ui::text_view::UITextView;
     use crate::window::GCiphersRsWindow;
 
+    use encryption::Encoding;
     use encryption::rsa::*;
 
     use super::*;
@@ -82,7 +83,7 @@ mod imp {
                 .expect("Приведение не удалось")
                 .downcast_ref::<GCiphersRsWindow>()
                 .expect("Приведение не удалось");
-            let text = self.text_view.get().get_text().to_lowercase();
+            let text = self.text_view.get().get_text();
             let n = self.n.get().text().to_string().parse::<usize>();
             let e = self.e.get().text().to_string().parse::<usize>();
             let d = self.d.get().text().to_string().parse::<usize>();
@@ -120,7 +121,7 @@ mod imp {
                 Ok(p) => p,
                 Err(e) => { window.show_message(&e.to_string()); return; }
             };
-            let (e, d, n) = gen_keys(p, q);
+            let RsaKeyPair { e, d, n } = gen_keys(p, q);
             self.n.get().set_text(&n.to_string());
             self.e.get().set_text(&e.to_string());
             self.d.get().set_text(&d.to_string());
@@ -129,7 +130,8 @@ mod imp {
         #[template_callback]
         fn on_encrypt_click(&self, _button: &Button) {
             self.call_p(|window, text, e, n, _| {
-                match encrypt(&window.mask_text(text), n, e) {
+                let encoding = window.get_encoding().unwrap_or(Encoding::Decimal);
+                match encrypt_with_encoding(&window.mask_text(&text.to_lowercase()), n, e, encoding) {
                     Ok(res) => Some(res),
                     Err(e) => {
                         window.show_message(&e.to_string());
@@ -142,7 +144,8 @@ mod imp {
         #[template_callback]
         fn on_decrypt_click(&self, _button: &Button) {
             self.call_p(|window, text, _, n, d| {
-                match decrypt(text, n, d) {
+                let encoding = window.get_encoding().unwrap_or(Encoding::Decimal);
+                match decrypt_with_encoding(text, n, d, encoding) {
                     Ok(res) => Some(window.demask_text(&res)),
                     Err(e) => {
                         window.show_message(&e.to_string());
@@ -30,6 +30,8 @@ mod imp {
     use crate::ui::text_view::UITextView;
     use crate::window::GCiphersRsWindow;
 
+    use encryption::CipherError;
+    use encryption::rsa::RsaKeyPair;
     use encryption::rsa_sign::*;
 
     use super::*;
@@ -79,7 +81,7 @@ mod imp {
     impl GCiphersRsRSASign {
         fn call_p<T>(&self, action: T)
         where
-            T: Fn(&GCiphersRsWindow, &str, usize, usize, usize, usize, usize) -> Option<String>,
+            T: Fn(&GCiphersRsWindow, &str, usize, usize, usize, usize, &str) -> Option<String>,
         {
             let root = self.obj().root().expect("Не удалось получить окно");
             let window = root
@@ -92,13 +94,9 @@ mod imp {
             let e = self.e.get().text().to_string().parse::<usize>();
             let d = self.d.get().text().to_string().parse::<usize>();
             let modula = self.modula.get().text().to_string().parse::<usize>();
-            let s = if self.sign_val.get().text().is_empty() {
-                Ok(0)
-            } else {
-                self.sign_val.get().text().to_string().parse::<usize>()
-            };
+            let s = self.sign_val.get().text().to_string();
             let mut args = Vec::new();
-            for elem in [e, n, d, modula, s].iter() {
+            for elem in [e, n, d, modula].iter() {
                 match elem {
                     Ok(elem) => args.push(*elem),
                     Err(e) => {
@@ -107,7 +105,7 @@ mod imp {
                     }
                 }
             }
-            let result = action(window, &text, args[0], args[1], args[2], args[3], args[4]);
+            let result = action(window, &text, args[0], args[1], args[2], args[3], &s);
             if let Some(result) = result {
                 self.sign_val.get().set_text(&result);
             }
@@ -137,7 +135,7 @@ mod imp {
                     return;
                 }
             };
-            let (e, d, n) = gen_keys(p, q);
+            let RsaKeyPair { e, d, n } = gen_keys(p, q);
             self.n.get().set_text(&n.to_string());
             self.e.get().set_text(&e.to_string());
             self.d.get().set_text(&d.to_string());
@@ -147,7 +145,10 @@ mod imp {
         fn on_sign_click(&self, _button: &Button) {
             self.call_p(|window, text, _, n, d, modula, _| {
                 match sign(&window.mask_text(text), n, d, modula) {
-                    Ok(res) => Some(res.to_string()),
+                    Ok(res) => Some(match window.get_encoding() {
+                        Some(encoding) => encoding.encode_numbers(&[res], n),
+                        None => res.to_string(),
+                    }),
                     Err(e) => {
                         window.show_message(&e.to_string());
                         None
@@ -159,6 +160,20 @@ mod imp {
         #[template_callback]
         fn on_check_sign_click(&self, _button: &Button) {
             self.call_p(|window, text, e, n, _, modula, s| {
+                let s = match window.get_encoding() {
+                    Some(encoding) => encoding.decode_numbers(s, n).and_then(|numbers| match numbers[..] {
+                        [s] => Ok(s),
+                        _ => Err(CipherError::InvalidSignature),
+                    }),
+                    None => s.parse::<usize>().map_err(|_| CipherError::InvalidSignature),
+                };
+                let s = match s {
+                    Ok(s) => s,
+                    Err(e) => {
+                        window.show_message(&e.to_string());
+                        return None;
+                    }
+                };
                 match check_sign(&window.mask_text(text), n, e, s, modula) {
                     Ok(true) => {
                         window.show_message("Подпись верна");
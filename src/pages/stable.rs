@@ -29,8 +29,9 @@ mod imp {
     use crate::ui::text_view::UITextView;
     use crate::window::GCiphersRsWindow;
 
-    use encryption::magma::{t, t_reverse};
-    use encryption::methods::{bytes_to_hex, bytes_to_string, hex_to_bytes, str_to_bytes};
+    use encryption::Padding;
+    use encryption::magma::{t, t_reverse, SBox};
+    use encryption::methods::{bytes_to_hex, bytes_to_string, hex_to_bytes};
 
     use super::*;
 
@@ -38,7 +39,9 @@ mod imp {
     #[template(resource = "/com/github/sidecuter/gciphers_rs/stable.ui")]
     pub struct GCiphersRsStable {
         #[template_child]
-        pub text_view: TemplateChild<UITextView>
+        pub text_view: TemplateChild<UITextView>,
+        #[template_child]
+        pub sbox: TemplateChild<gtk::DropDown>
     }
 
     #[glib::object_subclass]
@@ -57,7 +60,13 @@ mod imp {
         }
     }
 
-    impl ObjectImpl for GCiphersRsStable {}
+    impl ObjectImpl for GCiphersRsStable {
+        fn constructed(&self) {
+            self.parent_constructed();
+            let names: Vec<&str> = SBox::NAMED.iter().map(|sbox| sbox.name()).collect();
+            self.sbox.set_model(Some(&gtk::StringList::new(&names)));
+        }
+    }
     impl WidgetImpl for GCiphersRsStable {}
     impl BinImpl for GCiphersRsStable {}
 
@@ -79,12 +88,17 @@ mod imp {
             }
         }
 
+        fn get_sbox(&self) -> SBox {
+            SBox::NAMED.get(self.sbox.selected() as usize).copied().unwrap_or_default()
+        }
+
         #[template_callback]
         fn on_encrypt_click(&self, _button: &Button) {
+            let sbox = self.get_sbox();
             self.call_p(|window, text| {
                 let mut result = String::new();
                 let text_r = if window.get_prettify_state() {
-                    str_to_bytes(text, 4)
+                    Padding::Pkcs7.pad(text.as_bytes(), 4)
                 } else {
                     hex_to_bytes(text, 4)
                 };
@@ -96,7 +110,7 @@ mod imp {
                     }
                 };
                 for text_slice in text.windows(4).step_by(4) {
-                    result.push_str(&bytes_to_hex(&t(text_slice)));
+                    result.push_str(&bytes_to_hex(&t(text_slice, &sbox)));
                 }
                 Some(result)
             })
@@ -104,6 +118,7 @@ mod imp {
 
         #[template_callback]
         fn on_decrypt_click(&self, _button: &Button) {
+            let sbox = self.get_sbox();
             self.call_p(|window, text| {
                 let text = match hex_to_bytes(text, 4) {
                     Ok(text) => text,
@@ -114,10 +129,10 @@ mod imp {
                 };
                 let mut buffer = Vec::new();
                 for text_slice in text.windows(4).step_by(4) {
-                    buffer.extend(t_reverse(text_slice).into_iter())
+                    buffer.extend(t_reverse(text_slice, &sbox).into_iter())
                 }
                 if window.get_prettify_state() {
-                    Some(match bytes_to_string(&buffer) {
+                    Some(match Padding::Pkcs7.unpad(&buffer, 4).and_then(|buffer| bytes_to_string(&buffer)) {
                         Ok(text) => text,
                         Err(e) => {
                             window.show_message(&e.to_string());
@@ -18,10 +18,13 @@
  * SPDX-License-Identifier: GPL-3.0-or-later
  */
 
+use std::fmt::Display;
 use adw::Bin;
 use gtk::prelude::*;
 use adw::subclass::prelude::*;
 use gtk::{gio, glib};
+use encryption::{CipherError, Encoding, Normalizer, Signature};
+use encryption::registry::{self, Operation};
 use crate::menu_entry::GCiphersMenuEntry;
 use crate::pages::a5_1::GCiphersRsA51;
 use crate::pages::a5_2::GCiphersRsA52;
@@ -33,6 +36,7 @@ use crate::pages::trithemium::GCiphersRsTrithemium;
 use crate::pages::belazo::GCiphersRsBelazo;
 use crate::pages::cardano::GCiphersRsCardano;
 use crate::pages::ctr_magma::GCiphersRsCtrMagma;
+use crate::pages::des::GCiphersRsDes;
 use crate::pages::diffi_hellman::GCiphersRsDiffi;
 use crate::pages::ecc::GCiphersRsECC;
 use crate::pages::egsa::GCiphersRsEgsa;
@@ -48,9 +52,13 @@ use crate::pages::rsa::GCiphersRsRSA;
 use crate::pages::rsa_sign::GCiphersRsRSASign;
 use crate::pages::shenon::GCiphersRsShenon;
 use crate::pages::stable::GCiphersRsStable;
+use crate::pages::triple_des::GCiphersRsTripleDes;
 use crate::pages::vetrical::GCiphersRsVertical;
 use crate::pages::vigenere::GCiphersRsVigenere;
 
+/// Blocks shown in the trace of an encryption, a longer trace is cut
+const TRACE_BLOCKS: usize = 16;
+
 mod imp {
     use std::cell::RefCell;
     use gtk::{template_callbacks, ToggleButton};
@@ -70,7 +78,9 @@ mod imp {
         #[template_child]
         pub list_rows: TemplateChild<gtk::ListBox>,
         #[template_child]
-        pub prettify: TemplateChild<gtk::Switch>
+        pub prettify: TemplateChild<gtk::Switch>,
+        #[template_child]
+        pub encoding: TemplateChild<gtk::DropDown>
     }
 
     #[glib::object_subclass]
@@ -96,6 +106,7 @@ mod imp {
             obj.setup_labels();
             obj.setup_pages();
             obj.setup_stack();
+            obj.setup_encodings();
             self.list_rows.get().select_row(self.list_rows.get().row_at_index(0).as_ref());
         }
     }
@@ -152,66 +163,16 @@ impl GCiphersRsWindow {
 
     fn setup_pages(&self) {
         let pages = gio::ListStore::new::<Bin>();
-        pages.append(&GCiphersRsAtbash::new());
-        pages.append(&GCiphersRsCaesar::new());
-        pages.append(&GCiphersRsPolybius::new());
-        pages.append(&GCiphersRsTrithemium::new());
-        pages.append(&GCiphersRsBelazo::new());
-        pages.append(&GCiphersRsVigenere::new());
-        pages.append(&GCiphersRsStable::new());
-        pages.append(&GCiphersRsMatrix::new());
-        pages.append(&GCiphersRsPlayfair::new());
-        pages.append(&GCiphersRsVertical::new());
-        pages.append(&GCiphersRsCardano::new());
-        pages.append(&GCiphersRsFeistel::new());
-        pages.append(&GCiphersRsShenon::new());
-        pages.append(&GCiphersRsCtrMagma::new());
-        pages.append(&GCiphersRsA51::new());
-        pages.append(&GCiphersRsA52::new());
-        pages.append(&GCiphersRsMagma::new());
-        pages.append(&GCiphersRsAES::new());
-        pages.append(&GCiphersRsKuznechik::new());
-        pages.append(&GCiphersRsRSA::new());
-        pages.append(&GCiphersRsElgamal::new());
-        pages.append(&GCiphersRsECC::new());
-        pages.append(&GCiphersRsRSASign::new());
-        pages.append(&GCiphersRsEgsa::new());
-        pages.append(&GCiphersRsGOST94::new());
-        pages.append(&GCiphersRsGOST2012::new());
-        pages.append(&GCiphersRsDiffi::new());
+        for info in registry::algorithms() {
+            pages.append(&Self::create_page(info.id));
+        }
         self.imp().pages.replace(Some(pages));
     }
 
     fn setup_labels(&self) {
-        let labels = vec![
-            String::from("Атбаш"),
-            String::from("Цезарь"),
-            String::from("Полибий"),
-            String::from("Тритемий"),
-            String::from("Белазо"),
-            String::from("Виженер"),
-            String::from("S таблица"),
-            String::from("Матричный"),
-            String::from("Плейфер"),
-            String::from("Вертикальный"),
-            String::from("Кардано"),
-            String::from("Сеть Фейстеля"),
-            String::from("Шеннон"),
-            String::from("CTR Магма"),
-            String::from("A5/1"),
-            String::from("A5/2"),
-            String::from("Магма"),
-            String::from("AES"),
-            String::from("Кузнечик"),
-            String::from("RSA"),
-            String::from("Elgamal"),
-            String::from("ECC"),
-            String::from("ЭЦП RSA"),
-            String::from("EGSA"),
-            String::from("ГОСТ Р34.10-94"),
-            String::from("ГОСТ Р34.10-2012"),
-            String::from("Диффи-Хеллман"),
-        ];
+        let labels = registry::algorithms().iter()
+            .map(|info| String::from(info.name))
+            .collect();
         self.imp().labels.replace(Some(labels));
         self.setup_rows();
     }
@@ -223,6 +184,41 @@ impl GCiphersRsWindow {
         }
     }
 
+    fn create_page(id: &str) -> Bin {
+        match id {
+            "atbash" => GCiphersRsAtbash::new().upcast(),
+            "caesar" => GCiphersRsCaesar::new().upcast(),
+            "polybius" => GCiphersRsPolybius::new().upcast(),
+            "trithemium" => GCiphersRsTrithemium::new().upcast(),
+            "belazo" => GCiphersRsBelazo::new().upcast(),
+            "vigenere" => GCiphersRsVigenere::new().upcast(),
+            "s_table" => GCiphersRsStable::new().upcast(),
+            "matrix" => GCiphersRsMatrix::new().upcast(),
+            "playfair" => GCiphersRsPlayfair::new().upcast(),
+            "vertical" => GCiphersRsVertical::new().upcast(),
+            "cardano" => GCiphersRsCardano::new().upcast(),
+            "feistel" => GCiphersRsFeistel::new().upcast(),
+            "shenon" => GCiphersRsShenon::new().upcast(),
+            "ctr_magma" => GCiphersRsCtrMagma::new().upcast(),
+            "a5_1" => GCiphersRsA51::new().upcast(),
+            "a5_2" => GCiphersRsA52::new().upcast(),
+            "magma" => GCiphersRsMagma::new().upcast(),
+            "des" => GCiphersRsDes::new().upcast(),
+            "triple_des" => GCiphersRsTripleDes::new().upcast(),
+            "aes" => GCiphersRsAES::new().upcast(),
+            "kuznechik" => GCiphersRsKuznechik::new().upcast(),
+            "rsa" => GCiphersRsRSA::new().upcast(),
+            "elgamal" => GCiphersRsElgamal::new().upcast(),
+            "ecc" => GCiphersRsECC::new().upcast(),
+            "rsa_sign" => GCiphersRsRSASign::new().upcast(),
+            "egsa" => GCiphersRsEgsa::new().upcast(),
+            "gost_r_34_10_94" => GCiphersRsGOST94::new().upcast(),
+            "gost_r_34_10_2012" => GCiphersRsGOST2012::new().upcast(),
+            "diffie_hellman" => GCiphersRsDiffi::new().upcast(),
+            id => panic!("Нет страницы для алгоритма {id}")
+        }
+    }
+
     fn setup_stack(&self) {
         let pages = self.pages();
         for page in pages.into_iter() {
@@ -238,27 +234,105 @@ impl GCiphersRsWindow {
         self.imp().prettify.state()
     }
 
+    fn setup_encodings(&self) {
+        let names: Vec<&str> = std::iter::once("По умолчанию")
+            .chain(Encoding::ALL.iter().map(|encoding| encoding.name()))
+            .collect();
+        self.imp().encoding.set_model(Some(&gtk::StringList::new(&names)));
+    }
+
+    /// Encoding chosen for the output, `None` keeps the default of the algorithm
+    pub fn get_encoding(&self) -> Option<Encoding> {
+        match self.imp().encoding.selected() {
+            0 | gtk::INVALID_LIST_POSITION => None,
+            index => Encoding::ALL.get(index as usize - 1).copied()
+        }
+    }
+
+    /// Converts the hex output of a byte-oriented algorithm to the chosen encoding
+    pub fn encode_output(&self, hex: &str) -> Result<String, CipherError> {
+        match self.get_encoding() {
+            Some(encoding) => Ok(encoding.encode(&Encoding::HexLower.decode(hex)?)),
+            None => Ok(hex.to_owned())
+        }
+    }
+
+    /// Converts text in the chosen encoding back to hex
+    pub fn decode_input(&self, text: &str) -> Result<String, CipherError> {
+        match self.get_encoding() {
+            Some(encoding) => Ok(Encoding::HexLower.encode(&encoding.decode(text)?)),
+            None => Ok(text.to_owned())
+        }
+    }
+
+    /// Writes a signature with components below `modulus` in the chosen encoding
+    pub fn encode_signature(&self, signature: &Signature, modulus: usize) -> String {
+        match self.get_encoding() {
+            Some(encoding) => signature.encode(encoding, modulus),
+            None => signature.to_string()
+        }
+    }
+
+    pub fn decode_signature(&self, text: &str, modulus: usize) -> Result<Signature, CipherError> {
+        match self.get_encoding() {
+            Some(encoding) => Signature::decode(text, encoding, modulus),
+            None => text.parse()
+        }
+    }
+
     pub fn mask_text(&self, text: &str) -> String {
-        let mut result = String::from(text);
-        result = result.replace('.', "тчк")
-            .replace(',', "зпт")
-            .replace('-', "тире");
-        if !self.get_prettify_state() {
-            result.replace(' ', "")
+        let normalizer = if self.get_prettify_state() {
+            Normalizer::russian()
         } else {
-            result.replace(' ', "прб")
-        }
+            Normalizer::russian().with_substitution(" ", "")
+        };
+        normalizer.normalize(text)
     }
 
     pub fn demask_text(&self, text: &str) -> String {
-        let result = String::from(text);
         if self.get_prettify_state() {
-            result.replace("тчк", ".")
-                .replace("зпт", ",")
-                .replace("тире", "-")
-                .replace("прб", " ")
+            Normalizer::russian().denormalize(text)
         } else {
-            result
+            String::from(text)
+        }
+    }
+
+    /// Shows the rounds of the first `TRACE_BLOCKS` blocks in `label` and returns the result
+    pub fn get_traced<T: Display>(
+        &self, res: Result<(String, Vec<T>), CipherError>, label: &gtk::Label
+    ) -> Option<String> {
+        let (result, traces) = match res {
+            Ok(result) => result,
+            Err(e) => {
+                self.show_message(&e.to_string());
+                return None;
+            }
+        };
+        let mut text: Vec<String> = traces.iter().take(TRACE_BLOCKS).enumerate()
+            .map(|(i, trace)| format!("Блок {}\n{trace}", i + 1))
+            .collect();
+        if traces.len() > TRACE_BLOCKS {
+            text.push(format!("Показаны первые {TRACE_BLOCKS} блоков из {}", traces.len()));
+        }
+        label.set_text(&text.join("\n"));
+        Some(result)
+    }
+
+    /// Removes the trace of a previous encryption
+    pub fn clear_trace(&self, label: &gtk::Label) {
+        label.set_text("Зашифруйте текст, чтобы увидеть промежуточные значения");
+    }
+
+    /// Shows every violation of the parameter schema of the algorithm
+    pub fn check_params(&self, id: &str, operation: Operation, args: &[&str]) -> bool {
+        let info = registry::find(id).expect("Алгоритм отсутствует в реестре");
+        match info.validate(operation, args) {
+            Ok(()) => true,
+            Err(errors) => {
+                let messages: Vec<String> = errors.iter().map(ToString::to_string).collect();
+                self.show_message(&messages.join("\n"));
+                false
+            }
         }
     }
 